@@ -7,9 +7,9 @@ use crate::hal::dma::{Descriptor, Transfer, TransferResources};
 use crate::hal::pac::dma::DMA;
 use core::fmt::Write;
 use core::pin::Pin;
-use hal::ccu::Clocks;
+use hal::ccu::Config;
 use hal::console_writeln;
-use hal::display::hdmi::HdmiDisplay;
+use hal::display::hdmi::{HdmiDisplay, HotplugEvent};
 use hal::pac::{
     ccu::CCU, de::DE, de_mixer::MIXER1, hdmi::HDMI, pio::PIO, tcon1::TCON1, uart0::UART0,
     uart_common::NotConfigured,
@@ -18,10 +18,9 @@ use hal::prelude::*;
 use hal::serial::Serial;
 
 fn kernel_entry() -> ! {
-    let clocks = Clocks::read();
-
     let ccu = unsafe { CCU::from_paddr() };
     let mut ccu = ccu.constrain();
+    let clocks = ccu.freeze(Config::new()).unwrap();
 
     let pio = unsafe { PIO::from_paddr() };
     let gpio = pio.split(&mut ccu);
@@ -74,7 +73,12 @@ fn kernel_entry() -> ! {
 
     console_writeln!(serial, "Creating the display");
 
-    let display = HdmiDisplay::new(tcon1, mixer1, de, hdmi, &frame_buffer_mem, &mut ccu);
+    let mut display =
+        HdmiDisplay::new(tcon1, mixer1, de, hdmi, &frame_buffer_mem, &mut ccu).unwrap();
+
+    console_writeln!(serial, "Waiting for a sink");
+
+    while display.poll_hotplug(&mut ccu).unwrap() != Some(HotplugEvent::Connected) {}
 
     console_writeln!(&mut serial, "EDID: {:#?}", display.edid());
 
@@ -5,9 +5,9 @@ extern crate pine64_hal as hal;
 
 use core::fmt::Write;
 use core::pin::Pin;
-use hal::ccu::Clocks;
+use hal::ccu::Config;
 use hal::console_writeln;
-use hal::display::hdmi::HdmiDisplay;
+use hal::display::hdmi::{HdmiDisplay, HotplugEvent};
 use hal::pac::de::DE;
 use hal::pac::de_mixer::MIXER1;
 use hal::pac::uart0::UART0;
@@ -17,10 +17,9 @@ use hal::prelude::*;
 use hal::serial::Serial;
 
 fn kernel_entry() -> ! {
-    let clocks = Clocks::read();
-
     let ccu = unsafe { CCU::from_paddr() };
     let mut ccu = ccu.constrain();
+    let clocks = ccu.freeze(Config::new()).unwrap();
 
     let pio = unsafe { PIO::from_paddr() };
     let gpio = pio.split(&mut ccu);
@@ -67,7 +66,12 @@ fn kernel_entry() -> ! {
 
     console_writeln!(serial, "Creating the display");
 
-    let display = HdmiDisplay::new(tcon1, mixer1, de, hdmi, &frame_buffer_mem, &mut ccu);
+    let mut display =
+        HdmiDisplay::new(tcon1, mixer1, de, hdmi, &frame_buffer_mem, &mut ccu).unwrap();
+
+    console_writeln!(serial, "Waiting for a sink");
+
+    while display.poll_hotplug(&mut ccu).unwrap() != Some(HotplugEvent::Connected) {}
 
     console_writeln!(&mut serial, "EDID: {:#?}", display.edid());
 
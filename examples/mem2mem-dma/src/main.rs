@@ -3,7 +3,7 @@
 
 extern crate pine64_hal as hal;
 
-use crate::hal::ccu::Clocks;
+use crate::hal::ccu::Config;
 use crate::hal::console_writeln;
 use crate::hal::dma::{Descriptor, Transfer, TransferResources};
 use crate::hal::pac::ccu::CCU;
@@ -17,10 +17,9 @@ use core::fmt::Write;
 use core::pin::Pin;
 
 fn kernel_entry() -> ! {
-    let clocks = Clocks::read();
-
     let ccu = unsafe { CCU::from_paddr() };
     let mut ccu = ccu.constrain();
+    let clocks = ccu.freeze(Config::new()).unwrap();
 
     let pio = unsafe { PIO::from_paddr() };
     let gpio = pio.split(&mut ccu);
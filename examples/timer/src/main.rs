@@ -4,7 +4,7 @@
 extern crate pine64_hal as hal;
 
 use core::fmt::Write;
-use hal::ccu::Clocks;
+use hal::ccu::Config;
 use hal::console_writeln;
 use hal::pac::{ccu::CCU, pio::PIO, timer::TIMER, uart0::UART0, uart_common::NotConfigured};
 use hal::prelude::*;
@@ -12,10 +12,9 @@ use hal::serial::Serial;
 use hal::timer::{ClockSource, Timer};
 
 fn kernel_entry() -> ! {
-    let clocks = Clocks::read();
-
     let ccu = unsafe { CCU::from_paddr() };
     let mut ccu = ccu.constrain();
+    let clocks = ccu.freeze(Config::new()).unwrap();
 
     let pio = unsafe { PIO::from_paddr() };
     let gpio = pio.split(&mut ccu);
@@ -3,7 +3,7 @@
 
 extern crate pine64_hal as hal;
 
-use crate::hal::ccu::Clocks;
+use crate::hal::ccu::Config;
 use crate::hal::console_writeln;
 use crate::hal::pac::ccu::CCU;
 use crate::hal::pac::pio::PIO;
@@ -14,10 +14,9 @@ use crate::hal::serial::Serial;
 use core::fmt::Write;
 
 fn kernel_entry() -> ! {
-    let clocks = Clocks::read();
-
     let ccu = unsafe { CCU::from_paddr() };
     let mut ccu = ccu.constrain();
+    let clocks = ccu.freeze(Config::new()).unwrap();
 
     let pio = unsafe { PIO::from_paddr() };
     let gpio = pio.split(&mut ccu);
@@ -1,7 +1,6 @@
 use core::fmt::Write;
 use core::intrinsics;
 use core::panic::PanicInfo;
-use pine64_hal::ccu::Clocks;
 use pine64_hal::pac::ccu::CCU;
 use pine64_hal::pac::pio::PIO;
 use pine64_hal::pac::uart0::UART0;
@@ -16,10 +15,9 @@ fn panic(info: &PanicInfo) -> ! {
         asm::nop();
     }
 
-    let clocks = Clocks::read();
-
     let ccu = unsafe { CCU::from_paddr() };
     let mut ccu = ccu.constrain();
+    let clocks = ccu.snapshot();
 
     let pio = unsafe { PIO::from_paddr() };
     let gpio = pio.split(&mut ccu);
@@ -0,0 +1,395 @@
+//! X-Powers AXP803 PMIC
+//!
+//! ```ignore
+//! let rsb = Rsb::new(r_rsb, (pl0, pl1), rsb::DEFAULT_FREQ, clocks, &mut prcm)?;
+//! let mut pmic = Axp803::new(rsb)?;
+//! pmic.set_voltage(Regulator::Dldo1, 3_300_000)?;
+//! pmic.enable(Regulator::Dldo1)?;
+//! ```
+
+use crate::rsb::{self, Pins, Rsb};
+use core::convert::Infallible;
+use cortex_a::asm;
+
+pub const HARDWARE_ADDRESS: u16 = 0x3A3;
+pub const RUNTIME_ADDRESS: u8 = 0x2D;
+
+// Registers
+const POWER_SOURCE_STATUS: u8 = 0x00;
+const CHARGER_STATUS: u8 = 0x01;
+const POWER_OFF_CONTROL: u8 = 0x32;
+const CHARGER_CONTROL1: u8 = 0x33;
+const IRQ_ENABLE1: u8 = 0x40;
+const IRQ_STATUS1: u8 = 0x48;
+const IRQ_BANKS: u8 = 6;
+const BATTERY_VOLTAGE_HIGH: u8 = 0x78;
+const BATTERY_VOLTAGE_LOW: u8 = 0x79;
+const BATTERY_PERCENTAGE: u8 = 0xB9;
+
+// IRQ bank 5, the power key
+const PEK_BANK: u8 = 4;
+const PEK_RISING_EDGE: u8 = 1 << 6;
+const PEK_FALLING_EDGE: u8 = 1 << 5;
+const PEK_SHORT_PRESS: u8 = 1 << 1;
+const PEK_LONG_PRESS: u8 = 1 << 0;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Error {
+    Rsb(rsb::Error),
+    /// Voltage outside of the regulator's range
+    InvalidVoltage,
+}
+
+impl From<rsb::Error> for Error {
+    fn from(e: rsb::Error) -> Self {
+        Error::Rsb(e)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Regulator {
+    Dcdc1,
+    Dcdc2,
+    Dcdc3,
+    Dcdc4,
+    Dcdc5,
+    Dcdc6,
+    Aldo1,
+    Aldo2,
+    Aldo3,
+    Dldo1,
+    Dldo2,
+    Dldo3,
+    Dldo4,
+    Eldo1,
+    Eldo2,
+    Eldo3,
+}
+
+/// Linear run of selectors
+struct Range {
+    min_uv: u32,
+    step_uv: u32,
+    /// Number of selectors
+    count: u8,
+}
+
+struct RegulatorSpec {
+    voltage_reg: u8,
+    voltage_mask: u8,
+    enable_reg: u8,
+    enable_bit: u8,
+    ranges: &'static [Range],
+}
+
+const fn range(min_uv: u32, step_uv: u32, count: u8) -> Range {
+    Range {
+        min_uv,
+        step_uv,
+        count,
+    }
+}
+
+const DCDC1_RANGES: [Range; 1] = [range(1_600_000, 100_000, 19)];
+const DCDC234_RANGES: [Range; 2] = [range(500_000, 10_000, 71), range(1_220_000, 20_000, 5)];
+const DCDC5_RANGES: [Range; 2] = [range(800_000, 10_000, 33), range(1_140_000, 20_000, 36)];
+const DCDC6_RANGES: [Range; 2] = [range(600_000, 10_000, 51), range(1_120_000, 20_000, 21)];
+const LDO_RANGES: [Range; 1] = [range(700_000, 100_000, 27)];
+const DLDO2_RANGES: [Range; 2] = [range(700_000, 100_000, 28), range(3_600_000, 200_000, 4)];
+const ELDO_RANGES: [Range; 1] = [range(700_000, 50_000, 25)];
+
+const fn spec(
+    voltage_reg: u8,
+    voltage_mask: u8,
+    enable_reg: u8,
+    enable_bit: u8,
+    ranges: &'static [Range],
+) -> RegulatorSpec {
+    RegulatorSpec {
+        voltage_reg,
+        voltage_mask,
+        enable_reg,
+        enable_bit,
+        ranges,
+    }
+}
+
+static DCDC1: RegulatorSpec = spec(0x20, 0x1F, 0x10, 0, &DCDC1_RANGES);
+static DCDC2: RegulatorSpec = spec(0x21, 0x7F, 0x10, 1, &DCDC234_RANGES);
+static DCDC3: RegulatorSpec = spec(0x22, 0x7F, 0x10, 2, &DCDC234_RANGES);
+static DCDC4: RegulatorSpec = spec(0x23, 0x7F, 0x10, 3, &DCDC234_RANGES);
+static DCDC5: RegulatorSpec = spec(0x24, 0x7F, 0x10, 4, &DCDC5_RANGES);
+static DCDC6: RegulatorSpec = spec(0x25, 0x7F, 0x10, 5, &DCDC6_RANGES);
+static ALDO1: RegulatorSpec = spec(0x28, 0x1F, 0x13, 5, &LDO_RANGES);
+static ALDO2: RegulatorSpec = spec(0x29, 0x1F, 0x13, 6, &LDO_RANGES);
+static ALDO3: RegulatorSpec = spec(0x2A, 0x1F, 0x13, 7, &LDO_RANGES);
+static DLDO1: RegulatorSpec = spec(0x15, 0x1F, 0x12, 3, &LDO_RANGES);
+static DLDO2: RegulatorSpec = spec(0x16, 0x1F, 0x12, 4, &DLDO2_RANGES);
+static DLDO3: RegulatorSpec = spec(0x17, 0x1F, 0x12, 5, &LDO_RANGES);
+static DLDO4: RegulatorSpec = spec(0x18, 0x1F, 0x12, 6, &LDO_RANGES);
+static ELDO1: RegulatorSpec = spec(0x19, 0x1F, 0x12, 0, &ELDO_RANGES);
+static ELDO2: RegulatorSpec = spec(0x1A, 0x1F, 0x12, 1, &ELDO_RANGES);
+static ELDO3: RegulatorSpec = spec(0x1B, 0x1F, 0x12, 2, &ELDO_RANGES);
+
+impl Regulator {
+    fn spec(self) -> &'static RegulatorSpec {
+        use Regulator::*;
+        match self {
+            Dcdc1 => &DCDC1,
+            Dcdc2 => &DCDC2,
+            Dcdc3 => &DCDC3,
+            Dcdc4 => &DCDC4,
+            Dcdc5 => &DCDC5,
+            Dcdc6 => &DCDC6,
+            Aldo1 => &ALDO1,
+            Aldo2 => &ALDO2,
+            Aldo3 => &ALDO3,
+            Dldo1 => &DLDO1,
+            Dldo2 => &DLDO2,
+            Dldo3 => &DLDO3,
+            Dldo4 => &DLDO4,
+            Eldo1 => &ELDO1,
+            Eldo2 => &ELDO2,
+            Eldo3 => &ELDO3,
+        }
+    }
+}
+
+impl RegulatorSpec {
+    /// Lowest selector at or above `microvolts`, and its voltage
+    fn selector(&self, microvolts: u32) -> Option<(u8, u32)> {
+        let mut base = 0;
+        for r in self.ranges {
+            let max_uv = r.min_uv + r.step_uv * u32::from(r.count - 1);
+            if microvolts <= max_uv {
+                let steps = (microvolts.saturating_sub(r.min_uv) + r.step_uv - 1) / r.step_uv;
+                return Some((base + steps as u8, r.min_uv + steps * r.step_uv));
+            }
+            base += r.count;
+        }
+        None
+    }
+
+    fn microvolts(&self, selector: u8) -> u32 {
+        let mut sel = selector;
+        for r in self.ranges {
+            if sel < r.count {
+                return r.min_uv + r.step_uv * u32::from(sel);
+            }
+            sel -= r.count;
+        }
+        // Selectors past the table saturate at the maximum
+        let last = &self.ranges[self.ranges.len() - 1];
+        last.min_uv + last.step_uv * u32::from(last.count - 1)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PowerStatus {
+    pub acin_present: bool,
+    pub vbus_present: bool,
+    pub battery_present: bool,
+    pub charging: bool,
+}
+
+/// Power key events since the last read
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PowerKeyEvents {
+    pub pressed: bool,
+    pub released: bool,
+    pub short_press: bool,
+    pub long_press: bool,
+}
+
+impl PowerKeyEvents {
+    pub fn any(&self) -> bool {
+        self.pressed || self.released || self.short_press || self.long_press
+    }
+}
+
+pub struct Axp803<PINS> {
+    rsb: Rsb<PINS>,
+}
+
+impl<PINS> Axp803<PINS>
+where
+    PINS: Pins,
+{
+    pub fn new(mut rsb: Rsb<PINS>) -> Result<Self, Error> {
+        rsb.set_runtime_address(HARDWARE_ADDRESS, RUNTIME_ADDRESS)?;
+        Ok(Axp803 { rsb })
+    }
+
+    pub fn free(self) -> Rsb<PINS> {
+        self.rsb
+    }
+
+    pub fn read(&mut self, reg: u8) -> Result<u8, Error> {
+        Ok(self.rsb.read_u8(RUNTIME_ADDRESS, reg)?)
+    }
+
+    pub fn write(&mut self, reg: u8, value: u8) -> Result<(), Error> {
+        Ok(self.rsb.write_u8(RUNTIME_ADDRESS, reg, value)?)
+    }
+
+    fn modify(&mut self, reg: u8, mask: u8, value: u8) -> Result<(), Error> {
+        Ok(self.rsb.modify_u8(RUNTIME_ADDRESS, reg, mask, value)?)
+    }
+
+    /// Set the lowest output voltage at or above `microvolts`, returns it
+    pub fn set_voltage(&mut self, regulator: Regulator, microvolts: u32) -> Result<u32, Error> {
+        let spec = regulator.spec();
+        let (selector, actual) = spec.selector(microvolts).ok_or(Error::InvalidVoltage)?;
+        self.modify(spec.voltage_reg, spec.voltage_mask, selector)?;
+        Ok(actual)
+    }
+
+    pub fn voltage(&mut self, regulator: Regulator) -> Result<u32, Error> {
+        let spec = regulator.spec();
+        let selector = self.read(spec.voltage_reg)? & spec.voltage_mask;
+        Ok(spec.microvolts(selector))
+    }
+
+    pub fn enable(&mut self, regulator: Regulator) -> Result<(), Error> {
+        let spec = regulator.spec();
+        let bit = 1 << spec.enable_bit;
+        self.modify(spec.enable_reg, bit, bit)
+    }
+
+    pub fn disable(&mut self, regulator: Regulator) -> Result<(), Error> {
+        let spec = regulator.spec();
+        self.modify(spec.enable_reg, 1 << spec.enable_bit, 0)
+    }
+
+    pub fn is_enabled(&mut self, regulator: Regulator) -> Result<bool, Error> {
+        let spec = regulator.spec();
+        Ok(self.read(spec.enable_reg)? & (1 << spec.enable_bit) != 0)
+    }
+
+    pub fn power_status(&mut self) -> Result<PowerStatus, Error> {
+        let source = self.read(POWER_SOURCE_STATUS)?;
+        let charger = self.read(CHARGER_STATUS)?;
+        Ok(PowerStatus {
+            acin_present: source & (1 << 7) != 0,
+            vbus_present: source & (1 << 5) != 0,
+            battery_present: charger & (1 << 5) != 0,
+            charging: charger & (1 << 6) != 0,
+        })
+    }
+
+    /// Battery voltage in millivolts, 1.1 mV per LSB
+    pub fn battery_voltage(&mut self) -> Result<u32, Error> {
+        let high = u32::from(self.read(BATTERY_VOLTAGE_HIGH)?);
+        let low = u32::from(self.read(BATTERY_VOLTAGE_LOW)? & 0x0F);
+        Ok(((high << 4) | low) * 11 / 10)
+    }
+
+    /// Fuel gauge estimate, `None` until the gauge has calibrated
+    pub fn battery_percentage(&mut self) -> Result<Option<u8>, Error> {
+        let val = self.read(BATTERY_PERCENTAGE)?;
+        if val & (1 << 7) != 0 {
+            Ok(Some(val & 0x7F))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn set_charging(&mut self, enable: bool) -> Result<(), Error> {
+        self.modify(CHARGER_CONTROL1, 1 << 7, if enable { 1 << 7 } else { 0 })
+    }
+
+    /// Route the power key events to the NMI line
+    pub fn enable_power_key_interrupts(&mut self) -> Result<(), Error> {
+        let bits = PEK_RISING_EDGE | PEK_FALLING_EDGE | PEK_SHORT_PRESS | PEK_LONG_PRESS;
+        self.write(IRQ_STATUS1 + PEK_BANK, bits)?;
+        self.modify(IRQ_ENABLE1 + PEK_BANK, bits, bits)
+    }
+
+    pub fn disable_power_key_interrupts(&mut self) -> Result<(), Error> {
+        let bits = PEK_RISING_EDGE | PEK_FALLING_EDGE | PEK_SHORT_PRESS | PEK_LONG_PRESS;
+        self.modify(IRQ_ENABLE1 + PEK_BANK, bits, 0)
+    }
+
+    /// Read and clear the pending power key events
+    pub fn power_key_events(&mut self) -> Result<PowerKeyEvents, Error> {
+        let status = self.read(IRQ_STATUS1 + PEK_BANK)?;
+        let bits = PEK_RISING_EDGE | PEK_FALLING_EDGE | PEK_SHORT_PRESS | PEK_LONG_PRESS;
+        // Write 1 to clear
+        self.write(IRQ_STATUS1 + PEK_BANK, status & bits)?;
+        Ok(PowerKeyEvents {
+            // The key pulls the line low
+            pressed: status & PEK_FALLING_EDGE != 0,
+            released: status & PEK_RISING_EDGE != 0,
+            short_press: status & PEK_SHORT_PRESS != 0,
+            long_press: status & PEK_LONG_PRESS != 0,
+        })
+    }
+
+    /// Mask and clear every interrupt, so nothing wakes the PMIC back up,
+    /// then cut all the rails
+    ///
+    /// Only returns if the PMIC couldn't be reached.
+    pub fn power_off(&mut self) -> Result<Infallible, Error> {
+        for bank in 0..IRQ_BANKS {
+            self.write(IRQ_ENABLE1 + bank, 0)?;
+            self.write(IRQ_STATUS1 + bank, 0xFF)?;
+        }
+        self.modify(POWER_OFF_CONTROL, 1 << 7, 1 << 7)?;
+        loop {
+            asm::wfi();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selector_rounds_up() {
+        let dcdc2 = Regulator::Dcdc2.spec();
+        assert_eq!(dcdc2.selector(500_000), Some((0, 500_000)));
+        assert_eq!(dcdc2.selector(1_100_000), Some((60, 1_100_000)));
+        assert_eq!(dcdc2.selector(1_105_000), Some((61, 1_110_000)));
+        assert_eq!(dcdc2.selector(1_200_000), Some((70, 1_200_000)));
+        // Below the minimum is raised to it
+        assert_eq!(dcdc2.selector(400_000), Some((0, 500_000)));
+    }
+
+    #[test]
+    fn selector_crosses_ranges() {
+        let dcdc2 = Regulator::Dcdc2.spec();
+        // Between the ranges, the second range's first selector
+        assert_eq!(dcdc2.selector(1_210_000), Some((71, 1_220_000)));
+        assert_eq!(dcdc2.selector(1_300_000), Some((75, 1_300_000)));
+        assert_eq!(dcdc2.selector(1_300_001), None);
+
+        let dldo2 = Regulator::Dldo2.spec();
+        assert_eq!(dldo2.selector(3_300_000), Some((26, 3_300_000)));
+        assert_eq!(dldo2.selector(3_500_000), Some((28, 3_600_000)));
+        assert_eq!(dldo2.selector(4_200_000), Some((31, 4_200_000)));
+    }
+
+    #[test]
+    fn selector_round_trip() {
+        let regulators = [
+            Regulator::Dcdc1,
+            Regulator::Dcdc2,
+            Regulator::Dcdc5,
+            Regulator::Dcdc6,
+            Regulator::Aldo1,
+            Regulator::Dldo2,
+            Regulator::Eldo1,
+        ];
+        for reg in regulators.iter() {
+            let spec = reg.spec();
+            let count: u8 = spec.ranges.iter().map(|r| r.count).sum();
+            for sel in 0..count {
+                let uv = spec.microvolts(sel);
+                assert_eq!(spec.selector(uv), Some((sel, uv)));
+            }
+            // Past the table saturates at the maximum
+            assert_eq!(spec.microvolts(count), spec.microvolts(count - 1));
+        }
+    }
+}
@@ -0,0 +1,242 @@
+//! Bus clock gating and reset
+//!
+//! ```ignore
+//! ccu.enable_and_reset::<UART2>();
+//! // ...
+//! ccu.power_down::<UART2>();
+//! ```
+//!
+//! Peripherals without a register block in the PAC get a marker type here.
+
+use super::Ccu;
+use crate::pac::ccu::{
+    BusClockGating0, BusClockGating1, BusClockGating2, BusClockGating3, BusSoftReset0,
+    BusSoftReset1, BusSoftReset2, BusSoftReset3, BusSoftReset4,
+};
+use crate::pac::{
+    de::DE, dma::DMA, hdmi::HDMI, hstimer::HSTIMER, i2s0::I2S0, i2s1::I2S1, i2s2::I2S2, pio::PIO,
+    spi0::SPI0, spi1::SPI1, tcon0::TCON0, tcon1::TCON1, twi0::TWI0, twi1::TWI1, twi2::TWI2,
+    uart0::UART0, uart1::UART1, uart2::UART2, uart3::UART3, uart4::UART4,
+};
+
+/// Bus clock gating
+pub trait Enable {
+    fn enable(ccu: &mut Ccu);
+
+    fn disable(ccu: &mut Ccu);
+
+    fn is_enabled(ccu: &mut Ccu) -> bool;
+}
+
+/// Bus soft reset
+pub trait Reset {
+    /// Hold the peripheral in reset
+    fn assert_reset(ccu: &mut Ccu);
+
+    /// Release the peripheral from reset
+    fn deassert_reset(ccu: &mut Ccu);
+
+    fn reset(ccu: &mut Ccu) {
+        Self::assert_reset(ccu);
+        Self::deassert_reset(ccu);
+    }
+}
+
+impl Ccu {
+    pub fn enable<P: Enable>(&mut self) {
+        P::enable(self);
+    }
+
+    pub fn disable<P: Enable>(&mut self) {
+        P::disable(self);
+    }
+
+    /// Pulse the peripheral's reset and ungate its bus clock
+    pub fn enable_and_reset<P: Enable + Reset>(&mut self) {
+        P::reset(self);
+        P::enable(self);
+    }
+
+    /// Gate the peripheral's bus clock and hold it in reset
+    pub fn power_down<P: Enable + Reset>(&mut self) {
+        P::disable(self);
+        P::assert_reset(self);
+    }
+}
+
+macro_rules! bus_enable {
+    ($($PER:ident: ($bcgX:ident, $BCGt:ident, $Field:ident),)+) => {
+        $(
+            impl Enable for $PER {
+                fn enable(ccu: &mut Ccu) {
+                    ccu.$bcgX.enr().modify($BCGt::$Field::Set);
+                }
+
+                fn disable(ccu: &mut Ccu) {
+                    ccu.$bcgX.enr().modify($BCGt::$Field::Clear);
+                }
+
+                fn is_enabled(ccu: &mut Ccu) -> bool {
+                    ccu.$bcgX.enr().is_set($BCGt::$Field::Read)
+                }
+            }
+        )+
+    }
+}
+
+macro_rules! bus_reset {
+    ($($PER:ident: ($bsrX:ident, $BSRt:ident, [$($Field:ident),+]),)+) => {
+        $(
+            impl Reset for $PER {
+                fn assert_reset(ccu: &mut Ccu) {
+                    $(
+                        ccu.$bsrX.rstr().modify($BSRt::$Field::Clear);
+                    )+
+                }
+
+                fn deassert_reset(ccu: &mut Ccu) {
+                    $(
+                        ccu.$bsrX.rstr().modify($BSRt::$Field::Set);
+                    )+
+                }
+            }
+        )+
+    }
+}
+
+macro_rules! markers {
+    ($($(#[$attr:meta])* $PER:ident,)+) => {
+        $(
+            $(#[$attr])*
+            #[allow(non_camel_case_types)]
+            pub struct $PER {
+                _0: (),
+            }
+        )+
+    }
+}
+
+markers! {
+    MIPI_DSI,
+    /// Crypto engine
+    CE,
+    MMC0,
+    MMC1,
+    MMC2,
+    NAND,
+    EMAC,
+    /// Transport stream
+    TS,
+    USB_OTG,
+    USB_EHCI0,
+    USB_EHCI1,
+    USB_OHCI0,
+    USB_OHCI1,
+    /// Video engine
+    VE,
+    DEINTERLACE,
+    CSI,
+    GPU,
+    MSGBOX,
+    SPINLOCK,
+    LVDS,
+    /// Audio codec
+    CODEC,
+    SPDIF,
+    /// Thermal sensor
+    THS,
+    /// Smart card reader
+    SCR,
+}
+
+bus_enable! {
+    MIPI_DSI: (bcg0, BusClockGating0, MipiDsi),
+    CE: (bcg0, BusClockGating0, Ce),
+    DMA: (bcg0, BusClockGating0, Dma),
+    MMC0: (bcg0, BusClockGating0, Mmc0),
+    MMC1: (bcg0, BusClockGating0, Mmc1),
+    MMC2: (bcg0, BusClockGating0, Mmc2),
+    NAND: (bcg0, BusClockGating0, Nand),
+    EMAC: (bcg0, BusClockGating0, Emac),
+    TS: (bcg0, BusClockGating0, Ts),
+    HSTIMER: (bcg0, BusClockGating0, HsTimer),
+    SPI0: (bcg0, BusClockGating0, Spi0),
+    SPI1: (bcg0, BusClockGating0, Spi1),
+    USB_OTG: (bcg0, BusClockGating0, UsbOtg),
+    USB_EHCI0: (bcg0, BusClockGating0, UsbEhci0),
+    USB_EHCI1: (bcg0, BusClockGating0, UsbEhci1),
+    USB_OHCI0: (bcg0, BusClockGating0, UsbOhci0),
+    USB_OHCI1: (bcg0, BusClockGating0, UsbOhci1),
+    VE: (bcg1, BusClockGating1, Ve),
+    TCON0: (bcg1, BusClockGating1, Tcon0),
+    TCON1: (bcg1, BusClockGating1, Tcon1),
+    DEINTERLACE: (bcg1, BusClockGating1, Deinterlace),
+    CSI: (bcg1, BusClockGating1, Csi),
+    HDMI: (bcg1, BusClockGating1, Hdmi),
+    DE: (bcg1, BusClockGating1, De),
+    GPU: (bcg1, BusClockGating1, Gpu),
+    MSGBOX: (bcg1, BusClockGating1, MsgBox),
+    SPINLOCK: (bcg1, BusClockGating1, SpinLock),
+    CODEC: (bcg2, BusClockGating2, Codec),
+    SPDIF: (bcg2, BusClockGating2, Spdif),
+    PIO: (bcg2, BusClockGating2, Pio),
+    THS: (bcg2, BusClockGating2, Ths),
+    I2S0: (bcg2, BusClockGating2, I2sPcm0),
+    I2S1: (bcg2, BusClockGating2, I2sPcm1),
+    I2S2: (bcg2, BusClockGating2, I2sPcm2),
+    TWI0: (bcg3, BusClockGating3, Twi0),
+    TWI1: (bcg3, BusClockGating3, Twi1),
+    TWI2: (bcg3, BusClockGating3, Twi2),
+    SCR: (bcg3, BusClockGating3, Scr),
+    UART0: (bcg3, BusClockGating3, Uart0),
+    UART1: (bcg3, BusClockGating3, Uart1),
+    UART2: (bcg3, BusClockGating3, Uart2),
+    UART3: (bcg3, BusClockGating3, Uart3),
+    UART4: (bcg3, BusClockGating3, Uart4),
+}
+
+bus_reset! {
+    MIPI_DSI: (bsr0, BusSoftReset0, [MipiDsi]),
+    CE: (bsr0, BusSoftReset0, [Ce]),
+    DMA: (bsr0, BusSoftReset0, [Dma]),
+    MMC0: (bsr0, BusSoftReset0, [Mmc0]),
+    MMC1: (bsr0, BusSoftReset0, [Mmc1]),
+    MMC2: (bsr0, BusSoftReset0, [Mmc2]),
+    NAND: (bsr0, BusSoftReset0, [Nand]),
+    EMAC: (bsr0, BusSoftReset0, [Emac]),
+    TS: (bsr0, BusSoftReset0, [Ts]),
+    HSTIMER: (bsr0, BusSoftReset0, [HsTimer]),
+    SPI0: (bsr0, BusSoftReset0, [Spi0]),
+    SPI1: (bsr0, BusSoftReset0, [Spi1]),
+    USB_OTG: (bsr0, BusSoftReset0, [UsbOtg]),
+    USB_EHCI0: (bsr0, BusSoftReset0, [UsbEhci0]),
+    USB_EHCI1: (bsr0, BusSoftReset0, [UsbEhci1]),
+    USB_OHCI0: (bsr0, BusSoftReset0, [UsbOhci0]),
+    USB_OHCI1: (bsr0, BusSoftReset0, [UsbOhci1]),
+    VE: (bsr1, BusSoftReset1, [Ve]),
+    TCON0: (bsr1, BusSoftReset1, [Tcon0]),
+    TCON1: (bsr1, BusSoftReset1, [Tcon1]),
+    DEINTERLACE: (bsr1, BusSoftReset1, [Deinterlace]),
+    CSI: (bsr1, BusSoftReset1, [Csi]),
+    HDMI: (bsr1, BusSoftReset1, [Hdmi1, Hdmi0]),
+    DE: (bsr1, BusSoftReset1, [De]),
+    GPU: (bsr1, BusSoftReset1, [Gpu]),
+    MSGBOX: (bsr1, BusSoftReset1, [MsgBox]),
+    SPINLOCK: (bsr1, BusSoftReset1, [SpinLock]),
+    LVDS: (bsr2, BusSoftReset2, [Lvds]),
+    CODEC: (bsr3, BusSoftReset3, [Codec]),
+    SPDIF: (bsr3, BusSoftReset3, [Spdif]),
+    THS: (bsr3, BusSoftReset3, [Ths]),
+    I2S0: (bsr3, BusSoftReset3, [I2sPcm0]),
+    I2S1: (bsr3, BusSoftReset3, [I2sPcm1]),
+    I2S2: (bsr3, BusSoftReset3, [I2sPcm2]),
+    TWI0: (bsr4, BusSoftReset4, [Twi0]),
+    TWI1: (bsr4, BusSoftReset4, [Twi1]),
+    TWI2: (bsr4, BusSoftReset4, [Twi2]),
+    SCR: (bsr4, BusSoftReset4, [Scr]),
+    UART0: (bsr4, BusSoftReset4, [Uart0]),
+    UART1: (bsr4, BusSoftReset4, [Uart1]),
+    UART2: (bsr4, BusSoftReset4, [Uart2]),
+    UART3: (bsr4, BusSoftReset4, [Uart3]),
+    UART4: (bsr4, BusSoftReset4, [Uart4]),
+}
@@ -0,0 +1,100 @@
+//! CPUX operating points
+//!
+//! The voltages are the VDD-CPUX supply (DCDC2 on the AXP803) needed at
+//! each frequency. The supply must be raised before scaling up and may
+//! only be lowered after scaling down.
+
+use embedded_time::rate::Hertz;
+
+/// AXI is limited to 450 MHz
+pub const MAX_AXI_FREQ: Hertz = Hertz(450_000_000);
+
+/// CPUX APB/ATB is limited to 300 MHz
+pub const MAX_APB_FREQ: Hertz = Hertz(300_000_000);
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct OperatingPoint {
+    pub frequency: Hertz,
+    pub microvolts: u32,
+}
+
+impl OperatingPoint {
+    pub const fn new(frequency: Hertz, microvolts: u32) -> Self {
+        OperatingPoint {
+            frequency,
+            microvolts,
+        }
+    }
+
+    /// Slowest operating point running at least `freq`
+    pub fn for_frequency(freq: Hertz) -> Option<&'static OperatingPoint> {
+        OPERATING_POINTS.iter().find(|op| op.frequency.0 >= freq.0)
+    }
+
+    pub fn min() -> &'static OperatingPoint {
+        &OPERATING_POINTS[0]
+    }
+
+    pub fn max() -> &'static OperatingPoint {
+        &OPERATING_POINTS[OPERATING_POINTS.len() - 1]
+    }
+}
+
+/// Operating points, slowest first
+pub const OPERATING_POINTS: [OperatingPoint; 8] = [
+    OperatingPoint::new(Hertz(648_000_000), 1_040_000),
+    OperatingPoint::new(Hertz(816_000_000), 1_100_000),
+    OperatingPoint::new(Hertz(912_000_000), 1_120_000),
+    OperatingPoint::new(Hertz(960_000_000), 1_160_000),
+    OperatingPoint::new(Hertz(1_008_000_000), 1_200_000),
+    OperatingPoint::new(Hertz(1_056_000_000), 1_240_000),
+    OperatingPoint::new(Hertz(1_104_000_000), 1_260_000),
+    OperatingPoint::new(Hertz(1_152_000_000), 1_300_000),
+];
+
+/// Smallest AXI divider (1..=4) keeping AXI within its limit
+pub fn axi_divider(cpu: Hertz) -> u32 {
+    let div = (cpu.0 + MAX_AXI_FREQ.0 - 1) / MAX_AXI_FREQ.0;
+    div.clamp(1, 4)
+}
+
+/// Smallest APB divider (1, 2 or 4) keeping CPUX APB within its limit
+pub fn apb_divider(cpu: Hertz) -> u32 {
+    [1, 2, 4]
+        .iter()
+        .copied()
+        .find(|div| cpu.0 / div <= MAX_APB_FREQ.0)
+        .unwrap_or(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn axi_divider_limits() {
+        assert_eq!(axi_divider(Hertz(24_000_000)), 1);
+        assert_eq!(axi_divider(Hertz(450_000_000)), 1);
+        assert_eq!(axi_divider(Hertz(450_000_001)), 2);
+        assert_eq!(axi_divider(Hertz(900_000_000)), 2);
+        assert_eq!(axi_divider(Hertz(1_152_000_000)), 3);
+        assert_eq!(axi_divider(Hertz(2_000_000_000)), 4);
+        assert_eq!(axi_divider(Hertz(0)), 1);
+    }
+
+    #[test]
+    fn axi_within_limit_at_every_operating_point() {
+        for op in OPERATING_POINTS.iter() {
+            let div = axi_divider(op.frequency);
+            assert!(op.frequency.0 / div <= MAX_AXI_FREQ.0);
+            assert!(div == 1 || op.frequency.0 / (div - 1) > MAX_AXI_FREQ.0);
+        }
+    }
+
+    #[test]
+    fn apb_divider_limits() {
+        assert_eq!(apb_divider(Hertz(300_000_000)), 1);
+        assert_eq!(apb_divider(Hertz(648_000_000)), 4);
+        assert_eq!(apb_divider(Hertz(600_000_000)), 2);
+    }
+}
@@ -0,0 +1,991 @@
+//! CCU
+//!
+//! The PLL factor math lives in [`pll`], the module clock muxes and
+//! dividers in [`module_clock`]. This module does the register I/O.
+
+use crate::pac::ccu::{
+    Ahb1Apb1Config, Ahb2Config, Apb2Config, BusClockGating0, BusClockGating1, BusClockGating2,
+    BusClockGating3, BusSoftReset0, BusSoftReset1, BusSoftReset2, BusSoftReset3, BusSoftReset4,
+    CpuXAxiConfig, CCU,
+};
+use crate::pac::r_prcm::{Apb0Config, CpusConfig, RPRCM};
+use cortex_a::asm;
+use embedded_time::rate::Hertz;
+
+pub mod bus;
+pub mod cpu;
+pub mod module_clock;
+pub mod pll;
+
+pub use bus::{Enable, Reset};
+pub use cpu::OperatingPoint;
+pub use module_clock::{ClockSource, ModuleClock};
+pub use pll::{Pll, PllFactors};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Error {
+    /// A factor or divider value can't be encoded in its register field
+    InvalidFactors,
+    /// No factors produce a rate within the requested tolerance
+    NoSolution,
+    /// The module clock can't be sourced from the requested parent
+    InvalidSource,
+    /// The frequency is outside of the supported operating points
+    InvalidFrequency,
+    /// The PLL did not report lock in time
+    LockTimeout(Pll),
+    /// The clock feeds the frozen `Clocks`, or `Ccu::freeze` was already
+    /// called
+    Frozen,
+}
+
+/// Busy-wait iterations before giving up on a PLL lock or factor update
+const PLL_LOCK_TIMEOUT: u32 = 0x10_0000;
+
+/// Requested clock configuration, anything left unset is kept as the
+/// boot loader configured it
+///
+/// ```ignore
+/// let clocks = ccu.freeze(Config::new().cpu(816.MHz().into()).apb2(24.MHz().into()))?;
+/// ```
+#[derive(Debug, Copy, Clone, Default, PartialEq, PartialOrd)]
+pub struct Config {
+    cpu: Option<Hertz>,
+    pll_periph0: Option<Hertz>,
+    ahb1: Option<Hertz>,
+    apb2: Option<Hertz>,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Config::default()
+    }
+
+    /// CPUX frequency, must be an operating point frequency
+    pub fn cpu(mut self, freq: Hertz) -> Self {
+        self.cpu = Some(freq);
+        self
+    }
+
+    /// PLL_PERIPH0(1X) frequency, a multiple of 12 MHz
+    ///
+    /// AHB1 and APB2 are derived from it, so it must be running if either
+    /// is set. The boot loader normally leaves it at 600 MHz.
+    pub fn pll_periph0(mut self, freq: Hertz) -> Self {
+        self.pll_periph0 = Some(freq);
+        self
+    }
+
+    /// Highest AHB1 frequency, sourced from PLL_PERIPH0(1X)
+    pub fn ahb1(mut self, freq: Hertz) -> Self {
+        self.ahb1 = Some(freq);
+        self
+    }
+
+    /// Highest APB2 frequency, sourced from OSC24M or PLL_PERIPH0(2X)
+    pub fn apb2(mut self, freq: Hertz) -> Self {
+        self.apb2 = Some(freq);
+        self
+    }
+}
+
+pub trait CcuExt {
+    fn constrain(self) -> Ccu;
+}
+
+impl CcuExt for CCU {
+    fn constrain(self) -> Ccu {
+        Ccu {
+            bcg0: BCG0 { _0: () },
+            bcg1: BCG1 { _0: () },
+            bcg2: BCG2 { _0: () },
+            bcg3: BCG3 { _0: () },
+            bsr0: BSR0 { _0: () },
+            bsr1: BSR1 { _0: () },
+            bsr2: BSR2 { _0: () },
+            bsr3: BSR3 { _0: () },
+            bsr4: BSR4 { _0: () },
+            frozen: false,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct Clocks {
+    pll_periph0_1x: Hertz,
+    pll_periph0_2x: Hertz,
+    cpu: Hertz,
+    axi: Hertz,
+    ahb1: Hertz,
+    ahb2: Hertz,
+    apb1: Hertz,
+    apb2: Hertz,
+    apb0: Hertz,
+}
+
+impl Clocks {
+    pub const OSC_24M_FREQ: Hertz = Hertz(24_000_000);
+    pub const OSC_32K_FREQ: Hertz = Hertz(32_768);
+    /// Internal RC oscillator, nominal
+    pub const OSC_I16M_FREQ: Hertz = Hertz(16_000_000);
+
+    /// Only `Ccu::freeze` and `Ccu::snapshot` read the clock tree
+    fn read() -> Self {
+        let ccu = unsafe { &*CCU::ptr() };
+
+        // PLL_PERIPH0(1X) = 24MHz * N * K/2
+        let pll_periph0_1x = pll_rate(Pll::Periph0);
+
+        // PLL_PERIPH0(2X) = 24MHz * N * K
+        let pll_periph0_2x = Hertz::new(pll_periph0_1x.0 * 2);
+
+        // CPUX
+        let cpu_clk_src = ccu
+            .cpu_axi_cfg
+            .get_field(CpuXAxiConfig::ClockSrcSel::Read)
+            .unwrap();
+        let cpu_clk = if cpu_clk_src == CpuXAxiConfig::ClockSrcSel::LOsc {
+            Self::OSC_32K_FREQ.0
+        } else if cpu_clk_src == CpuXAxiConfig::ClockSrcSel::Osc24M {
+            Self::OSC_24M_FREQ.0
+        } else {
+            pll_rate(Pll::CpuX).0
+        };
+
+        // AXI
+        let axi_div = 1 + ccu
+            .cpu_axi_cfg
+            .get_field(CpuXAxiConfig::AxiClockDivRatio::Read)
+            .unwrap()
+            .val();
+        let axi_clk = cpu_clk / axi_div;
+
+        // AHB1
+        let ahb1_pre_div = 1 + ccu
+            .ahb1_apb1_cfg
+            .get_field(Ahb1Apb1Config::Ahb1PreDiv::Read)
+            .unwrap()
+            .val();
+        let ahb1_clk_src = ccu
+            .ahb1_apb1_cfg
+            .get_field(Ahb1Apb1Config::Ahb1ClockSrcSel::Read)
+            .unwrap();
+        let ahb1_src_clk = if ahb1_clk_src == Ahb1Apb1Config::Ahb1ClockSrcSel::LOsc {
+            Self::OSC_32K_FREQ.0
+        } else if ahb1_clk_src == Ahb1Apb1Config::Ahb1ClockSrcSel::Osc24M {
+            Self::OSC_24M_FREQ.0
+        } else if ahb1_clk_src == Ahb1Apb1Config::Ahb1ClockSrcSel::Axi {
+            axi_clk
+        } else {
+            // Pre-divider only applies to PLL_PERIPH0(1X)
+            pll_periph0_1x.0 / ahb1_pre_div
+        };
+        let ahb1_div = 1
+            << ccu
+                .ahb1_apb1_cfg
+                .get_field(Ahb1Apb1Config::Ahb1ClockDivRatio::Read)
+                .unwrap()
+                .val();
+        let ahb1_clk = ahb1_src_clk / ahb1_div;
+
+        // AHB2
+        let ahb2_clk_src = ccu
+            .ahb2_cfg
+            .get_field(Ahb2Config::ClockConfig::Read)
+            .unwrap();
+        let ahb2_clk = if ahb2_clk_src == Ahb2Config::ClockConfig::PllPeriph01xD2 {
+            pll_periph0_1x.0 / 2
+        } else {
+            ahb1_clk
+        };
+
+        // APB1, a ratio of 0 also divides by 2
+        let apb1_clk_ratio = ccu
+            .ahb1_apb1_cfg
+            .get_field(Ahb1Apb1Config::Apb1ClockDivRatio::Read)
+            .unwrap();
+        let apb1_clk = if apb1_clk_ratio == Ahb1Apb1Config::Apb1ClockDivRatio::Divide4 {
+            ahb1_clk / 4
+        } else if apb1_clk_ratio == Ahb1Apb1Config::Apb1ClockDivRatio::Divide8 {
+            ahb1_clk / 8
+        } else {
+            ahb1_clk / 2
+        };
+
+        // APB2 = source / M / N
+        let apb2_clk_src = ccu
+            .apb2_cfg
+            .get_field(Apb2Config::ClockSrcSel::Read)
+            .unwrap();
+        let apb2_src_clk = if apb2_clk_src == Apb2Config::ClockSrcSel::LOsc {
+            Self::OSC_32K_FREQ.0
+        } else if apb2_clk_src == Apb2Config::ClockSrcSel::Osc24M {
+            Self::OSC_24M_FREQ.0
+        } else {
+            pll_periph0_2x.0
+        };
+        let apb2_m = 1 + ccu
+            .apb2_cfg
+            .get_field(Apb2Config::RatioM::Read)
+            .unwrap()
+            .val();
+        let apb2_n = 1
+            << ccu
+                .apb2_cfg
+                .get_field(Apb2Config::RatioN::Read)
+                .unwrap()
+                .val();
+        let apb2_clk = apb2_src_clk / apb2_m / apb2_n;
+
+        // CPUS (AHB0) and APB0, in the R_PRCM
+        let prcm = unsafe { &*RPRCM::ptr() };
+        let cpus_clk_src = prcm
+            .cpus_cfg
+            .get_field(CpusConfig::ClockSrcSel::Read)
+            .unwrap();
+        let cpus_src_clk = if cpus_clk_src == CpusConfig::ClockSrcSel::LOsc {
+            Self::OSC_32K_FREQ.0
+        } else if cpus_clk_src == CpusConfig::ClockSrcSel::Osc24M {
+            Self::OSC_24M_FREQ.0
+        } else if cpus_clk_src == CpusConfig::ClockSrcSel::PllPeriph0 {
+            // Pre-divider only applies to PLL_PERIPH0(1X)
+            let pre_div = 1 + prcm
+                .cpus_cfg
+                .get_field(CpusConfig::PllPeriph0PreDiv::Read)
+                .unwrap()
+                .val();
+            pll_periph0_1x.0 / pre_div
+        } else {
+            Self::OSC_I16M_FREQ.0
+        };
+        let cpus_div = 1
+            << prcm
+                .cpus_cfg
+                .get_field(CpusConfig::ClockDivRatio::Read)
+                .unwrap()
+                .val();
+        let apb0_div = 1
+            << prcm
+                .apb0_cfg
+                .get_field(Apb0Config::ClockDivRatio::Read)
+                .unwrap()
+                .val();
+        let apb0_clk = cpus_src_clk / cpus_div / apb0_div;
+
+        Clocks {
+            pll_periph0_1x,
+            pll_periph0_2x,
+            cpu: Hertz::new(cpu_clk),
+            axi: Hertz::new(axi_clk),
+            ahb1: Hertz::new(ahb1_clk),
+            ahb2: Hertz::new(ahb2_clk),
+            apb1: Hertz::new(apb1_clk),
+            apb2: Hertz::new(apb2_clk),
+            apb0: Hertz::new(apb0_clk),
+        }
+    }
+
+    pub fn pll_periph0_1x(&self) -> Hertz {
+        self.pll_periph0_1x
+    }
+
+    pub fn pll_periph0_2x(&self) -> Hertz {
+        self.pll_periph0_2x
+    }
+
+    pub fn cpu(&self) -> Hertz {
+        self.cpu
+    }
+
+    pub fn axi(&self) -> Hertz {
+        self.axi
+    }
+
+    pub fn ahb1(&self) -> Hertz {
+        self.ahb1
+    }
+
+    pub fn ahb2(&self) -> Hertz {
+        self.ahb2
+    }
+
+    pub fn apb1(&self) -> Hertz {
+        self.apb1
+    }
+
+    pub fn apb2(&self) -> Hertz {
+        self.apb2
+    }
+
+    /// APB0 of the R_ peripherals, see `prcm`
+    pub fn apb0(&self) -> Hertz {
+        self.apb0
+    }
+}
+
+/// The clock tree as read back by `Ccu::snapshot`, for diagnostics
+///
+/// Nothing validated these rates, so the drivers don't accept it in place
+/// of `Clocks`. The UARTs do, to print a panic message.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct ClockSnapshot {
+    pub cpu: Hertz,
+    pub axi: Hertz,
+    pub ahb1: Hertz,
+    pub ahb2: Hertz,
+    pub apb1: Hertz,
+    pub apb2: Hertz,
+    pub apb0: Hertz,
+}
+
+impl From<Clocks> for ClockSnapshot {
+    fn from(clocks: Clocks) -> Self {
+        ClockSnapshot {
+            cpu: clocks.cpu,
+            axi: clocks.axi,
+            ahb1: clocks.ahb1,
+            ahb2: clocks.ahb2,
+            apb1: clocks.apb1,
+            apb2: clocks.apb2,
+            apb0: clocks.apb0,
+        }
+    }
+}
+
+pub struct Ccu {
+    pub bcg0: BCG0,
+    pub bcg1: BCG1,
+    pub bcg2: BCG2,
+    pub bcg3: BCG3,
+    // bsr0: AHB1 Reset 0
+    // bsr1: AHB1 Reset 1
+    // bsr2: AHB1 Reset 2
+    // bsr3: APB1 Reset
+    // bsr4: APB2 Reset
+    pub bsr0: BSR0,
+    pub bsr1: BSR1,
+    pub bsr2: BSR2,
+    pub bsr3: BSR3,
+    pub bsr4: BSR4,
+    frozen: bool,
+}
+
+// TODO - rename the wrappers
+// - BSR4 -> APB2
+
+pub struct BCG0 {
+    _0: (),
+}
+
+impl BCG0 {
+    pub(crate) fn enr(&mut self) -> &mut BusClockGating0::Register {
+        unsafe { &mut (*CCU::mut_ptr()).bcg0 }
+    }
+}
+
+pub struct BCG1 {
+    _0: (),
+}
+
+impl BCG1 {
+    pub(crate) fn enr(&mut self) -> &mut BusClockGating1::Register {
+        unsafe { &mut (*CCU::mut_ptr()).bcg1 }
+    }
+}
+
+pub struct BCG2 {
+    _0: (),
+}
+
+impl BCG2 {
+    pub(crate) fn enr(&mut self) -> &mut BusClockGating2::Register {
+        unsafe { &mut (*CCU::mut_ptr()).bcg2 }
+    }
+}
+
+pub struct BCG3 {
+    _0: (),
+}
+
+impl BCG3 {
+    pub(crate) fn enr(&mut self) -> &mut BusClockGating3::Register {
+        unsafe { &mut (*CCU::mut_ptr()).bcg3 }
+    }
+}
+
+pub struct BSR0 {
+    _0: (),
+}
+
+impl BSR0 {
+    pub(crate) fn rstr(&mut self) -> &mut BusSoftReset0::Register {
+        unsafe { &mut (*CCU::mut_ptr()).bsr0 }
+    }
+}
+
+pub struct BSR1 {
+    _0: (),
+}
+
+impl BSR1 {
+    pub(crate) fn rstr(&mut self) -> &mut BusSoftReset1::Register {
+        unsafe { &mut (*CCU::mut_ptr()).bsr1 }
+    }
+}
+
+pub struct BSR2 {
+    _0: (),
+}
+
+impl BSR2 {
+    pub(crate) fn rstr(&mut self) -> &mut BusSoftReset2::Register {
+        unsafe { &mut (*CCU::mut_ptr()).bsr2 }
+    }
+}
+
+pub struct BSR3 {
+    _0: (),
+}
+
+impl BSR3 {
+    pub(crate) fn rstr(&mut self) -> &mut BusSoftReset3::Register {
+        unsafe { &mut (*CCU::mut_ptr()).bsr3 }
+    }
+}
+
+pub struct BSR4 {
+    _0: (),
+}
+
+impl BSR4 {
+    pub(crate) fn rstr(&mut self) -> &mut BusSoftReset4::Register {
+        unsafe { &mut (*CCU::mut_ptr()).bsr4 }
+    }
+}
+
+macro_rules! clock_registers {
+    ($read:ident, $write:ident, $clk:ident, [$($var:ident => $reg:ident,)+]) => {
+        fn $read(clk: $clk) -> u32 {
+            let ccu = unsafe { &*CCU::ptr() };
+            match clk {
+                $($clk::$var => ccu.$reg.read(),)+
+            }
+        }
+
+        fn $write(clk: $clk, val: u32) {
+            let ccu = unsafe { &mut *CCU::mut_ptr() };
+            match clk {
+                $($clk::$var => ccu.$reg.write(val),)+
+            }
+        }
+    };
+}
+
+clock_registers!(
+    pll_read,
+    pll_write,
+    Pll,
+    [
+        CpuX => pll_cpu_ctrl,
+        Audio => pll_audio,
+        Video0 => pll_video0,
+        Ve => pll_ve,
+        Ddr0 => pll_ddr0,
+        Periph0 => pll_periph0,
+        Periph1 => pll_periph1,
+        Video1 => pll_video1,
+        Gpu => pll_gpu,
+        Mipi => pll_mipi,
+        Hsic => pll_hsic,
+        De => pll_de,
+        Ddr1 => pll_ddr1,
+    ]
+);
+
+clock_registers!(
+    module_clock_read,
+    module_clock_write,
+    ModuleClock,
+    [
+        Ths => ths_clk_cfg,
+        Nand => nand_clk_cfg,
+        Mmc0 => mmc0_clk_cfg,
+        Mmc1 => mmc1_clk_cfg,
+        Mmc2 => mmc2_clk_cfg,
+        Ts => ts_clk_cfg,
+        Ce => ce_clk_cfg,
+        Spi0 => spi0_clk_cfg,
+        Spi1 => spi1_clk_cfg,
+        I2sPcm0 => i2s_pcm0_clk_cfg,
+        I2sPcm1 => i2s_pcm1_clk_cfg,
+        I2sPcm2 => i2s_pcm2_clk_cfg,
+        Spdif => spdif_clk_cfg,
+        De => de_clk_cfg,
+        Tcon0 => tcon0_clk_cfg,
+        Tcon1 => tcon1_clk_cfg,
+        Deinterlace => deinterlace_clk_cfg,
+        CsiMisc => csi_misc_clk_cfg,
+        CsiMaster => csi_clk_cfg,
+        CsiSpecial => csi_clk_cfg,
+        Ve => ve_clk_cfg,
+        AcDigital => ac_dig_clk_cfg,
+        Avs => avs_clk_cfg,
+        Hdmi => hdmi_clk_cfg,
+        HdmiDdc => hdmi_slow_clk_cfg,
+        MBus => mbus_clk_cfg,
+        MipiDsi => mipi_dsi_clk_cfg,
+        Gpu => gpu_clk_cfg,
+    ]
+);
+
+/// PLL_CPUX steps are 24 MHz, or 12 MHz with M = 2
+const CPU_FREQ_TOLERANCE: u32 = 12_000_000;
+
+/// A few cycles for the CPUX clock mux and dividers to settle
+fn cpu_clock_settle() {
+    for _ in 0..16 {
+        asm::nop();
+    }
+}
+
+fn wait_for_lock(pll: Pll) -> Result<(), Error> {
+    let mut timeout = PLL_LOCK_TIMEOUT;
+    while pll_read(pll) & (1 << pll::LOCK_BIT) == 0 {
+        if timeout == 0 {
+            return Err(Error::LockTimeout(pll));
+        }
+        timeout -= 1;
+        asm::nop();
+    }
+    Ok(())
+}
+
+fn pll_parent_rate(pll: Pll) -> Hertz {
+    match pll.parent() {
+        Some(parent) => pll_rate(parent),
+        None => Clocks::OSC_24M_FREQ,
+    }
+}
+
+fn pll_rate(pll: Pll) -> Hertz {
+    let reg = pll_read(pll);
+    if reg & (1 << pll::ENABLE_BIT) == 0 {
+        return Hertz::new(0);
+    }
+    let spec = pll.spec();
+    spec.rate(pll_parent_rate(pll), &spec.decode(reg))
+}
+
+fn clock_source_rate(src: ClockSource) -> Hertz {
+    match src {
+        ClockSource::LOsc => Clocks::OSC_32K_FREQ,
+        ClockSource::Osc24M => Clocks::OSC_24M_FREQ,
+        ClockSource::PllAudio
+        | ClockSource::PllAudio2x
+        | ClockSource::PllAudio4x
+        | ClockSource::PllAudio8x => {
+            let reg = pll_read(Pll::Audio);
+            if reg & (1 << pll::ENABLE_BIT) == 0 {
+                return Hertz::new(0);
+            }
+            let f = Pll::Audio.spec().decode(reg);
+            // PLL_AUDIO(8X) = 24MHz * N * 2 / M, the P divider only
+            // applies to PLL_AUDIO(1X)
+            let audio_8x = Clocks::OSC_24M_FREQ.0 as u64 * f.n as u64 * 2 / f.m as u64;
+            let rate = match src {
+                ClockSource::PllAudio8x => audio_8x,
+                ClockSource::PllAudio4x => audio_8x / 2,
+                ClockSource::PllAudio2x => audio_8x / 4,
+                _ => audio_8x / (2 * f.p as u64),
+            };
+            Hertz::new(rate as u32)
+        }
+        ClockSource::PllVideo0 => pll_rate(Pll::Video0),
+        ClockSource::PllVideo0x2 => Hertz::new(pll_rate(Pll::Video0).0 * 2),
+        ClockSource::PllVe => pll_rate(Pll::Ve),
+        ClockSource::PllDdr0 => pll_rate(Pll::Ddr0),
+        ClockSource::PllPeriph0 => pll_rate(Pll::Periph0),
+        ClockSource::PllPeriph0x2 => Hertz::new(pll_rate(Pll::Periph0).0 * 2),
+        ClockSource::PllPeriph1 => pll_rate(Pll::Periph1),
+        ClockSource::PllPeriph1x2 => Hertz::new(pll_rate(Pll::Periph1).0 * 2),
+        ClockSource::PllVideo1 => pll_rate(Pll::Video1),
+        ClockSource::PllVideo1x2 => Hertz::new(pll_rate(Pll::Video1).0 * 2),
+        ClockSource::PllGpu => pll_rate(Pll::Gpu),
+        ClockSource::PllMipi => pll_rate(Pll::Mipi),
+        ClockSource::PllHsic => pll_rate(Pll::Hsic),
+        ClockSource::PllDe => pll_rate(Pll::De),
+        ClockSource::PllDdr1 => pll_rate(Pll::Ddr1),
+    }
+}
+
+impl Ccu {
+    /// Apply the configuration and validate the clock tree
+    ///
+    /// Every enabled PLL must report lock, the returned `Clocks` are then
+    /// what the drivers are configured against. This can only be done
+    /// once, afterwards PLL_PERIPH0 can't be changed and `Error::Frozen` is
+    /// returned. CPUX can still be scaled with `set_cpu_frequency`, the
+    /// drivers don't depend on it.
+    pub fn freeze(&mut self, config: Config) -> Result<Clocks, Error> {
+        if self.frozen {
+            return Err(Error::Frozen);
+        }
+
+        if let Some(freq) = config.pll_periph0 {
+            self.set_pll_rate(Pll::Periph0, freq, Hertz::new(0))?;
+        }
+
+        if let Some(freq) = config.cpu {
+            self.set_cpu_frequency(freq)?;
+        }
+
+        if let Some(freq) = config.ahb1 {
+            self.set_ahb1(freq)?;
+        }
+
+        if let Some(freq) = config.apb2 {
+            self.set_apb2(freq)?;
+        }
+
+        for pll in Pll::ALL.iter() {
+            if pll_read(*pll) & (1 << pll::ENABLE_BIT) != 0 {
+                wait_for_lock(*pll)?;
+            }
+        }
+
+        self.frozen = true;
+        Ok(Clocks::read())
+    }
+
+    /// Read the clock tree as it is, without applying a configuration or
+    /// waiting for the PLLs to lock
+    ///
+    /// For code that must not reprogram the clocks, like a panic handler.
+    /// Everything else should use the `Clocks` from `freeze`.
+    pub fn snapshot(&self) -> ClockSnapshot {
+        Clocks::read().into()
+    }
+
+    /// The PLL feeding the bus clocks of the frozen `Clocks`
+    fn check_frozen(&self, pll: Pll) -> Result<(), Error> {
+        match pll {
+            Pll::Periph0 if self.frozen => Err(Error::Frozen),
+            _ => Ok(()),
+        }
+    }
+
+    fn set_ahb1(&mut self, freq: Hertz) -> Result<(), Error> {
+        let src = pll_rate(Pll::Periph0).0;
+        if src == 0 {
+            return Err(Error::NoSolution);
+        }
+
+        // AHB1 = PLL_PERIPH0(1X) / pre_div / 2^ratio
+        let mut best: Option<(u32, u32, u32)> = None;
+        for pre_div in 1..=4 {
+            for ratio in 0..4 {
+                let rate = src / pre_div / (1 << ratio);
+                if rate > freq.0 {
+                    continue;
+                }
+                let better = match best {
+                    Some((_, _, best_rate)) => rate > best_rate,
+                    None => true,
+                };
+                if better {
+                    best = Some((pre_div, ratio, rate));
+                }
+            }
+        }
+        let (pre_div, ratio, _rate) = best.ok_or(Error::NoSolution)?;
+
+        let ccu = unsafe { &mut *CCU::mut_ptr() };
+        ccu.ahb1_apb1_cfg.modify(
+            Ahb1Apb1Config::Ahb1PreDiv::Field::new(pre_div - 1).unwrap()
+                + Ahb1Apb1Config::Ahb1ClockDivRatio::Field::new(ratio).unwrap(),
+        );
+        ccu.ahb1_apb1_cfg
+            .modify(Ahb1Apb1Config::Ahb1ClockSrcSel::PllPeriph01x);
+
+        Ok(())
+    }
+
+    fn set_apb2(&mut self, freq: Hertz) -> Result<(), Error> {
+        let osc = Clocks::OSC_24M_FREQ.0;
+        let periph0_2x = pll_rate(Pll::Periph0).0 * 2;
+
+        // APB2 = source / M / 2^N
+        let mut best: Option<(bool, u32, u32, u32)> = None;
+        for (use_pll, src) in [(false, osc), (true, periph0_2x)].iter() {
+            if *src == 0 {
+                continue;
+            }
+            for m in 1..=32 {
+                for n in 0..4 {
+                    let rate = src / m / (1 << n);
+                    if rate > freq.0 {
+                        continue;
+                    }
+                    let better = match best {
+                        Some((_, _, _, best_rate)) => rate > best_rate,
+                        None => true,
+                    };
+                    if better {
+                        best = Some((*use_pll, m, n, rate));
+                    }
+                }
+            }
+        }
+        let (use_pll, m, n, _rate) = best.ok_or(Error::NoSolution)?;
+
+        let ccu = unsafe { &mut *CCU::mut_ptr() };
+        ccu.apb2_cfg.modify(
+            Apb2Config::RatioM::Field::new(m - 1).unwrap()
+                + Apb2Config::RatioN::Field::new(n).unwrap(),
+        );
+        if use_pll {
+            ccu.apb2_cfg.modify(Apb2Config::ClockSrcSel::PllPeriph02x);
+        } else {
+            ccu.apb2_cfg.modify(Apb2Config::ClockSrcSel::Osc24M);
+        }
+
+        Ok(())
+    }
+
+    /// Primary output rate of a PLL, zero when disabled
+    pub fn pll_rate(&self, pll: Pll) -> Hertz {
+        pll_rate(pll)
+    }
+
+    pub fn pll_factors(&self, pll: Pll) -> PllFactors {
+        pll.spec().decode(pll_read(pll))
+    }
+
+    /// Program and enable a PLL, waits for it to lock
+    ///
+    /// Returns `Error::LockTimeout` if the PLL doesn't lock.
+    pub fn set_pll_factors(&mut self, pll: Pll, factors: PllFactors) -> Result<Hertz, Error> {
+        self.check_frozen(pll)?;
+        let spec = pll.spec();
+
+        let mut reg = spec.apply(pll_read(pll), &factors)?;
+        reg |= spec.enable_bits | (1 << pll::ENABLE_BIT);
+        pll_write(pll, reg);
+
+        if let Some(bit) = spec.update_bit {
+            pll_write(pll, reg | (1 << bit));
+            let mut timeout = PLL_LOCK_TIMEOUT;
+            while pll_read(pll) & (1 << bit) != 0 {
+                if timeout == 0 {
+                    return Err(Error::LockTimeout(pll));
+                }
+                timeout -= 1;
+                asm::nop();
+            }
+        }
+
+        wait_for_lock(pll)?;
+
+        Ok(spec.rate(pll_parent_rate(pll), &factors))
+    }
+
+    /// Solve for and program the factors producing the rate closest to
+    /// `target`, within `tolerance`
+    pub fn set_pll_rate(
+        &mut self,
+        pll: Pll,
+        target: Hertz,
+        tolerance: Hertz,
+    ) -> Result<Hertz, Error> {
+        let (factors, _rate) = pll.spec().solve(pll_parent_rate(pll), target, tolerance)?;
+        self.set_pll_factors(pll, factors)
+    }
+
+    pub fn disable_pll(&mut self, pll: Pll) -> Result<(), Error> {
+        self.check_frozen(pll)?;
+        let reg = pll_read(pll);
+        pll_write(pll, reg & !(1 << pll::ENABLE_BIT));
+        Ok(())
+    }
+
+    pub fn clock_source_rate(&self, src: ClockSource) -> Hertz {
+        clock_source_rate(src)
+    }
+
+    /// Module clock rate, zero when gated or the parent is disabled
+    pub fn module_clock_rate(&self, clk: ModuleClock) -> Hertz {
+        let spec = clk.spec();
+        let reg = module_clock_read(clk);
+        if spec.is_gated(reg) {
+            return Hertz::new(0);
+        }
+        match spec.source(reg) {
+            Some(src) => spec.rate(clock_source_rate(src), reg),
+            None => Hertz::new(0),
+        }
+    }
+
+    /// Select the parent, program the highest rate not exceeding `rate`
+    /// and ungate the module clock
+    ///
+    /// The parent must already be running.
+    pub fn set_module_clock(
+        &mut self,
+        clk: ModuleClock,
+        src: ClockSource,
+        rate: Hertz,
+    ) -> Result<Hertz, Error> {
+        let spec = clk.spec();
+        let (m, n, actual) = spec.solve(clock_source_rate(src), rate)?;
+
+        let mut reg = spec.apply(module_clock_read(clk), src, m, n)?;
+        if let Some(bit) = spec.gate_bit {
+            reg |= 1 << bit;
+        }
+        module_clock_write(clk, reg);
+
+        Ok(actual)
+    }
+
+    pub fn disable_module_clock(&mut self, clk: ModuleClock) {
+        if let Some(bit) = clk.spec().gate_bit {
+            let reg = module_clock_read(clk);
+            module_clock_write(clk, reg & !(1 << bit));
+        }
+    }
+
+    /// Reprogram PLL_CPUX and re-derive the AXI and CPUX APB dividers
+    ///
+    /// CPUX runs from OSC24M while PLL_CPUX relocks. The frequency must
+    /// not exceed the fastest operating point, and VDD-CPUX must already
+    /// be at the voltage of `OperatingPoint::for_frequency(freq)`. The
+    /// frozen `Clocks` keep the CPUX and AXI rates from `freeze`.
+    ///
+    /// If PLL_CPUX doesn't lock, its previous factors and the previous CPUX
+    /// clock source are restored before the error is returned.
+    pub fn set_cpu_frequency(&mut self, freq: Hertz) -> Result<Hertz, Error> {
+        self.check_frozen(Pll::CpuX)?;
+        if freq.0 < OperatingPoint::min().frequency.0
+            || OperatingPoint::for_frequency(freq).is_none()
+        {
+            return Err(Error::InvalidFrequency);
+        }
+
+        let (factors, rate) =
+            pll::PLL_CPUX.solve(Clocks::OSC_24M_FREQ, freq, Hertz::new(CPU_FREQ_TOLERANCE))?;
+
+        let ccu = unsafe { &mut *CCU::mut_ptr() };
+        let prev_factors = self.pll_factors(Pll::CpuX);
+        let prev_src = ccu
+            .cpu_axi_cfg
+            .get_field(CpuXAxiConfig::ClockSrcSel::Read)
+            .unwrap();
+
+        ccu.cpu_axi_cfg.modify(CpuXAxiConfig::ClockSrcSel::Osc24M);
+        cpu_clock_settle();
+
+        if let Err(e) = self.set_pll_factors(Pll::CpuX, factors) {
+            // Back to the previous rate, CPUX stays on OSC24M if PLL_CPUX
+            // doesn't lock at all
+            if self.set_pll_factors(Pll::CpuX, prev_factors).is_ok() {
+                ccu.cpu_axi_cfg.modify(prev_src);
+                cpu_clock_settle();
+            }
+            return Err(e);
+        }
+
+        let axi_div = cpu::axi_divider(rate);
+        let apb_div = match cpu::apb_divider(rate) {
+            1 => CpuXAxiConfig::ApbClockDivRatio::Divide1,
+            2 => CpuXAxiConfig::ApbClockDivRatio::Divide2,
+            _ => CpuXAxiConfig::ApbClockDivRatio::Divide4,
+        };
+        ccu.cpu_axi_cfg
+            .modify(CpuXAxiConfig::AxiClockDivRatio::Field::new(axi_div - 1).unwrap() + apb_div);
+        cpu_clock_settle();
+
+        ccu.cpu_axi_cfg.modify(CpuXAxiConfig::ClockSrcSel::PllCpuX);
+        cpu_clock_settle();
+
+        Ok(rate)
+    }
+
+    pub fn set_operating_point(&mut self, op: &OperatingPoint) -> Result<Hertz, Error> {
+        self.set_cpu_frequency(op.frequency)
+    }
+
+    pub(crate) fn pll_video0(&self) -> Hertz {
+        self.pll_rate(Pll::Video0)
+    }
+
+    pub(crate) fn set_pll_video0(&mut self, clk: Hertz) {
+        if clk.0 == 0 {
+            self.disable_pll(Pll::Video0)
+                .expect("Invalid PLL_VIDEO0 rate");
+        } else {
+            // 6 MHz steps to allow higher frequency for DE2
+            let m = 4;
+            let n = clk.0 / (Clocks::OSC_24M_FREQ.0 / m);
+            // PLL3 rate = 24000000 * n / m
+            self.set_pll_factors(Pll::Video0, PllFactors::nm(n, m))
+                .expect("Invalid PLL_VIDEO0 rate");
+        }
+    }
+
+    pub(crate) fn set_pll_de(&mut self, clk: Hertz) {
+        if clk.0 == 0 {
+            self.disable_pll(Pll::De).expect("Invalid PLL_DE rate");
+        } else {
+            // 12 MHz steps
+            let m = 2;
+            let n = clk.0 / (Clocks::OSC_24M_FREQ.0 / m);
+            // PLL10 rate = 24000000 * n / m
+            self.set_pll_factors(Pll::De, PllFactors::nm(n, m))
+                .expect("Invalid PLL_DE rate");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frozen_ccu() -> Ccu {
+        Ccu {
+            bcg0: BCG0 { _0: () },
+            bcg1: BCG1 { _0: () },
+            bcg2: BCG2 { _0: () },
+            bcg3: BCG3 { _0: () },
+            bsr0: BSR0 { _0: () },
+            bsr1: BSR1 { _0: () },
+            bsr2: BSR2 { _0: () },
+            bsr3: BSR3 { _0: () },
+            bsr4: BSR4 { _0: () },
+            frozen: true,
+        }
+    }
+
+    #[test]
+    fn freeze_keeps_only_periph0() {
+        let ccu = frozen_ccu();
+        assert_eq!(ccu.check_frozen(Pll::Periph0), Err(Error::Frozen));
+        assert_eq!(ccu.check_frozen(Pll::CpuX), Ok(()));
+        assert_eq!(ccu.check_frozen(Pll::Video0), Ok(()));
+    }
+
+    #[test]
+    fn cpu_frequency_after_freeze() {
+        let mut ccu = frozen_ccu();
+        // Gets past the freeze check, then rejects the rate before any
+        // register is touched
+        assert_eq!(
+            ccu.set_cpu_frequency(Hertz(100_000_000)),
+            Err(Error::InvalidFrequency)
+        );
+        assert_eq!(
+            ccu.set_operating_point(&OperatingPoint::new(Hertz(2_000_000_000), 1_300_000)),
+            Err(Error::InvalidFrequency)
+        );
+        assert_eq!(ccu.disable_pll(Pll::Periph0), Err(Error::Frozen));
+    }
+}
@@ -0,0 +1,486 @@
+//! Module clocks
+//!
+//! Describes the source mux, dividers and gate of each module (special)
+//! clock register. Like the PLL factor math this is free of register
+//! access.
+//!
+//! SCLK = source / M / N
+//!
+//! The DRAM clocks are owned by the boot loader and are not modeled.
+
+use super::pll::Factor;
+use super::Error;
+use embedded_time::rate::Hertz;
+
+/// Parent clocks available to the module clock muxes
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum ClockSource {
+    LOsc,
+    Osc24M,
+    PllAudio,
+    PllAudio2x,
+    PllAudio4x,
+    PllAudio8x,
+    PllVideo0,
+    PllVideo0x2,
+    PllVe,
+    PllDdr0,
+    PllPeriph0,
+    PllPeriph0x2,
+    PllPeriph1,
+    PllPeriph1x2,
+    PllVideo1,
+    PllVideo1x2,
+    PllGpu,
+    PllMipi,
+    PllHsic,
+    PllDe,
+    PllDdr1,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum ModuleClock {
+    Ths,
+    Nand,
+    Mmc0,
+    Mmc1,
+    Mmc2,
+    Ts,
+    Ce,
+    Spi0,
+    Spi1,
+    I2sPcm0,
+    I2sPcm1,
+    I2sPcm2,
+    Spdif,
+    De,
+    Tcon0,
+    Tcon1,
+    Deinterlace,
+    CsiMisc,
+    /// CSI master clock (MCLK)
+    CsiMaster,
+    /// CSI special clock (SCLK)
+    CsiSpecial,
+    Ve,
+    AcDigital,
+    Avs,
+    Hdmi,
+    HdmiDdc,
+    MBus,
+    MipiDsi,
+    Gpu,
+}
+
+/// Source mux field
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct MuxField {
+    pub offset: u32,
+    pub width: u32,
+}
+
+impl MuxField {
+    pub const fn new(offset: u32, width: u32) -> Self {
+        MuxField { offset, width }
+    }
+
+    pub fn mask(&self) -> u32 {
+        ((1 << self.width) - 1) << self.offset
+    }
+}
+
+/// Static description of a module clock register
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct ModuleClockSpec {
+    /// Parent selected by each mux value, `None` for reserved values
+    pub sources: &'static [Option<ClockSource>],
+    /// Absent when the module has a single fixed parent
+    pub mux: Option<MuxField>,
+    pub m: Option<Factor>,
+    pub n: Option<Factor>,
+    pub gate_bit: Option<u32>,
+}
+
+use ClockSource::*;
+
+const OSC24M_ONLY: [Option<ClockSource>; 1] = [Some(Osc24M)];
+const PERIPH_2X_SOURCES: [Option<ClockSource>; 3] =
+    [Some(Osc24M), Some(PllPeriph0x2), Some(PllPeriph1x2)];
+const PERIPH_1X_SOURCES: [Option<ClockSource>; 3] =
+    [Some(Osc24M), Some(PllPeriph0), Some(PllPeriph1)];
+const PERIPH_0_1_SOURCES: [Option<ClockSource>; 2] = [Some(PllPeriph0), Some(PllPeriph1)];
+const I2S_SOURCES: [Option<ClockSource>; 4] = [
+    Some(PllAudio8x),
+    Some(PllAudio4x),
+    Some(PllAudio2x),
+    Some(PllAudio),
+];
+const THS_DIVIDERS: [u32; 4] = [1, 2, 4, 6];
+
+const fn mnp_clock(sources: &'static [Option<ClockSource>]) -> ModuleClockSpec {
+    ModuleClockSpec {
+        sources,
+        mux: Some(MuxField::new(24, 2)),
+        m: Some(Factor::linear(0, 4)),
+        n: Some(Factor::pow2(16, 2)),
+        gate_bit: Some(31),
+    }
+}
+
+const fn gate_only(sources: &'static [Option<ClockSource>]) -> ModuleClockSpec {
+    ModuleClockSpec {
+        sources,
+        mux: None,
+        m: None,
+        n: None,
+        gate_bit: Some(31),
+    }
+}
+
+const THS: ModuleClockSpec = ModuleClockSpec {
+    sources: &OSC24M_ONLY,
+    mux: Some(MuxField::new(24, 2)),
+    m: Some(Factor::table(0, 2, &THS_DIVIDERS)),
+    n: None,
+    gate_bit: Some(31),
+};
+
+const NAND: ModuleClockSpec = mnp_clock(&PERIPH_1X_SOURCES);
+
+const MMC_CE: ModuleClockSpec = mnp_clock(&PERIPH_2X_SOURCES);
+
+const SPI: ModuleClockSpec = mnp_clock(&PERIPH_1X_SOURCES);
+
+const TS: ModuleClockSpec = ModuleClockSpec {
+    sources: &[Some(Osc24M), Some(PllPeriph0)],
+    mux: Some(MuxField::new(24, 4)),
+    m: Some(Factor::linear(0, 4)),
+    n: Some(Factor::pow2(16, 2)),
+    gate_bit: Some(31),
+};
+
+const I2S_PCM: ModuleClockSpec = ModuleClockSpec {
+    sources: &I2S_SOURCES,
+    mux: Some(MuxField::new(16, 2)),
+    m: None,
+    n: None,
+    gate_bit: Some(31),
+};
+
+const SPDIF: ModuleClockSpec = ModuleClockSpec {
+    sources: &[Some(PllAudio)],
+    mux: None,
+    m: Some(Factor::linear(0, 4)),
+    n: None,
+    gate_bit: Some(31),
+};
+
+const DE: ModuleClockSpec = ModuleClockSpec {
+    sources: &[Some(PllPeriph0x2), Some(PllDe)],
+    mux: Some(MuxField::new(24, 3)),
+    m: Some(Factor::linear(0, 4)),
+    n: None,
+    gate_bit: Some(31),
+};
+
+const TCON0: ModuleClockSpec = ModuleClockSpec {
+    sources: &[Some(PllMipi), None, Some(PllVideo0x2)],
+    mux: Some(MuxField::new(24, 3)),
+    m: None,
+    n: None,
+    gate_bit: Some(31),
+};
+
+const TCON1: ModuleClockSpec = ModuleClockSpec {
+    sources: &[Some(PllVideo0), None, Some(PllVideo1)],
+    mux: Some(MuxField::new(24, 2)),
+    m: Some(Factor::linear(0, 4)),
+    n: None,
+    gate_bit: Some(31),
+};
+
+const DEINTERLACE: ModuleClockSpec = ModuleClockSpec {
+    sources: &PERIPH_0_1_SOURCES,
+    mux: Some(MuxField::new(24, 3)),
+    m: Some(Factor::linear(0, 4)),
+    n: None,
+    gate_bit: Some(31),
+};
+
+const CSI_MASTER: ModuleClockSpec = ModuleClockSpec {
+    sources: &[
+        Some(Osc24M),
+        Some(PllVideo1),
+        None,
+        None,
+        None,
+        Some(PllPeriph1),
+    ],
+    mux: Some(MuxField::new(8, 3)),
+    m: Some(Factor::linear(0, 4)),
+    n: None,
+    gate_bit: Some(15),
+};
+
+const CSI_SPECIAL: ModuleClockSpec = ModuleClockSpec {
+    sources: &PERIPH_0_1_SOURCES,
+    mux: Some(MuxField::new(24, 3)),
+    m: Some(Factor::linear(16, 4)),
+    n: None,
+    gate_bit: Some(31),
+};
+
+const VE: ModuleClockSpec = ModuleClockSpec {
+    sources: &[Some(PllVe)],
+    mux: None,
+    m: None,
+    n: Some(Factor::linear(16, 3)),
+    gate_bit: Some(31),
+};
+
+/// The mux values 2 and 3 are reserved on the A64
+const HDMI: ModuleClockSpec = ModuleClockSpec {
+    sources: &[Some(PllVideo0), Some(PllVideo1)],
+    mux: Some(MuxField::new(24, 2)),
+    m: Some(Factor::linear(0, 4)),
+    n: None,
+    gate_bit: Some(31),
+};
+
+const MBUS: ModuleClockSpec = ModuleClockSpec {
+    sources: &[
+        Some(Osc24M),
+        Some(PllPeriph0x2),
+        Some(PllDdr0),
+        Some(PllDdr1),
+    ],
+    mux: Some(MuxField::new(24, 2)),
+    m: Some(Factor::linear(0, 3)),
+    n: None,
+    gate_bit: Some(31),
+};
+
+const MIPI_DSI: ModuleClockSpec = ModuleClockSpec {
+    sources: &[Some(PllVideo0), None, Some(PllPeriph0)],
+    mux: Some(MuxField::new(8, 2)),
+    m: Some(Factor::linear(0, 4)),
+    n: None,
+    gate_bit: Some(15),
+};
+
+const GPU: ModuleClockSpec = ModuleClockSpec {
+    sources: &[Some(PllGpu)],
+    mux: None,
+    m: Some(Factor::linear(0, 3)),
+    n: None,
+    gate_bit: Some(31),
+};
+
+impl ModuleClock {
+    pub fn spec(self) -> &'static ModuleClockSpec {
+        use ModuleClock::*;
+        match self {
+            Ths => &THS,
+            Nand => &NAND,
+            Mmc0 | Mmc1 | Mmc2 | Ce => &MMC_CE,
+            Ts => &TS,
+            Spi0 | Spi1 => &SPI,
+            I2sPcm0 | I2sPcm1 | I2sPcm2 => &I2S_PCM,
+            Spdif => &SPDIF,
+            De => &DE,
+            Tcon0 => &TCON0,
+            Tcon1 => &TCON1,
+            Deinterlace => &DEINTERLACE,
+            CsiMisc | Avs | HdmiDdc => &GATE_OSC24M,
+            CsiMaster => &CSI_MASTER,
+            CsiSpecial => &CSI_SPECIAL,
+            Ve => &VE,
+            AcDigital => &GATE_AUDIO,
+            Hdmi => &HDMI,
+            MBus => &MBUS,
+            MipiDsi => &MIPI_DSI,
+            Gpu => &GPU,
+        }
+    }
+}
+
+const GATE_OSC24M: ModuleClockSpec = gate_only(&OSC24M_ONLY);
+const GATE_AUDIO: ModuleClockSpec = gate_only(&[Some(PllAudio)]);
+
+impl ModuleClockSpec {
+    /// Parent currently selected by the register value
+    pub fn source(&self, reg: u32) -> Option<ClockSource> {
+        match self.mux {
+            Some(mux) => {
+                let sel = ((reg & mux.mask()) >> mux.offset) as usize;
+                self.sources.get(sel).copied().flatten()
+            }
+            None => self.sources[0],
+        }
+    }
+
+    /// Mux value selecting the given parent
+    pub fn source_sel(&self, src: ClockSource) -> Result<u32, Error> {
+        self.sources
+            .iter()
+            .position(|s| *s == Some(src))
+            .map(|sel| sel as u32)
+            .ok_or(Error::InvalidSource)
+    }
+
+    pub fn is_gated(&self, reg: u32) -> bool {
+        match self.gate_bit {
+            Some(bit) => reg & (1 << bit) == 0,
+            None => false,
+        }
+    }
+
+    /// Total divider encoded in the register value
+    pub fn divider(&self, reg: u32) -> u32 {
+        self.m.map_or(1, |f| f.decode(reg)) * self.n.map_or(1, |f| f.decode(reg))
+    }
+
+    pub fn rate(&self, parent: Hertz, reg: u32) -> Hertz {
+        Hertz(parent.0 / self.divider(reg))
+    }
+
+    /// Find the M and N dividers producing the highest rate that
+    /// does not exceed `target`
+    ///
+    /// Returns `(m, n, rate)`.
+    pub fn solve(&self, parent: Hertz, target: Hertz) -> Result<(u32, u32, Hertz), Error> {
+        let one = Factor::table(0, 0, &[1]);
+        let m_factor = self.m.unwrap_or(one);
+        let n_factor = self.n.unwrap_or(one);
+
+        let mut best: Option<(u32, u32, Hertz)> = None;
+        for n in n_factor.values() {
+            for m in m_factor.values() {
+                let rate = Hertz(parent.0 / (m * n));
+                if rate.0 > target.0 {
+                    continue;
+                }
+                let better = match best {
+                    Some((_, _, best_rate)) => rate.0 > best_rate.0,
+                    None => true,
+                };
+                if better {
+                    best = Some((m, n, rate));
+                }
+            }
+        }
+
+        best.ok_or(Error::NoSolution)
+    }
+
+    /// Replace the mux and divider fields of a register value
+    pub fn apply(&self, reg: u32, src: ClockSource, m: u32, n: u32) -> Result<u32, Error> {
+        let mut reg = reg;
+
+        let sel = self.source_sel(src)?;
+        if let Some(mux) = self.mux {
+            reg = (reg & !mux.mask()) | (sel << mux.offset);
+        }
+
+        match self.m {
+            Some(f) => reg = (reg & !f.mask()) | f.encode(m)?,
+            None if m != 1 => return Err(Error::InvalidFactors),
+            None => (),
+        }
+        match self.n {
+            Some(f) => reg = (reg & !f.mask()) | f.encode(n)?,
+            None if n != 1 => return Err(Error::InvalidFactors),
+            None => (),
+        }
+
+        Ok(reg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERIPH0: Hertz = Hertz(600_000_000);
+
+    #[test]
+    fn solve_apply_round_trip() {
+        let spec = ModuleClock::Spi0.spec();
+        let (m, n, rate) = spec.solve(PERIPH0, Hertz(100_000_000)).unwrap();
+        assert_eq!((m, n, rate.0), (6, 1, 100_000_000));
+
+        let reg = spec.apply(1 << 31, PllPeriph0, m, n).unwrap();
+        assert_eq!(spec.source(reg), Some(PllPeriph0));
+        assert_eq!(spec.divider(reg), m * n);
+        assert_eq!(spec.rate(PERIPH0, reg), rate);
+        assert!(!spec.is_gated(reg));
+    }
+
+    #[test]
+    fn solve_never_exceeds_target() {
+        let spec = ModuleClock::Mmc0.spec();
+        let (m, n, rate) = spec.solve(PERIPH0, Hertz(52_000_000)).unwrap();
+        assert!(rate.0 <= 52_000_000);
+        assert_eq!(rate, Hertz(PERIPH0.0 / (m * n)));
+        assert_eq!(rate.0, 50_000_000);
+
+        // Faster than the parent runs undivided
+        assert_eq!(
+            spec.solve(PERIPH0, Hertz(1_000_000_000)),
+            Ok((1, 1, PERIPH0))
+        );
+    }
+
+    #[test]
+    fn solve_rejects_too_slow() {
+        // M and N divide by 128 at most
+        let spec = ModuleClock::Spi0.spec();
+        assert!(spec.solve(PERIPH0, Hertz(PERIPH0.0 / 128)).is_ok());
+        assert_eq!(
+            spec.solve(PERIPH0, Hertz(PERIPH0.0 / 128 - 1)),
+            Err(Error::NoSolution)
+        );
+    }
+
+    #[test]
+    fn apply_rejects_invalid() {
+        let spi = ModuleClock::Spi0.spec();
+        assert_eq!(spi.apply(0, PllPeriph0, 17, 1), Err(Error::InvalidFactors));
+        assert_eq!(spi.apply(0, PllPeriph0, 1, 3), Err(Error::InvalidFactors));
+        assert_eq!(spi.apply(0, PllVideo0, 1, 1), Err(Error::InvalidSource));
+
+        // THS divides by 1, 2, 4 or 6
+        let ths = ModuleClock::Ths.spec();
+        assert_eq!(ths.apply(0, Osc24M, 3, 1), Err(Error::InvalidFactors));
+        let reg = ths.apply(0, Osc24M, 6, 1).unwrap();
+        assert_eq!(ths.divider(reg), 6);
+
+        // I2S has no dividers
+        let i2s = ModuleClock::I2sPcm2.spec();
+        assert_eq!(i2s.apply(0, PllAudio, 2, 1), Err(Error::InvalidFactors));
+    }
+
+    #[test]
+    fn reserved_mux_values() {
+        let tcon0 = ModuleClock::Tcon0.spec();
+        assert_eq!(tcon0.source(1 << 24), None);
+        assert_eq!(tcon0.source(2 << 24), Some(PllVideo0x2));
+        assert_eq!(tcon0.source_sel(PllVideo0x2), Ok(2));
+
+        let hdmi = ModuleClock::Hdmi.spec();
+        assert_eq!(hdmi.source(1 << 24), Some(PllVideo1));
+        assert_eq!(hdmi.source(2 << 24), None);
+        assert_eq!(hdmi.source_sel(PllVideo0x2), Err(Error::InvalidSource));
+    }
+
+    #[test]
+    fn nand_runs_from_the_1x_outputs() {
+        let nand = ModuleClock::Nand.spec();
+        assert_eq!(nand.source(1 << 24), Some(PllPeriph0));
+        assert_eq!(nand.source(2 << 24), Some(PllPeriph1));
+        assert_eq!(nand.source_sel(PllPeriph0x2), Err(Error::InvalidSource));
+
+        let mmc = ModuleClock::Mmc0.spec();
+        assert_eq!(mmc.source(1 << 24), Some(PllPeriph0x2));
+    }
+}
@@ -0,0 +1,609 @@
+//! PLL factor math
+//!
+//! Describes the factor fields of every PLL control register and how the
+//! factors combine into an output rate. Nothing in here touches the
+//! hardware, so the encoding and the solver can be exercised on the host.
+//!
+//! PLL output = (parent * N * K) / (M * P)
+
+use super::Error;
+use embedded_time::rate::Hertz;
+
+pub const ENABLE_BIT: u32 = 31;
+pub const LOCK_BIT: u32 = 28;
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Pll {
+    CpuX,
+    Audio,
+    Video0,
+    Ve,
+    Ddr0,
+    Periph0,
+    Periph1,
+    Video1,
+    Gpu,
+    Mipi,
+    Hsic,
+    De,
+    Ddr1,
+}
+
+impl Pll {
+    pub const COUNT: usize = 13;
+
+    pub const ALL: [Pll; Pll::COUNT] = [
+        Pll::CpuX,
+        Pll::Audio,
+        Pll::Video0,
+        Pll::Ve,
+        Pll::Ddr0,
+        Pll::Periph0,
+        Pll::Periph1,
+        Pll::Video1,
+        Pll::Gpu,
+        Pll::Mipi,
+        Pll::Hsic,
+        Pll::De,
+        Pll::Ddr1,
+    ];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn spec(self) -> &'static PllSpec {
+        match self {
+            Pll::CpuX => &PLL_CPUX,
+            Pll::Audio => &PLL_AUDIO,
+            Pll::Video0 | Pll::Video1 => &PLL_VIDEO,
+            Pll::Ve | Pll::Gpu | Pll::Hsic | Pll::De => &PLL_NM,
+            Pll::Ddr0 => &PLL_DDR0,
+            Pll::Periph0 | Pll::Periph1 => &PLL_PERIPH,
+            Pll::Mipi => &PLL_MIPI,
+            Pll::Ddr1 => &PLL_DDR1,
+        }
+    }
+
+    /// PLL_MIPI is the only PLL not fed directly by OSC24M
+    pub fn parent(self) -> Option<Pll> {
+        match self {
+            Pll::Mipi => Some(Pll::Video0),
+            _ => None,
+        }
+    }
+}
+
+/// How a factor value is stored in its register field
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Encoding {
+    /// value = field + 1
+    Linear,
+    /// value = 2^field
+    Pow2,
+    /// value = table[field]
+    Table(&'static [u32]),
+}
+
+/// A factor field within a clock control register
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Factor {
+    pub offset: u32,
+    pub width: u32,
+    pub encoding: Encoding,
+    /// Smallest usable factor value
+    pub min: u32,
+}
+
+impl Factor {
+    pub const fn linear(offset: u32, width: u32) -> Self {
+        Factor {
+            offset,
+            width,
+            encoding: Encoding::Linear,
+            min: 1,
+        }
+    }
+
+    pub const fn pow2(offset: u32, width: u32) -> Self {
+        Factor {
+            offset,
+            width,
+            encoding: Encoding::Pow2,
+            min: 1,
+        }
+    }
+
+    pub const fn table(offset: u32, width: u32, table: &'static [u32]) -> Self {
+        Factor {
+            offset,
+            width,
+            encoding: Encoding::Table(table),
+            min: 1,
+        }
+    }
+
+    pub const fn with_min(self, min: u32) -> Self {
+        Factor { min, ..self }
+    }
+
+    pub fn mask(&self) -> u32 {
+        ((1 << self.width) - 1) << self.offset
+    }
+
+    /// Number of distinct field values
+    pub fn field_count(&self) -> u32 {
+        match self.encoding {
+            Encoding::Table(t) => t.len() as u32,
+            _ => 1 << self.width,
+        }
+    }
+
+    /// Factor value for the given raw field value
+    pub fn value_of(&self, field: u32) -> u32 {
+        match self.encoding {
+            Encoding::Linear => field + 1,
+            Encoding::Pow2 => 1 << field,
+            Encoding::Table(t) => t[field as usize],
+        }
+    }
+
+    pub fn max_value(&self) -> u32 {
+        (0..self.field_count())
+            .map(|f| self.value_of(f))
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// Decode the factor value from a whole register value
+    pub fn decode(&self, reg: u32) -> u32 {
+        let field = (reg & self.mask()) >> self.offset;
+        if field < self.field_count() {
+            self.value_of(field)
+        } else {
+            // Reserved table entries alias the last entry
+            self.value_of(self.field_count() - 1)
+        }
+    }
+
+    /// Encode the factor value into its position in the register
+    pub fn encode(&self, value: u32) -> Result<u32, Error> {
+        if value < self.min {
+            return Err(Error::InvalidFactors);
+        }
+        (0..self.field_count())
+            .find(|f| self.value_of(*f) == value)
+            .map(|f| f << self.offset)
+            .ok_or(Error::InvalidFactors)
+    }
+
+    /// Iterator over the valid factor values, smallest field first
+    pub fn values(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.field_count())
+            .map(move |f| self.value_of(f))
+            .filter(move |v| *v >= self.min)
+    }
+}
+
+/// Factor values, a factor not present in a PLL is always 1
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct PllFactors {
+    pub n: u32,
+    pub k: u32,
+    pub m: u32,
+    pub p: u32,
+}
+
+impl PllFactors {
+    pub const fn new(n: u32, k: u32, m: u32, p: u32) -> Self {
+        PllFactors { n, k, m, p }
+    }
+
+    /// Factors for the N/M style PLLs
+    pub const fn nm(n: u32, m: u32) -> Self {
+        PllFactors { n, k: 1, m, p: 1 }
+    }
+}
+
+impl Default for PllFactors {
+    fn default() -> Self {
+        PllFactors::new(1, 1, 1, 1)
+    }
+}
+
+/// Static description of a PLL control register
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct PllSpec {
+    pub n: Factor,
+    pub k: Option<Factor>,
+    pub m: Option<Factor>,
+    pub p: Option<Factor>,
+    /// Fixed divider between the factors and the PLL's primary output,
+    /// PLL_PERIPHx(1X) is half of the N * K rate
+    pub post_div: u32,
+    /// Allowed range of parent * N * K / M, before P and the post divider
+    pub min_rate: Hertz,
+    pub max_rate: Hertz,
+    /// Set to select integer mode
+    pub integer_mode_bit: Option<u32>,
+    /// Set to latch new factors, self-clears once applied
+    pub update_bit: Option<u32>,
+    /// Additional bits set along with the enable bit
+    pub enable_bits: u32,
+}
+
+const ANY_MIN_RATE: Hertz = Hertz(0);
+const ANY_MAX_RATE: Hertz = Hertz(u32::MAX);
+
+/// PLL_CPUX = (24MHz * N * K) / (M * P)
+pub const PLL_CPUX: PllSpec = PllSpec {
+    n: Factor::linear(8, 5),
+    k: Some(Factor::linear(4, 2)),
+    m: Some(Factor::linear(0, 2)),
+    p: Some(Factor::pow2(16, 2)),
+    post_div: 1,
+    min_rate: Hertz(200_000_000),
+    max_rate: Hertz(2_600_000_000),
+    integer_mode_bit: None,
+    update_bit: None,
+    enable_bits: 0,
+};
+
+/// PLL_AUDIO = (24MHz * N) / (M * P)
+pub const PLL_AUDIO: PllSpec = PllSpec {
+    n: Factor::linear(8, 7),
+    k: None,
+    m: Some(Factor::linear(0, 5)),
+    p: Some(Factor::linear(16, 4)),
+    post_div: 1,
+    min_rate: ANY_MIN_RATE,
+    max_rate: ANY_MAX_RATE,
+    integer_mode_bit: None,
+    update_bit: None,
+    enable_bits: 0,
+};
+
+/// PLL_VIDEO0/1 = (24MHz * N) / M, integer mode
+pub const PLL_VIDEO: PllSpec = PllSpec {
+    n: Factor::linear(8, 7),
+    k: None,
+    m: Some(Factor::linear(0, 4)),
+    p: None,
+    post_div: 1,
+    min_rate: Hertz(192_000_000),
+    max_rate: Hertz(1_008_000_000),
+    integer_mode_bit: Some(24),
+    update_bit: None,
+    enable_bits: 0,
+};
+
+/// PLL_VE/GPU/HSIC/DE = (24MHz * N) / M, integer mode
+pub const PLL_NM: PllSpec = PllSpec {
+    n: Factor::linear(8, 7),
+    k: None,
+    m: Some(Factor::linear(0, 4)),
+    p: None,
+    post_div: 1,
+    min_rate: ANY_MIN_RATE,
+    max_rate: ANY_MAX_RATE,
+    integer_mode_bit: Some(24),
+    update_bit: None,
+    enable_bits: 0,
+};
+
+/// PLL_DDR0 = (24MHz * N * K) / M
+pub const PLL_DDR0: PllSpec = PllSpec {
+    n: Factor::linear(8, 5),
+    k: Some(Factor::linear(4, 2)),
+    m: Some(Factor::linear(0, 2)),
+    p: None,
+    post_div: 1,
+    min_rate: ANY_MIN_RATE,
+    max_rate: ANY_MAX_RATE,
+    integer_mode_bit: None,
+    update_bit: Some(20),
+    enable_bits: 0,
+};
+
+/// PLL_PERIPH0/1(1X) = (24MHz * N * K) / 2
+pub const PLL_PERIPH: PllSpec = PllSpec {
+    n: Factor::linear(8, 5),
+    k: Some(Factor::linear(4, 2).with_min(2)),
+    m: None,
+    p: None,
+    post_div: 2,
+    min_rate: ANY_MIN_RATE,
+    max_rate: ANY_MAX_RATE,
+    integer_mode_bit: None,
+    update_bit: None,
+    enable_bits: 0,
+};
+
+/// PLL_MIPI = (PLL_VIDEO0(1X) * N * K) / M
+pub const PLL_MIPI: PllSpec = PllSpec {
+    n: Factor::linear(8, 4),
+    k: Some(Factor::linear(4, 2).with_min(2)),
+    m: Some(Factor::linear(0, 4)),
+    p: None,
+    post_div: 1,
+    min_rate: Hertz(500_000_000),
+    max_rate: Hertz(1_400_000_000),
+    integer_mode_bit: None,
+    update_bit: None,
+    // LDO1 and LDO2
+    enable_bits: (1 << 23) | (1 << 22),
+};
+
+/// PLL_DDR1 = (24MHz * N) / M
+pub const PLL_DDR1: PllSpec = PllSpec {
+    n: Factor::linear(8, 7),
+    k: None,
+    m: Some(Factor::linear(0, 2)),
+    p: None,
+    post_div: 1,
+    min_rate: ANY_MIN_RATE,
+    max_rate: ANY_MAX_RATE,
+    integer_mode_bit: None,
+    update_bit: Some(30),
+    enable_bits: 0,
+};
+
+impl PllSpec {
+    /// Mask of all the factor fields
+    pub fn factor_mask(&self) -> u32 {
+        self.n.mask()
+            | self.k.map_or(0, |f| f.mask())
+            | self.m.map_or(0, |f| f.mask())
+            | self.p.map_or(0, |f| f.mask())
+    }
+
+    pub fn decode(&self, reg: u32) -> PllFactors {
+        PllFactors {
+            n: self.n.decode(reg),
+            k: self.k.map_or(1, |f| f.decode(reg)),
+            m: self.m.map_or(1, |f| f.decode(reg)),
+            p: self.p.map_or(1, |f| f.decode(reg)),
+        }
+    }
+
+    /// Encode the factors, in position, without any of the control bits
+    pub fn encode(&self, factors: &PllFactors) -> Result<u32, Error> {
+        fn encode_opt(f: &Option<Factor>, value: u32) -> Result<u32, Error> {
+            match f {
+                Some(f) => f.encode(value),
+                None if value == 1 => Ok(0),
+                None => Err(Error::InvalidFactors),
+            }
+        }
+
+        let mut bits = self.n.encode(factors.n)?;
+        bits |= encode_opt(&self.k, factors.k)?;
+        bits |= encode_opt(&self.m, factors.m)?;
+        bits |= encode_opt(&self.p, factors.p)?;
+        Ok(bits)
+    }
+
+    /// Replace the factor fields of a register value, selecting integer
+    /// mode when the PLL has one
+    pub fn apply(&self, reg: u32, factors: &PllFactors) -> Result<u32, Error> {
+        let mut reg = (reg & !self.factor_mask()) | self.encode(factors)?;
+        if let Some(bit) = self.integer_mode_bit {
+            reg |= 1 << bit;
+        }
+        Ok(reg)
+    }
+
+    /// Output rate for the given factors
+    pub fn rate(&self, parent: Hertz, factors: &PllFactors) -> Hertz {
+        let num = parent.0 as u64 * factors.n as u64 * factors.k as u64;
+        let den = factors.m as u64 * factors.p as u64 * self.post_div as u64;
+        if den == 0 {
+            return Hertz(0);
+        }
+        Hertz((num / den).min(u32::MAX as u64) as u32)
+    }
+
+    /// Whether the pre-P rate lies within the PLL's operating range
+    pub fn in_range(&self, parent: Hertz, factors: &PllFactors) -> bool {
+        if factors.m == 0 {
+            return false;
+        }
+        let vco = parent.0 as u64 * factors.n as u64 * factors.k as u64 / factors.m as u64;
+        vco >= self.min_rate.0 as u64 && vco <= self.max_rate.0 as u64
+    }
+
+    /// Find the factors producing the rate closest to `target`
+    ///
+    /// Ties go to the smallest M, then the smallest P, then the smallest K.
+    /// Returns the factors and the rate they produce, or
+    /// `Error::NoSolution` if the best rate is more than `tolerance` away.
+    pub fn solve(
+        &self,
+        parent: Hertz,
+        target: Hertz,
+        tolerance: Hertz,
+    ) -> Result<(PllFactors, Hertz), Error> {
+        let one = Factor::table(0, 0, &[1]);
+        let k_factor = self.k.unwrap_or(one);
+        let m_factor = self.m.unwrap_or(one);
+        let p_factor = self.p.unwrap_or(one);
+
+        if parent.0 == 0 || target.0 == 0 {
+            return Err(Error::NoSolution);
+        }
+
+        let mut best: Option<(PllFactors, Hertz, u32)> = None;
+
+        for m in m_factor.values() {
+            for p in p_factor.values() {
+                for k in k_factor.values() {
+                    // N = target * M * P * post_div / (parent * K), rounded
+                    let num = target.0 as u64 * m as u64 * p as u64 * self.post_div as u64;
+                    let den = parent.0 as u64 * k as u64;
+                    let n = (num + den / 2) / den;
+                    let n = (n.min(self.n.max_value() as u64) as u32).max(self.n.min);
+
+                    let factors = PllFactors { n, k, m, p };
+                    if !self.in_range(parent, &factors) {
+                        continue;
+                    }
+
+                    let rate = self.rate(parent, &factors);
+                    let diff = if rate.0 > target.0 {
+                        rate.0 - target.0
+                    } else {
+                        target.0 - rate.0
+                    };
+
+                    let better = match best {
+                        Some((_, _, best_diff)) => diff < best_diff,
+                        None => true,
+                    };
+                    if better {
+                        best = Some((factors, rate, diff));
+                    }
+                }
+            }
+        }
+
+        match best {
+            Some((factors, rate, diff)) if diff <= tolerance.0 => Ok((factors, rate)),
+            _ => Err(Error::NoSolution),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OSC24M: Hertz = Hertz(24_000_000);
+
+    fn round_trip(spec: &PllSpec, factors: &PllFactors) {
+        let bits = spec.encode(factors).unwrap();
+        assert_eq!(bits & !spec.factor_mask(), 0);
+        assert_eq!(spec.decode(bits), *factors);
+
+        // Control bits outside the factor fields are kept
+        let reg = spec.apply(1 << ENABLE_BIT, factors).unwrap();
+        assert_ne!(reg & (1 << ENABLE_BIT), 0);
+        assert_eq!(spec.decode(reg), *factors);
+    }
+
+    #[test]
+    fn solve_video_exact() {
+        let (factors, rate) = PLL_VIDEO
+            .solve(OSC24M, Hertz(297_000_000), Hertz(0))
+            .unwrap();
+        assert_eq!(rate.0, 297_000_000);
+        assert_eq!(PLL_VIDEO.rate(OSC24M, &factors), rate);
+        round_trip(&PLL_VIDEO, &factors);
+    }
+
+    #[test]
+    fn solve_cpux_uses_k() {
+        // N alone tops out at 32 * 24MHz
+        let (factors, rate) = PLL_CPUX
+            .solve(OSC24M, Hertz(1_008_000_000), Hertz(0))
+            .unwrap();
+        assert_eq!(rate.0, 1_008_000_000);
+        assert_eq!(factors, PllFactors::new(21, 2, 1, 1));
+        round_trip(&PLL_CPUX, &factors);
+    }
+
+    #[test]
+    fn solve_periph_post_div() {
+        let (factors, rate) = PLL_PERIPH
+            .solve(OSC24M, Hertz(600_000_000), Hertz(0))
+            .unwrap();
+        assert_eq!(rate.0, 600_000_000);
+        assert!(factors.k >= 2);
+        round_trip(&PLL_PERIPH, &factors);
+    }
+
+    #[test]
+    fn solve_audio_fractional() {
+        // 22.5792MHz isn't reachable from 24MHz, the best is 22_577_777Hz
+        let (factors, rate) = PLL_AUDIO
+            .solve(OSC24M, Hertz(22_579_200), Hertz(2_000))
+            .unwrap();
+        assert_eq!(rate.0, 22_577_777);
+        round_trip(&PLL_AUDIO, &factors);
+    }
+
+    #[test]
+    fn decode_encode_all_fields() {
+        for &pll in Pll::ALL.iter() {
+            let spec = pll.spec();
+            let factors = spec.decode(spec.factor_mask());
+            round_trip(spec, &factors);
+        }
+    }
+
+    #[test]
+    fn encode_rejects_out_of_range() {
+        let n = PLL_CPUX.n;
+        assert_eq!(n.encode(0), Err(Error::InvalidFactors));
+        assert_eq!(n.encode(32), Ok(31 << 8));
+        assert_eq!(n.encode(33), Err(Error::InvalidFactors));
+
+        // P is a power of two
+        let p = PLL_CPUX.p.unwrap();
+        assert_eq!(p.encode(4), Ok(2 << 16));
+        assert_eq!(p.encode(3), Err(Error::InvalidFactors));
+
+        // K of PLL_PERIPH can't be 1
+        assert_eq!(
+            PLL_PERIPH.encode(&PllFactors::new(25, 1, 1, 1)),
+            Err(Error::InvalidFactors)
+        );
+
+        // Absent factors must be 1
+        assert_eq!(
+            PLL_VIDEO.encode(&PllFactors::new(99, 1, 8, 2)),
+            Err(Error::InvalidFactors)
+        );
+        assert_eq!(
+            PLL_VIDEO.encode(&PllFactors::new(99, 2, 8, 1)),
+            Err(Error::InvalidFactors)
+        );
+    }
+
+    #[test]
+    fn solve_rejects_out_of_range() {
+        // PLL_VIDEO can't go below 192MHz
+        assert_eq!(
+            PLL_VIDEO.solve(OSC24M, Hertz(100_000_000), Hertz(10_000_000)),
+            Err(Error::NoSolution)
+        );
+        assert_eq!(
+            PLL_VIDEO.solve(OSC24M, Hertz(2_000_000_000), Hertz(10_000_000)),
+            Err(Error::NoSolution)
+        );
+        assert_eq!(
+            PLL_VIDEO.solve(Hertz(0), Hertz(297_000_000), Hertz(0)),
+            Err(Error::NoSolution)
+        );
+    }
+
+    #[test]
+    fn solve_tolerance_edge() {
+        let target = Hertz(193_000_000);
+        let (factors, rate) = PLL_VIDEO.solve(OSC24M, target, Hertz(10_000_000)).unwrap();
+        assert_ne!(rate.0, target.0);
+        let diff = if rate.0 > target.0 {
+            rate.0 - target.0
+        } else {
+            target.0 - rate.0
+        };
+
+        assert_eq!(
+            PLL_VIDEO.solve(OSC24M, target, Hertz(diff)),
+            Ok((factors, rate))
+        );
+        assert_eq!(
+            PLL_VIDEO.solve(OSC24M, target, Hertz(diff - 1)),
+            Err(Error::NoSolution)
+        );
+    }
+}
@@ -4,24 +4,44 @@
 
 use super::{BitsPerPixel, DisplayTiming};
 use crate::ccu::Ccu;
-use crate::pac::ccu::{BusClockGating1, BusSoftReset1, DeClockConfig};
+use crate::pac::ccu::DeClockConfig;
 use crate::pac::de::{BusConfig, GateConfig, ResetConfig, SelConfig};
 use crate::pac::de_mixer::{
+    self,
     bld::{InSize, OutputSize},
     global::{GlobalControl, GlobalSize},
     ui::{Attr, OvlSize, Size},
-    NUM_CHANNELS, NUM_CHANNEL_CONFIGS, NUM_UI_CHANNELS,
+    MIXER0, MIXER1, NUM_CHANNELS, NUM_CHANNEL_CONFIGS, NUM_UI_CHANNELS,
 };
-use crate::pac::{ccu::CCU, de::DE, de_mixer::MIXER1, sysc::SYSC};
+use crate::pac::{ccu::CCU, de::DE, sysc::SYSC};
+use core::ops::DerefMut;
 use embedded_time::rate::Hertz;
 
-pub struct DisplayEngine2 {
-    mixer: MIXER1,
+/// Blender pipe 0 and its fill colour enabled
+const BLD_FCOLOR_CTL_PIPE0: u32 = 0x0000_0101;
+/// Only the fill colour, the background shows
+const BLD_FCOLOR_CTL_BLANK: u32 = 0x0000_0001;
+
+/// MIXER0 feeds TCON0 and MIXER1 feeds TCON1
+pub trait Mixer: DerefMut<Target = de_mixer::RegisterBlock> {
+    const INDEX: usize;
+}
+
+impl Mixer for MIXER0 {
+    const INDEX: usize = 0;
+}
+
+impl Mixer for MIXER1 {
+    const INDEX: usize = 1;
+}
+
+pub struct DisplayEngine2<MIXER> {
+    mixer: MIXER,
     de: DE,
 }
 
-impl DisplayEngine2 {
-    pub(crate) fn new(mixer: MIXER1, de: DE, ccu: &mut Ccu) -> Self {
+impl<MIXER: Mixer> DisplayEngine2<MIXER> {
+    pub(crate) fn new(mixer: MIXER, de: DE, ccu: &mut Ccu) -> Self {
         // Set SRAM for video use
         let sysc = unsafe { &mut *SYSC::mut_ptr() };
         let val = sysc.sram_ctrl.read();
@@ -36,9 +56,7 @@ impl DisplayEngine2 {
         raw_ccu.de_clk_cfg.modify(DeClockConfig::ClockSel::PllDe);
 
         // Set ahb gating to pass
-        ccu.bsr1.rstr().modify(BusSoftReset1::De::Clear);
-        ccu.bsr1.rstr().modify(BusSoftReset1::De::Set);
-        ccu.bcg1.enr().modify(BusClockGating1::De::Set);
+        ccu.enable_and_reset::<DE>();
 
         // Clock on
         raw_ccu.de_clk_cfg.modify(DeClockConfig::SClockGating::Set);
@@ -48,9 +66,15 @@ impl DisplayEngine2 {
 
     pub(crate) fn set_mode(&mut self, fb_addr: u32, bpp: BitsPerPixel, timing: &DisplayTiming) {
         // Enable clock
-        self.de.rst_cfg.modify(ResetConfig::Mux1::Set);
-        self.de.gate_cfg.modify(GateConfig::Mux1::Set);
-        self.de.bus_cfg.modify(BusConfig::Mux1::Set);
+        if MIXER::INDEX == 0 {
+            self.de.rst_cfg.modify(ResetConfig::Mux0::Set);
+            self.de.gate_cfg.modify(GateConfig::Mux0::Set);
+            self.de.bus_cfg.modify(BusConfig::Mux0::Set);
+        } else {
+            self.de.rst_cfg.modify(ResetConfig::Mux1::Set);
+            self.de.gate_cfg.modify(GateConfig::Mux1::Set);
+            self.de.bus_cfg.modify(BusConfig::Mux1::Set);
+        }
 
         self.de.sel_cfg.modify(SelConfig::Bit0::Clear);
 
@@ -115,7 +139,7 @@ impl DisplayEngine2 {
         }
         self.mixer.bld.out_ctl.write(0);
 
-        self.mixer.bld.fcolor_ctl.write(0x00000101);
+        self.mixer.bld.fcolor_ctl.write(BLD_FCOLOR_CTL_PIPE0);
         self.mixer.bld.route.write(1);
         self.mixer.bld.premultiply.write(0);
         self.mixer.bld.bkcolor.write(0xff000000);
@@ -178,4 +202,20 @@ impl DisplayEngine2 {
         // Apply settings
         self.mixer.global.dbuf.write(1);
     }
+    /// Scan out from `fb_addr` from the next frame on
+    pub(crate) fn set_framebuffer(&mut self, fb_addr: u32) {
+        self.mixer.ui[0].cfg[0].top_laddr.write(fb_addr);
+        self.mixer.global.dbuf.write(1);
+    }
+
+    /// Show the black background instead of the UI layer
+    pub(crate) fn set_blanking(&mut self, blank: bool) {
+        let ctl = if blank {
+            BLD_FCOLOR_CTL_BLANK
+        } else {
+            BLD_FCOLOR_CTL_PIPE0
+        };
+        self.mixer.bld.fcolor_ctl.write(ctl);
+        self.mixer.global.dbuf.write(1);
+    }
 }
@@ -1,8 +1,13 @@
 //! DesignWare HDMI bridge
 
+use super::hdmi::audio::{self, AudioClock, AudioConfig, SampleWidth};
+use super::hdmi::color::{ColorDepth, ColorFormat, Colorimetry, OutputFormat, Quantization};
+use super::hdmi::edid::EdidError;
+use super::hdmi::Error;
+use super::modes::{self, PictureAspect};
 use super::DisplayTiming;
-use crate::ccu::Ccu;
-use crate::delay::delay_ms;
+use crate::ccu::{Ccu, Pll, PllFactors};
+use crate::delay::{delay_ms, delay_us};
 use crate::pac::hdmi::{self, PhyPll, HDMI};
 
 // TODO
@@ -12,7 +17,6 @@ use crate::pac::hdmi::{self, PhyPll, HDMI};
 
 pub const HDMI_EDID_BLOCK_SIZE: usize = 128;
 
-const BLOCK_0: usize = 0;
 const I2C_CLK_HIGH: u8 = 0xD8;
 const I2C_CLK_LOW: u8 = 0xFE;
 const DIV_STD_MODE: u8 = 0x00;
@@ -20,10 +24,16 @@ const DIV_FAST_STD_MODE: u8 = 0x08;
 const SLAVE_DDC_ADDR: u8 = 0x50;
 const SEGADDR_DDC: u8 = 0x30;
 const OP_RD8: u8 = 0x01;
+const OP_RD8_EXT: u8 = 0x02;
+const I2CM_STAT0_ERROR: u8 = 0x01;
+const I2CM_STAT0_DONE: u8 = 0x02;
+/// Per byte, a 100 kHz read takes well under a millisecond
+const I2CM_TIMEOUT_MS: usize = 10;
 
 const TX_INVID0_INTERNAL_DE_GENERATOR_DISABLE: u8 = 0x00;
 const TX_INVID0_VIDEO_MAPPING_MASK: u8 = 0x1F;
 const TX_INVID0_VIDEO_MAPPING_OFFSET: u8 = 0x00;
+const TX_INVID0_VIDEO_MAPPING_RGB888: u8 = 0x01;
 
 const TX_INSTUFFING_BDBDATA_STUFFING_ENABLE: u8 = 0x4;
 const TX_INSTUFFING_RCRDATA_STUFFING_ENABLE: u8 = 0x02;
@@ -43,16 +53,21 @@ const VP_STUFF_PR_STUFFING_STUFFING_MODE: u8 = 0x01;
 const VP_STUFF_PP_STUFFING_STUFFING_MODE: u8 = 0x02;
 const VP_STUFF_YCC422_STUFFING_STUFFING_MODE: u8 = 0x04;
 
+const VP_CONF_OUTPUT_SELECTOR_PP: u8 = 0x00;
+const VP_CONF_OUTPUT_SELECTOR_YCC422: u8 = 0x01;
 const VP_CONF_OUTPUT_SELECTOR_BYPASS: u8 = 0x03;
 const VP_CONF_PP_EN_ENMASK: u8 = 0x20;
 const VP_CONF_BYPASS_EN_MASK: u8 = 0x40;
 const VP_CONF_PP_EN_DISABLE: u8 = 0x00;
+const VP_CONF_PP_EN_ENABLE: u8 = 0x20;
+const VP_CONF_BYPASS_EN_DISABLE: u8 = 0x00;
 const VP_CONF_BYPASS_EN_ENABLE: u8 = 0x40;
 const VP_CONF_PR_EN_DISABLE: u8 = 0x00;
 const VP_CONF_PR_EN_MASK: u8 = 0x10;
 const VP_CONF_BYPASS_SELECT_VID_PACKETIZER: u8 = 0x04;
 const VP_CONF_YCC422_EN_MASK: u8 = 0x08;
 const VP_CONF_YCC422_EN_DISABLE: u8 = 0x00;
+const VP_CONF_YCC422_EN_ENABLE: u8 = 0x08;
 const VP_CONF_BYPASS_SELECT_MASK: u8 = 0x04;
 const VP_CONF_OUTPUT_SELECTOR_MASK: u8 = 0x03;
 
@@ -70,10 +85,107 @@ const FC_INVIDCONF_DVI_MODEZ_DVI_MODE: u8 = 0x00;
 const FC_INVIDCONF_R_V_BLANK_IN_OSC_ACTIVE_LOW: u8 = 0x00;
 const FC_INVIDCONF_IN_I_P_PROGRESSIVE: u8 = 0x0;
 
+const FC_AVICONF0_PIX_FMT_RGB: u8 = 0x00;
+const FC_AVICONF0_PIX_FMT_YCBCR422: u8 = 0x01;
+const FC_AVICONF0_PIX_FMT_YCBCR444: u8 = 0x02;
+const FC_AVICONF0_ACTIVE_FMT_INFO_PRESENT: u8 = 0x40;
+const FC_AVICONF1_PIC_ASPECT_RATIO_4_3: u8 = 0x10;
+const FC_AVICONF1_PIC_ASPECT_RATIO_16_9: u8 = 0x20;
+const FC_AVICONF1_ACTIVE_ASPECT_SAME_AS_PIC: u8 = 0x08;
+const FC_AVICONF1_COLORIMETRY_NO_DATA: u8 = 0x00;
+const FC_AVICONF1_COLORIMETRY_ITUR601: u8 = 0x40;
+const FC_AVICONF1_COLORIMETRY_ITUR709: u8 = 0x80;
+const FC_AVICONF2_RGB_QUANT_LIMITED_RANGE: u8 = 0x04;
+const FC_AVICONF2_RGB_QUANT_FULL_RANGE: u8 = 0x08;
+const FC_AVICONF2_IT_CONTENT_VALID: u8 = 0x80;
+const FC_AVICONF3_QUANT_RANGE_LIMITED: u8 = 0x00;
+const FC_AVICONF3_QUANT_RANGE_FULL: u8 = 0x04;
+const FC_AVIVID_MASK: u8 = 0x7F;
+
+const FC_GCP_CLEAR_AVMUTE: u8 = 0x01;
+
+const FC_AUDICONF0_CC_OFFSET: u8 = 4;
+const FC_AUDSCONF_LAYOUT1: u8 = 0x01;
+const FC_AUDSCHNLS8_WORDLENGTH_16: u8 = 0x02;
+const FC_AUDSCHNLS8_WORDLENGTH_24: u8 = 0x0B;
+const FC_AUDSCHNLS8_ORIGSAMPFREQ_OFFSET: u8 = 4;
+
+const FC_DATAUTO0_VSD_MASK: u8 = 0x08;
+const FC_DATAUTO0_SPD_MASK: u8 = 0x10;
+
+const HDMI_IEEE_OUI: u32 = 0x000C03;
+const HDMI_VSI_FORMAT_NONE: u8 = 0x00;
+const HDMI_VSI_FORMAT_EXTENDED: u8 = 0x20;
+
+const SPD_VENDOR_NAME: &[u8] = b"Pine64";
+const SPD_PRODUCT_NAME: &[u8] = b"Pine A64";
+const SPD_VENDOR_NAME_LEN: usize = 8;
+const SPD_PRODUCT_NAME_LEN: usize = 16;
+const SPD_SOURCE_PC_GENERAL: u8 = 0x09;
+
 const MC_FLOWCTRL_FEED_THROUGH_OFF_CSC_BYPASS: u8 = 0x00;
+const MC_FLOWCTRL_FEED_THROUGH_OFF_CSC_IN_PATH: u8 = 0x01;
 const MC_CLKDIS_PIXELCLK_DISABLE: u8 = 0x01;
 const MC_CLKDIS_TMDSCLK_DISABLE: u8 = 0x02;
+const MC_CLKDIS_AUDCLK_DISABLE: u8 = 0x08;
+const MC_CLKDIS_CSCCLK_DISABLE: u8 = 0x10;
 const MC_SWRSTZ_TMDSSWRST_REQ: u8 = 0x02;
+const MC_SWRSTZ_I2SSWRST_REQ: u8 = 0x08;
+
+const CSC_CFG_DECMODE_CHROMA_INT_FORMULA3: u8 = 0x03;
+const CSC_SCALE_CSCSCALE_MASK: u8 = 0x03;
+const CSC_SCALE_COLOR_DEPTH_MASK: u8 = 0xF0;
+const CSC_SCALE_COLOR_DEPTH_24BPP: u8 = 0x00;
+const CSC_SCALE_COLOR_DEPTH_30BPP: u8 = 0x50;
+const CSC_SCALE_COLOR_DEPTH_36BPP: u8 = 0x60;
+
+/// Rows produce G/Y, R/Cr and B/Cb from G, R, B and an offset.
+/// Coefficients are 15-bit two's complement, in 1/8192 units with a CSC
+/// scale of 1 and 1/16384 units with 0.
+type CscCoefficients = [[u16; 4]; 3];
+
+#[rustfmt::skip]
+const CSC_RGB_FULL_TO_LIMITED: CscCoefficients = [
+    [0x1B7C, 0x0000, 0x0000, 0x0020],
+    [0x0000, 0x1B7C, 0x0000, 0x0020],
+    [0x0000, 0x0000, 0x1B7C, 0x0020],
+];
+
+#[rustfmt::skip]
+const CSC_RGB_TO_BT601_FULL: CscCoefficients = [
+    [0x2591, 0x1322, 0x074B, 0x0000],
+    [0x6535, 0x2000, 0x7ACC, 0x0200],
+    [0x6ACD, 0x7534, 0x2000, 0x0200],
+];
+
+#[rustfmt::skip]
+const CSC_RGB_TO_BT601_LIMITED: CscCoefficients = [
+    [0x2044, 0x106F, 0x0644, 0x0040],
+    [0x6876, 0x1C1C, 0x7B6E, 0x0200],
+    [0x6D60, 0x7684, 0x1C1C, 0x0200],
+];
+
+#[rustfmt::skip]
+const CSC_RGB_TO_BT709_FULL: CscCoefficients = [
+    [0x2DC5, 0x0D9B, 0x049E, 0x0000],
+    [0x62F0, 0x2000, 0x7D11, 0x0200],
+    [0x6756, 0x78AB, 0x2000, 0x0200],
+];
+
+#[rustfmt::skip]
+const CSC_RGB_TO_BT709_LIMITED: CscCoefficients = [
+    [0x2750, 0x0BAF, 0x03F8, 0x0040],
+    [0x6678, 0x1C1C, 0x7D6C, 0x0200],
+    [0x6A55, 0x798F, 0x1C1C, 0x0200],
+];
+
+const UNSCRAMBLE_KEY: u32 = 0x42494E47;
+
+const AUD_CONF0_SW_RESET: u8 = 0x80;
+const AUD_CONF0_I2S_SELECT: u8 = 0x20;
+const AUD_CONF1_MODE_I2S: u8 = 0x00;
+const AUD_CTS3_CTS_MANUAL: u8 = 0x10;
+const AUD_INPUTCLKFS_64FS: u8 = 0x04;
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub enum HdmiReg {
@@ -122,9 +234,57 @@ pub enum HdmiReg {
     FcCh0Pream,
     FcCh1Pream,
     FcCh2Pream,
+    FcAviConf3,
+    FcGcp,
+    FcAviConf0,
+    FcAviConf1,
+    FcAviConf2,
+    FcAviVid,
+    FcAviEtb0,
+    FcAviEtb1,
+    FcAviSbb0,
+    FcAviSbb1,
+    FcAviElb0,
+    FcAviElb1,
+    FcAviSrb0,
+    FcAviSrb1,
+    FcAudiConf0,
+    FcAudiConf1,
+    FcAudiConf2,
+    FcAudiConf3,
+    FcVsdIeeeId2,
+    FcVsdSize,
+    FcVsdIeeeId1,
+    FcVsdIeeeId0,
+    /// 24 bytes
+    FcVsdPayload(u8),
+    /// 8 bytes
+    FcSpdVendorName(u8),
+    /// 16 bytes
+    FcSpdProductName(u8),
+    FcSpdDeviceInf,
+    FcAudsConf,
+    /// 9 bytes of IEC 60958 channel status
+    FcAudsChnls(u8),
+    FcDatAuto0,
+    FcDatAuto1,
+    FcDatAuto2,
     McFlowCtrl,
     McClkDis,
     McSwRstz,
+    CscCfg,
+    CscScale,
+    /// 24 bytes, MSB first
+    CscCoef(u8),
+    AudConf0,
+    AudConf1,
+    AudN1,
+    AudN2,
+    AudN3,
+    AudCts1,
+    AudCts2,
+    AudCts3,
+    AudInputClkFs,
 }
 
 impl HdmiReg {
@@ -176,9 +336,144 @@ impl HdmiReg {
             FcCh0Pream => 0x1014,
             FcCh1Pream => 0x1015,
             FcCh2Pream => 0x1016,
+            FcAviConf3 => 0x1017,
+            FcGcp => 0x1018,
+            FcAviConf0 => 0x1019,
+            FcAviConf1 => 0x101A,
+            FcAviConf2 => 0x101B,
+            FcAviVid => 0x101C,
+            FcAviEtb0 => 0x101D,
+            FcAviEtb1 => 0x101E,
+            FcAviSbb0 => 0x101F,
+            FcAviSbb1 => 0x1020,
+            FcAviElb0 => 0x1021,
+            FcAviElb1 => 0x1022,
+            FcAviSrb0 => 0x1023,
+            FcAviSrb1 => 0x1024,
+            FcAudiConf0 => 0x1025,
+            FcAudiConf1 => 0x1026,
+            FcAudiConf2 => 0x1027,
+            FcAudiConf3 => 0x1028,
+            FcVsdIeeeId2 => 0x1029,
+            FcVsdSize => 0x102A,
+            FcVsdIeeeId1 => 0x1030,
+            FcVsdIeeeId0 => 0x1031,
+            FcVsdPayload(n) => 0x1032 + usize::from(n),
+            FcSpdVendorName(n) => 0x104A + usize::from(n),
+            FcSpdProductName(n) => 0x1052 + usize::from(n),
+            FcSpdDeviceInf => 0x1062,
+            FcAudsConf => 0x1063,
+            FcAudsChnls(n) => 0x1067 + usize::from(n),
+            FcDatAuto0 => 0x10B3,
+            FcDatAuto1 => 0x10B4,
+            FcDatAuto2 => 0x10B5,
             McFlowCtrl => 0x4004,
             McClkDis => 0x4001,
             McSwRstz => 0x4002,
+            CscCfg => 0x4100,
+            CscScale => 0x4101,
+            CscCoef(n) => 0x4102 + usize::from(n),
+            AudConf0 => 0x3100,
+            AudConf1 => 0x3101,
+            AudN1 => 0x3200,
+            AudN2 => 0x3201,
+            AudN3 => 0x3202,
+            AudCts1 => 0x3203,
+            AudCts2 => 0x3204,
+            AudCts3 => 0x3205,
+            AudInputClkFs => 0x3206,
+        }
+    }
+}
+
+/// HDMI 1.4 VIC of the 4K formats, sent in the vendor specific InfoFrame
+fn hdmi_vic(vic: u8) -> Option<u8> {
+    match vic {
+        95 => Some(1),
+        94 => Some(2),
+        93 => Some(3),
+        _ => None,
+    }
+}
+
+fn avi_colorimetry(colorimetry: Colorimetry) -> u8 {
+    match colorimetry {
+        Colorimetry::Bt601 => FC_AVICONF1_COLORIMETRY_ITUR601,
+        Colorimetry::Bt709 => FC_AVICONF1_COLORIMETRY_ITUR709,
+    }
+}
+
+/// Whether PLL_VIDEO0 can produce `clock`, in Hz, for the TMDS lanes and
+/// still divide down to the pixel clock for TCON1
+pub(crate) fn tmds_clock_supported(clock: u32, depth: ColorDepth) -> bool {
+    pll_search(clock / 1000, depth).is_some()
+}
+
+/// PHY divider and PLL_VIDEO0 N and M for a TMDS clock of `clk_khz`
+///
+/// TCON1 divides PLL_VIDEO0 by at most 16 for the pixel clock, with deep
+/// colour the PHY divider must keep that a whole number.
+fn pll_search(clk_khz: u32, depth: ColorDepth) -> Option<(u32, u32, u32)> {
+    // Divider step and the TMDS to pixel clock ratio
+    let (step, num, den) = match depth {
+        ColorDepth::Bits8 => (1, 1, 1),
+        ColorDepth::Bits10 => (4, 5, 4),
+        ColorDepth::Bits12 => (2, 3, 2),
+    };
+
+    let mut best = None;
+    let mut best_diff = 0x0FFFFFFF;
+
+    for div in (step..=16).step_by(step as usize) {
+        let target = clk_khz * div;
+
+        if target < 192000 {
+            continue;
+        }
+        if target > 912000 {
+            continue;
+        }
+        if div * num / den > 16 {
+            continue;
+        }
+
+        for m in 1..=16 {
+            let n = (m * target) / 24000;
+
+            if (1..=128).contains(&n) {
+                let value = (24000 * n) / m / div;
+                let diff = clk_khz - value;
+                if diff < best_diff {
+                    best_diff = diff;
+                    best = Some((div, n, m));
+                }
+            }
+        }
+    }
+
+    best
+}
+
+/// PHY clock divider by pixel clock band
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+enum PhyDivider {
+    Div1,
+    Div2,
+    Div4,
+    Div11,
+}
+
+impl PhyDivider {
+    fn for_clock(clock: u32) -> Self {
+        // No docs...
+        if clock <= 27000000 {
+            PhyDivider::Div11
+        } else if clock <= 74250000 {
+            PhyDivider::Div4
+        } else if clock <= 148500000 {
+            PhyDivider::Div2
+        } else {
+            PhyDivider::Div1
         }
     }
 }
@@ -205,32 +500,142 @@ impl DwHdmi {
         h
     }
 
-    pub(crate) fn enable(&mut self, timing: &DisplayTiming, ccu: &mut Ccu) {
+    /// Descramble the register offsets, needed before accessing the
+    /// controller
+    pub(crate) fn unlock(&mut self) {
+        self.hdmi.phy_unscramble.write(UNSCRAMBLE_KEY);
+    }
+
+    /// Scramble the register offsets again or otherwise the BSP driver
+    /// won't work. Dummy read is needed or otherwise last write doesn't get
+    /// written correctly.
+    pub(crate) fn lock(&mut self) {
+        let _ = self.hdmi.version.read();
+        self.hdmi.phy_unscramble.write(0);
+    }
+
+    /// `vic` is the CEA-861 code sent in the AVI InfoFrame, only used
+    /// when `timing.hdmi_monitor` is set
+    pub(crate) fn enable(
+        &mut self,
+        timing: &DisplayTiming,
+        vic: Option<u8>,
+        format: &OutputFormat,
+        ccu: &mut Ccu,
+    ) -> Result<(), Error> {
+        // Nothing is touched for a clock PLL_VIDEO0 can't produce
+        let tmds_clock = format.tmds_clock(timing.pixel_clock.typ);
+        if !tmds_clock_supported(tmds_clock, format.depth) {
+            return Err(Error::UnsupportedMode);
+        }
+
         self.av_composer(timing);
 
-        self.phy_cfg(timing.pixel_clock.typ, ccu);
+        self.phy_cfg(tmds_clock, format.depth, ccu)?;
 
-        self.enable_video_path();
+        self.enable_video_path(format);
 
-        assert_eq!(timing.hdmi_monitor, false, "TODO - only DVI for now");
+        if timing.hdmi_monitor {
+            self.config_avi(vic, format);
+            self.config_vendor_specific(vic);
+            self.config_spd();
+            self.config_audio_infoframe();
+            self.hdmi_write(HdmiReg::FcGcp, FC_GCP_CLEAR_AVMUTE);
+        }
 
-        self.video_packetize();
-        self.video_csc();
+        self.video_packetize(format);
+        self.video_csc(format);
         self.video_sample();
 
         self.clear_overflow();
+        Ok(())
     }
 
-    pub(crate) fn read_edid_block(&mut self, edid_block: &mut [u8; HDMI_EDID_BLOCK_SIZE]) {
-        self.read_edid(BLOCK_0, edid_block);
+    /// Route I2S2 into the audio sampler, set up the clock regeneration
+    /// packets and describe the stream in the audio InfoFrame
+    pub(crate) fn enable_audio(
+        &mut self,
+        tmds_clock: u32,
+        config: &AudioConfig,
+        clock: &AudioClock,
+    ) {
+        use HdmiReg::*;
 
-        if edid_block[0x7E] != 0 {
-            todo!("read EDID ext block")
-        }
+        // Reset the sampler FIFO before changing the format
+        self.hdmi_write(AudConf0, AUD_CONF0_SW_RESET);
+        self.hdmi_write(McSwRstz, !MC_SWRSTZ_I2SSWRST_REQ);
+
+        let lines = (1 << config.lines()) - 1;
+        let conf0 = AUD_CONF0_I2S_SELECT | lines;
+        let conf1 = AUD_CONF1_MODE_I2S | config.width().bits();
+
+        // IEC 60958 channel status, sample rate and word length
+        let rate = config.rate().iec_code();
+        self.hdmi_write(FcAudsChnls(7), rate);
+        let word_length = match config.width() {
+            SampleWidth::Bits16 => FC_AUDSCHNLS8_WORDLENGTH_16,
+            SampleWidth::Bits24 => FC_AUDSCHNLS8_WORDLENGTH_24,
+        };
+        self.hdmi_write(
+            FcAudsChnls(8),
+            (!rate << FC_AUDSCHNLS8_ORIGSAMPFREQ_OFFSET) | word_length,
+        );
+
+        let layout = if config.channels() > 2 {
+            FC_AUDSCONF_LAYOUT1
+        } else {
+            0
+        };
+        self.hdmi_write(FcAudsConf, layout);
+
+        // Coding type, sample size and rate refer to the stream header
+        self.hdmi_write(
+            FcAudiConf0,
+            (config.channels() - 1) << FC_AUDICONF0_CC_OFFSET,
+        );
+        self.hdmi_write(FcAudiConf1, 0);
+        self.hdmi_write(FcAudiConf2, config.channel_allocation());
+        self.hdmi_write(FcAudiConf3, 0);
+
+        self.hdmi_write(AudInputClkFs, AUD_INPUTCLKFS_64FS);
+        self.hdmi_write(AudConf0, conf0);
+        self.hdmi_write(AudConf1, conf1);
+
+        let n = audio::audio_n(tmds_clock, config.rate());
+        let cts = audio::audio_cts(tmds_clock, n, clock);
+        self.set_cts_n(cts, n);
+
+        self.hdmi_mod(McClkDis, MC_CLKDIS_AUDCLK_DISABLE, 0);
+    }
+
+    pub(crate) fn disable_audio(&mut self) {
+        use HdmiReg::*;
+
+        self.hdmi_mod(McClkDis, MC_CLKDIS_AUDCLK_DISABLE, MC_CLKDIS_AUDCLK_DISABLE);
+        self.set_cts_n(0, 0);
+        self.config_audio_infoframe();
     }
 
-    // TODO - timeout
-    fn read_edid(&mut self, block_index: usize, edid_block: &mut [u8; HDMI_EDID_BLOCK_SIZE]) {
+    fn set_cts_n(&mut self, cts: u32, n: u32) {
+        use HdmiReg::*;
+
+        // The manual CTS bit must change before the values
+        self.hdmi_mod(AudCts3, AUD_CTS3_CTS_MANUAL, 0);
+        self.hdmi_write(AudCts3, ((cts >> 16) as u8 & 0x0F) | AUD_CTS3_CTS_MANUAL);
+        self.hdmi_write(AudCts2, (cts >> 8) as u8);
+        self.hdmi_write(AudCts1, cts as u8);
+
+        self.hdmi_write(AudN3, (n >> 16) as u8 & 0x0F);
+        self.hdmi_write(AudN2, (n >> 8) as u8);
+        self.hdmi_write(AudN1, n as u8);
+    }
+
+    /// `EdidError::Ddc` when the sink doesn't answer or NACKs a byte
+    pub(crate) fn read_edid(
+        &mut self,
+        block_index: usize,
+        edid_block: &mut [u8; HDMI_EDID_BLOCK_SIZE],
+    ) -> Result<(), EdidError> {
         use HdmiReg::*;
 
         // Set ddc i2c clk which devided from ddc_clk to 100khz
@@ -240,108 +645,75 @@ impl DwHdmi {
 
         self.hdmi_write(I2cmSlave, SLAVE_DDC_ADDR);
         self.hdmi_write(I2cmSegAddr, SEGADDR_DDC);
-        self.hdmi_write(I2cmSegPtr, 0);
-        if block_index != 0 {
-            todo!("block >> 1");
-        }
+        // Two blocks per 256 byte segment
+        self.hdmi_write(I2cmSegPtr, (block_index >> 1) as u8);
+        let shift = (block_index & 1) * HDMI_EDID_BLOCK_SIZE;
+        let op = if block_index == 0 { OP_RD8 } else { OP_RD8_EXT };
 
-        for n in 0..HDMI_EDID_BLOCK_SIZE {
-            self.hdmi_write(I2cmAddr, n as u8);
+        for (n, byte) in edid_block.iter_mut().enumerate() {
+            self.hdmi_write(I2cmAddr, (shift + n) as u8);
 
-            self.hdmi_write(I2cmOp, OP_RD8);
+            self.hdmi_write(I2cmOp, op);
 
-            self.wait_i2c_done(10);
+            self.wait_i2c_done(I2CM_TIMEOUT_MS)?;
 
-            edid_block[n] = self.hdmi_read(I2cmDataI);
+            *byte = self.hdmi_read(I2cmDataI);
         }
+        Ok(())
     }
 
-    fn phy_cfg(&mut self, mpixel_clock: u32, ccu: &mut Ccu) {
-        let phy_div = self.pll_set(mpixel_clock / 1000, ccu);
-        self.phy_set(mpixel_clock, phy_div);
+    fn phy_cfg(&mut self, tmds_clock: u32, depth: ColorDepth, ccu: &mut Ccu) -> Result<(), Error> {
+        let phy_div = self.pll_set(tmds_clock / 1000, depth, ccu)?;
+        self.phy_set(tmds_clock, phy_div);
+        Ok(())
     }
 
     fn phy_set(&mut self, clock: u32, phy_div: u32) {
-        let div = Self::get_phy_divider(clock);
-
-        // No docs...
-        match div {
-            2 => {
-                self.hdmi.phy_pll.write(0x39dc5040);
-                self.hdmi.phy_clk.write(0x80084380 | (phy_div - 1));
-                delay_ms(10);
-                self.hdmi.phy_unk3.write(0x00000001);
-                self.hdmi.phy_pll.modify(PhyPll::B25::Set);
-                delay_ms(100);
-                let tmp = (self.hdmi.phy_status.read() & 0x1_F800) >> 11;
-                self.hdmi
-                    .phy_pll
-                    .modify(PhyPll::B31::Set + PhyPll::B30::Set);
-                self.hdmi
-                    .phy_pll
-                    .modify(PhyPll::F0::Field::new(tmp).unwrap());
-                self.hdmi.phy_ctrl.write(0x01FFFF7F);
-                self.hdmi.phy_unk1.write(0x8063a800);
-                self.hdmi.phy_unk2.write(0x0F81C485);
-            }
-            _ => unimplemented!(), // TODO - use an enum for exh match
+        let div = PhyDivider::for_clock(clock);
+
+        // No docs, the values are from the Allwinner BSP
+        let (pll, clk) = match div {
+            PhyDivider::Div1 => (0x30dc5fc0, 0x800863C0),
+            PhyDivider::Div2 => (0x39dc5040, 0x80084380),
+            PhyDivider::Div4 => (0x39dc5040, 0x80084340),
+            PhyDivider::Div11 => (0x39dc5040, 0x80084300),
+        };
+        self.hdmi.phy_pll.write(pll);
+        self.hdmi.phy_clk.write(clk | (phy_div - 1));
+        delay_ms(10);
+        self.hdmi.phy_unk3.write(0x00000001);
+        self.hdmi.phy_pll.modify(PhyPll::B25::Set);
+        delay_ms(if div == PhyDivider::Div1 { 200 } else { 100 });
+        let mut tmp = (self.hdmi.phy_status.read() & 0x1_F800) >> 11;
+        self.hdmi
+            .phy_pll
+            .modify(PhyPll::B31::Set + PhyPll::B30::Set);
+        if div == PhyDivider::Div1 {
+            tmp = (tmp + 2).min(0x3F);
         }
-    }
-
-    fn pll_set(&mut self, clk_khz: u32, ccu: &mut Ccu) -> u32 {
-        let mut best_div = 0;
-        let mut best_n = 0;
-        let mut best_m = 0;
-        let mut best_diff = 0x0FFFFFFF;
-
-        for div in 1..=16 {
-            let target = clk_khz * div;
-
-            if target < 192000 {
-                continue;
-            }
-            if target > 912000 {
-                continue;
-            }
-
-            for m in 1..=16 {
-                let n = (m * target) / 24000;
-
-                if (n >= 1) && (n <= 128) {
-                    let value = (24000 * n) / m / div;
-                    let diff = clk_khz - value;
-                    if diff < best_diff {
-                        best_diff = diff;
-                        best_m = m;
-                        best_n = n;
-                        best_div = div;
-                    }
-                }
-            }
+        self.hdmi
+            .phy_pll
+            .modify(PhyPll::F0::Field::new(tmp).unwrap());
+        if div == PhyDivider::Div1 {
+            delay_ms(100);
+        }
+        self.hdmi.phy_ctrl.write(0x01FFFF7F);
+        if div == PhyDivider::Div1 {
+            self.hdmi.phy_unk1.write(0x8063b000);
+            self.hdmi.phy_unk2.write(0x0F8246B5);
+        } else {
+            self.hdmi.phy_unk1.write(0x8063a800);
+            self.hdmi.phy_unk2.write(0x0F81C485);
         }
+    }
 
-        // TODO
-        //assert_ne!(best_diff, 0);
-        assert_ne!(best_div, 0);
-        assert_ne!(best_m, 0);
-        assert_ne!(best_n, 0);
+    fn pll_set(&mut self, clk_khz: u32, depth: ColorDepth, ccu: &mut Ccu) -> Result<u32, Error> {
+        let (div, n, m) = pll_search(clk_khz, depth).ok_or(Error::UnsupportedMode)?;
 
-        ccu.set_pll_video0_factors(best_m, best_n);
+        ccu.set_pll_factors(Pll::Video0, PllFactors::nm(n, m))
+            .map_err(|_| Error::UnsupportedMode)?;
 
-        best_div
-    }
-
-    fn get_phy_divider(clock: u32) -> u32 {
-        // No docs...
-        if clock <= 27000000 {
-            11
-        } else if clock <= 74250000 {
-            4
-        } else if clock <= 148500000 {
-            2
-        } else {
-            1
-        }
+        Ok(div)
     }
 
     fn av_composer(&mut self, timing: &DisplayTiming) {
@@ -414,7 +786,119 @@ impl DwHdmi {
         self.hdmi_write(FcVSyncInWidth, timing.vsync_len.typ as u8);
     }
 
-    fn enable_video_path(&mut self) {
+    fn config_avi(&mut self, vic: Option<u8>, format: &OutputFormat) {
+        use HdmiReg::*;
+
+        // HDMI 1.4 sinks expect the 4K formats in the vendor specific
+        // InfoFrame instead
+        let avi_vic = vic.filter(|vic| hdmi_vic(*vic).is_none()).unwrap_or(0);
+        let aspect = match vic.and_then(modes::picture_aspect) {
+            Some(PictureAspect::FourThree) => FC_AVICONF1_PIC_ASPECT_RATIO_4_3,
+            Some(PictureAspect::SixteenNine) => FC_AVICONF1_PIC_ASPECT_RATIO_16_9,
+            None => 0,
+        };
+
+        let (pix_fmt, colorimetry) = match (format.color, format.colorimetry) {
+            (ColorFormat::Rgb, _) => (FC_AVICONF0_PIX_FMT_RGB, FC_AVICONF1_COLORIMETRY_NO_DATA),
+            (ColorFormat::YCbCr444, c) => (FC_AVICONF0_PIX_FMT_YCBCR444, avi_colorimetry(c)),
+            (ColorFormat::YCbCr422, c) => (FC_AVICONF0_PIX_FMT_YCBCR422, avi_colorimetry(c)),
+        };
+        self.hdmi_write(FcAviConf0, pix_fmt | FC_AVICONF0_ACTIVE_FMT_INFO_PRESENT);
+        self.hdmi_write(
+            FcAviConf1,
+            colorimetry | aspect | FC_AVICONF1_ACTIVE_ASPECT_SAME_AS_PIC,
+        );
+
+        // Explicit rather than left to the CE/IT format default
+        let (rgb_quant, ycc_quant) = match format.quantization {
+            Quantization::Full => (
+                FC_AVICONF2_RGB_QUANT_FULL_RANGE,
+                FC_AVICONF3_QUANT_RANGE_FULL,
+            ),
+            Quantization::Limited => (
+                FC_AVICONF2_RGB_QUANT_LIMITED_RANGE,
+                FC_AVICONF3_QUANT_RANGE_LIMITED,
+            ),
+        };
+        let mut conf2 = 0;
+        let mut conf3 = 0;
+        if format.color == ColorFormat::Rgb {
+            conf2 |= rgb_quant;
+        } else {
+            conf3 |= ycc_quant;
+        }
+        if vic.is_none() {
+            conf2 |= FC_AVICONF2_IT_CONTENT_VALID;
+        }
+        self.hdmi_write(FcAviConf2, conf2);
+        self.hdmi_write(FcAviConf3, conf3);
+        self.hdmi_write(FcAviVid, avi_vic & FC_AVIVID_MASK);
+
+        // No bars
+        for reg in [
+            FcAviEtb0, FcAviEtb1, FcAviSbb0, FcAviSbb1, FcAviElb0, FcAviElb1, FcAviSrb0, FcAviSrb1,
+        ]
+        .iter()
+        {
+            self.hdmi_write(*reg, 0);
+        }
+    }
+
+    fn config_vendor_specific(&mut self, vic: Option<u8>) {
+        use HdmiReg::*;
+
+        self.hdmi_mod(FcDatAuto0, FC_DATAUTO0_VSD_MASK, 0);
+
+        let payload: &[u8] = match vic.and_then(hdmi_vic) {
+            Some(hdmi_vic) => &[HDMI_VSI_FORMAT_EXTENDED, hdmi_vic],
+            None => &[HDMI_VSI_FORMAT_NONE],
+        };
+
+        // Length includes the IEEE OUI
+        self.hdmi_write(FcVsdSize, 3 + payload.len() as u8);
+        self.hdmi_write(FcVsdIeeeId0, HDMI_IEEE_OUI as u8);
+        self.hdmi_write(FcVsdIeeeId1, (HDMI_IEEE_OUI >> 8) as u8);
+        self.hdmi_write(FcVsdIeeeId2, (HDMI_IEEE_OUI >> 16) as u8);
+        for (n, b) in payload.iter().enumerate() {
+            self.hdmi_write(FcVsdPayload(n as u8), *b);
+        }
+
+        // Once per frame
+        self.hdmi_write(FcDatAuto1, 1);
+        self.hdmi_write(FcDatAuto2, 0x11);
+        self.hdmi_mod(FcDatAuto0, FC_DATAUTO0_VSD_MASK, FC_DATAUTO0_VSD_MASK);
+    }
+
+    fn config_spd(&mut self) {
+        use HdmiReg::*;
+
+        self.hdmi_mod(FcDatAuto0, FC_DATAUTO0_SPD_MASK, 0);
+
+        // Zero padded
+        for n in 0..SPD_VENDOR_NAME_LEN {
+            let c = SPD_VENDOR_NAME.get(n).copied().unwrap_or(0);
+            self.hdmi_write(FcSpdVendorName(n as u8), c);
+        }
+        for n in 0..SPD_PRODUCT_NAME_LEN {
+            let c = SPD_PRODUCT_NAME.get(n).copied().unwrap_or(0);
+            self.hdmi_write(FcSpdProductName(n as u8), c);
+        }
+        self.hdmi_write(FcSpdDeviceInf, SPD_SOURCE_PC_GENERAL);
+
+        self.hdmi_mod(FcDatAuto0, FC_DATAUTO0_SPD_MASK, FC_DATAUTO0_SPD_MASK);
+    }
+
+    /// Refer to the stream header, until there's audio to describe
+    fn config_audio_infoframe(&mut self) {
+        use HdmiReg::*;
+
+        self.hdmi_write(FcAudiConf0, 0);
+        self.hdmi_write(FcAudiConf1, 0);
+        self.hdmi_write(FcAudiConf2, 0);
+        self.hdmi_write(FcAudiConf3, 0);
+    }
+
+    fn enable_video_path(&mut self, format: &OutputFormat) {
         use HdmiReg::*;
 
         // Control period minimum duration
@@ -438,19 +922,27 @@ impl DwHdmi {
         self.hdmi_write(McClkDis, clkdis);
 
         // Enable csc path
-        // TODO - if is_color_space_conversion
-
-        // Enable color space conversion if needed
-        // TODO - if is_color_space_conversion
-        self.hdmi_write(McFlowCtrl, MC_FLOWCTRL_FEED_THROUGH_OFF_CSC_BYPASS);
+        if format.needs_csc() {
+            clkdis &= !MC_CLKDIS_CSCCLK_DISABLE;
+            self.hdmi_write(McClkDis, clkdis);
+            self.hdmi_write(McFlowCtrl, MC_FLOWCTRL_FEED_THROUGH_OFF_CSC_IN_PATH);
+        } else {
+            self.hdmi_write(McFlowCtrl, MC_FLOWCTRL_FEED_THROUGH_OFF_CSC_BYPASS);
+        }
     }
 
-    fn video_packetize(&mut self) {
+    fn video_packetize(&mut self, format: &OutputFormat) {
         use HdmiReg::*;
 
-        let color_depth = 0;
+        // Deep colour goes through the pixel packer, 4:2:2 through the
+        // YCC422 remapper
         let remap_size = VP_REMAP_YCC422_16BIT;
-        let output_select = VP_CONF_OUTPUT_SELECTOR_BYPASS;
+        let (color_depth, output_select) = match (format.color, format.depth) {
+            (ColorFormat::YCbCr422, _) => (0, VP_CONF_OUTPUT_SELECTOR_YCC422),
+            (_, ColorDepth::Bits8) => (0, VP_CONF_OUTPUT_SELECTOR_BYPASS),
+            (_, ColorDepth::Bits10) => (5, VP_CONF_OUTPUT_SELECTOR_PP),
+            (_, ColorDepth::Bits12) => (6, VP_CONF_OUTPUT_SELECTOR_PP),
+        };
 
         // Set the packetizer registers
         let val = ((color_depth << VP_PR_CD_COLOR_DEPTH_OFFSET) & VP_PR_CD_COLOR_DEPTH_MASK)
@@ -480,7 +972,13 @@ impl DwHdmi {
 
         self.hdmi_write(VpRemap, remap_size);
 
-        let vp_conf = VP_CONF_BYPASS_EN_ENABLE | VP_CONF_PP_EN_DISABLE | VP_CONF_YCC422_EN_DISABLE;
+        let vp_conf = if output_select == VP_CONF_OUTPUT_SELECTOR_PP {
+            VP_CONF_BYPASS_EN_DISABLE | VP_CONF_PP_EN_ENABLE | VP_CONF_YCC422_EN_DISABLE
+        } else if output_select == VP_CONF_OUTPUT_SELECTOR_YCC422 {
+            VP_CONF_BYPASS_EN_DISABLE | VP_CONF_PP_EN_DISABLE | VP_CONF_YCC422_EN_ENABLE
+        } else {
+            VP_CONF_BYPASS_EN_ENABLE | VP_CONF_PP_EN_DISABLE | VP_CONF_YCC422_EN_DISABLE
+        };
         self.hdmi_mod(
             VpConf,
             VP_CONF_BYPASS_EN_MASK | VP_CONF_PP_EN_ENMASK | VP_CONF_YCC422_EN_MASK,
@@ -496,16 +994,51 @@ impl DwHdmi {
         self.hdmi_mod(VpConf, VP_CONF_OUTPUT_SELECTOR_MASK, output_select);
     }
 
-    fn video_csc(&mut self) {
-        // TODO - my setup bails out early, need to put this logic back together
+    fn video_csc(&mut self, format: &OutputFormat) {
+        use HdmiReg::*;
+
+        if !format.needs_csc() {
+            return;
+        }
+
+        // 4:2:2 drops every other chroma sample
+        let decimation = if format.color == ColorFormat::YCbCr422 {
+            CSC_CFG_DECMODE_CHROMA_INT_FORMULA3
+        } else {
+            0
+        };
+        let color_depth = match format.depth {
+            ColorDepth::Bits8 => CSC_SCALE_COLOR_DEPTH_24BPP,
+            ColorDepth::Bits10 => CSC_SCALE_COLOR_DEPTH_30BPP,
+            ColorDepth::Bits12 => CSC_SCALE_COLOR_DEPTH_36BPP,
+        };
+        self.hdmi_write(CscCfg, decimation);
+        self.hdmi_mod(CscScale, CSC_SCALE_COLOR_DEPTH_MASK, color_depth);
+
+        let (coefficients, scale) = match (format.color, format.colorimetry, format.quantization) {
+            (ColorFormat::Rgb, _, _) => (&CSC_RGB_FULL_TO_LIMITED, 1),
+            (_, Colorimetry::Bt601, Quantization::Full) => (&CSC_RGB_TO_BT601_FULL, 0),
+            (_, Colorimetry::Bt601, Quantization::Limited) => (&CSC_RGB_TO_BT601_LIMITED, 0),
+            (_, Colorimetry::Bt709, Quantization::Full) => (&CSC_RGB_TO_BT709_FULL, 0),
+            (_, Colorimetry::Bt709, Quantization::Limited) => (&CSC_RGB_TO_BT709_LIMITED, 0),
+        };
+
+        // The registers are sequential, a row of four MSB/LSB pairs each
+        for (row, coefficients) in coefficients.iter().enumerate() {
+            for (col, coefficient) in coefficients.iter().enumerate() {
+                let n = (row * 8 + col * 2) as u8;
+                self.hdmi_write(CscCoef(n), (coefficient >> 8) as u8);
+                self.hdmi_write(CscCoef(n + 1), *coefficient as u8);
+            }
+        }
+        self.hdmi_mod(CscScale, CSC_SCALE_CSCSCALE_MASK, scale);
     }
 
     fn video_sample(&mut self) {
         use HdmiReg::*;
 
-        // TODO - handle all of the MEDIA_BUS_FMT_* variants
-        // switch on hdmi->hdmi_data.enc_in_bus_format
-        let color_format = 0x01;
+        // TCON1 always feeds 8-bit RGB, the CSC does any conversion
+        let color_format = TX_INVID0_VIDEO_MAPPING_RGB888;
 
         let val = TX_INVID0_INTERNAL_DE_GENERATOR_DISABLE
             | ((color_format << TX_INVID0_VIDEO_MAPPING_OFFSET) & TX_INVID0_VIDEO_MAPPING_MASK);
@@ -538,15 +1071,21 @@ impl DwHdmi {
         }
     }
 
-    // TODO - timeout
-    fn wait_i2c_done(&mut self, _msec: usize) {
-        loop {
+    fn wait_i2c_done(&mut self, msec: usize) -> Result<(), EdidError> {
+        for _ in 0..msec * 100 {
             let val = self.hdmi_read(HdmiReg::IhI2cmStat0);
-            if val & 0x02 != 0 {
+            if val & (I2CM_STAT0_ERROR | I2CM_STAT0_DONE) != 0 {
+                // Write 1 to clear
                 self.hdmi_write(HdmiReg::IhI2cmStat0, val);
-                return;
+                return if val & I2CM_STAT0_ERROR != 0 {
+                    Err(EdidError::Ddc)
+                } else {
+                    Ok(())
+                };
             }
+            delay_us(10);
         }
+        Err(EdidError::Ddc)
     }
 
     fn hdmi_write(&mut self, reg: HdmiReg, val: u8) {
@@ -0,0 +1,470 @@
+//! HDMI audio
+//!
+//! PCM from a ring buffer is sent by DMA through I2S2, which is wired to
+//! the DesignWare HDMI audio sampler. I2S2 is the master, with 32-bit
+//! slots and BCLK at 64 fs. Each SDO line carries two channels.
+//!
+//! ```ignore
+//! let config = AudioConfig::new(SampleRate::Khz48, 2, SampleWidth::Bits16)?;
+//! let mut audio = HdmiAudio::new(i2s2, config, dma.ch0, desc, ring, &mut ccu)?;
+//! display.enable_audio(&audio)?;
+//! audio.start();
+//! // Keep the ring filled ahead of audio.position()
+//! ```
+
+use super::cea::{AudioFormat, CeaExtension};
+use crate::ccu::{Ccu, ClockSource, ModuleClock, Pll};
+use crate::dma::descriptor::{AddressMode, BurstLength, Config, DrqPort, Param};
+use crate::dma::{Buffer, Channel, Descriptor, SupportedWordSize};
+use crate::pac::i2s2::{self, I2S2};
+use crate::pac::i2s_common::{
+    ChannelConfig, ChannelMap, ChannelSelect, ClockDivide, Control, FifoControl, Format0, Format1,
+    IntControl,
+};
+use core::convert::TryFrom;
+use core::mem;
+use core::ops::Deref;
+use core::pin::Pin;
+use core::sync::atomic::{self, Ordering};
+use embedded_time::rate::Hertz;
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Error {
+    /// 2 to 8 channels are supported
+    InvalidChannels,
+    /// The ring buffer's word size doesn't match the sample width, or it
+    /// doesn't hold whole frames
+    BufferFormat,
+    /// PLL_AUDIO or the I2S dividers can't produce the sample rate
+    InvalidClock,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum SampleRate {
+    Khz32,
+    Khz44_1,
+    Khz48,
+}
+
+impl SampleRate {
+    pub fn hz(self) -> u32 {
+        match self {
+            SampleRate::Khz32 => 32_000,
+            SampleRate::Khz44_1 => 44_100,
+            SampleRate::Khz48 => 48_000,
+        }
+    }
+
+    /// PLL_AUDIO(1X) for the rate's family, 512 fs at 44.1 and 48 kHz
+    fn pll_rate(self) -> Hertz {
+        match self {
+            SampleRate::Khz44_1 => Hertz(22_579_200),
+            _ => Hertz(24_576_000),
+        }
+    }
+
+    /// IEC 60958 channel status sampling frequency code
+    pub(crate) fn iec_code(self) -> u8 {
+        match self {
+            SampleRate::Khz32 => 0x3,
+            SampleRate::Khz44_1 => 0x0,
+            SampleRate::Khz48 => 0x2,
+        }
+    }
+}
+
+/// Samples are right aligned, 24-bit samples in 32-bit words
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum SampleWidth {
+    Bits16,
+    Bits24,
+}
+
+impl SampleWidth {
+    pub fn bits(self) -> u8 {
+        match self {
+            SampleWidth::Bits16 => 16,
+            SampleWidth::Bits24 => 24,
+        }
+    }
+
+    /// Bytes per sample in the ring buffer
+    pub fn container_size(self) -> usize {
+        match self {
+            SampleWidth::Bits16 => 2,
+            SampleWidth::Bits24 => 4,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct AudioConfig {
+    rate: SampleRate,
+    channels: u8,
+    width: SampleWidth,
+}
+
+impl AudioConfig {
+    pub fn new(rate: SampleRate, channels: u8, width: SampleWidth) -> Result<Self, Error> {
+        if !(2..=8).contains(&channels) {
+            return Err(Error::InvalidChannels);
+        }
+        Ok(AudioConfig {
+            rate,
+            channels,
+            width,
+        })
+    }
+
+    pub fn rate(&self) -> SampleRate {
+        self.rate
+    }
+
+    pub fn channels(&self) -> u8 {
+        self.channels
+    }
+
+    pub fn width(&self) -> SampleWidth {
+        self.width
+    }
+
+    /// Bytes per frame, one sample of every channel
+    pub fn frame_size(&self) -> usize {
+        usize::from(self.channels) * self.width.container_size()
+    }
+
+    /// I2S SDO lines, two channels each
+    pub(crate) fn lines(&self) -> u8 {
+        (self.channels + 1) / 2
+    }
+
+    /// CEA-861 speaker allocation, front pair first, then LFE, centre and
+    /// the rear speakers
+    pub(crate) fn channel_allocation(&self) -> u8 {
+        match self.channels {
+            2 => 0x00,
+            3 => 0x01,
+            4 => 0x03,
+            5 => 0x07,
+            6 => 0x0B,
+            7 => 0x0F,
+            _ => 0x13,
+        }
+    }
+
+    /// Whether the sink's short audio descriptors, or its basic audio
+    /// support, cover this format
+    pub fn is_supported_by(&self, cea: &CeaExtension) -> bool {
+        let basic = cea.basic_audio && self.channels == 2 && self.width == SampleWidth::Bits16;
+        basic
+            || cea.audio_formats().iter().any(|sad| {
+                let rate = match self.rate {
+                    SampleRate::Khz32 => sad.sample_rates.khz_32(),
+                    SampleRate::Khz44_1 => sad.sample_rates.khz_44_1(),
+                    SampleRate::Khz48 => sad.sample_rates.khz_48(),
+                };
+                let depth = sad.lpcm_bit_depths().map_or(false, |d| match self.width {
+                    SampleWidth::Bits16 => d.bits_16(),
+                    SampleWidth::Bits24 => d.bits_24(),
+                });
+                sad.format == AudioFormat::Lpcm
+                    && sad.max_channels >= self.channels
+                    && rate
+                    && depth
+            })
+    }
+}
+
+/// HDMI recommended N for the audio clock regeneration packets
+///
+/// The 1.001 divided pixel clocks have their own values so CTS stays an
+/// integer.
+pub(crate) fn audio_n(tmds_clock: u32, rate: SampleRate) -> u32 {
+    let khz = tmds_clock / 1000;
+    let near = |clock: u32| khz + 1 >= clock && khz <= clock + 1;
+    let (n32, n44, n48) = if near(25_175) {
+        (4576, 7007, 6864)
+    } else if near(74_176) {
+        (11648, 17836, 11648)
+    } else if near(148_352) {
+        (11648, 8918, 5824)
+    } else if near(296_703) {
+        (5824, 4459, 5824)
+    } else {
+        (4096, 6272, 6144)
+    };
+    match rate {
+        SampleRate::Khz32 => n32,
+        SampleRate::Khz44_1 => n44,
+        SampleRate::Khz48 => n48,
+    }
+}
+
+/// CTS = f_TMDS * N / (128 * fs), with the rate I2S2 actually runs at
+pub(crate) fn audio_cts(tmds_clock: u32, n: u32, clock: &AudioClock) -> u32 {
+    let num = u64::from(tmds_clock) * u64::from(n) * u64::from(clock.clocks_per_frame);
+    let den = 128 * u64::from(clock.i2s_clock.0);
+    ((num + den / 2) / den) as u32
+}
+
+/// fs = i2s_clock / clocks_per_frame, in I2S module clocks
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub(crate) struct AudioClock {
+    pub i2s_clock: Hertz,
+    pub clocks_per_frame: u32,
+}
+
+/// BCLKs per LRCK period
+const BCLKS_PER_FRAME: u32 = 64;
+const SLOT_WIDTH: u32 = 32;
+
+/// PLL_AUDIO can't hit 22.5792 MHz exactly, CTS absorbs the error
+const PLL_TOLERANCE: Hertz = Hertz(2_000);
+
+/// TX FIFO register offset, the DMA destination
+const TXFIFO_OFFSET: usize = 0x20;
+
+const CLOCK_DIVIDERS: [u32; 15] = [1, 2, 4, 6, 8, 12, 16, 24, 32, 48, 64, 96, 128, 176, 192];
+
+/// Sample rate error allowed, in 1/1000
+const RATE_TOLERANCE: u32 = 1;
+
+/// Index and value of the BCLK divider closest to `rate`
+fn bclk_divider(i2s_clock: Hertz, rate: SampleRate) -> Result<(u32, u32), Error> {
+    let fs = rate.hz();
+    let error = |actual: u32| {
+        if actual > fs {
+            actual - fs
+        } else {
+            fs - actual
+        }
+    };
+    let (sel, div, actual) = CLOCK_DIVIDERS
+        .iter()
+        .enumerate()
+        .map(|(sel, &div)| (sel as u32, div, i2s_clock.0 / (BCLKS_PER_FRAME * div)))
+        .min_by_key(|&(_, _, actual)| error(actual))
+        .ok_or(Error::InvalidClock)?;
+    if error(actual) * 1000 > fs * RATE_TOLERANCE {
+        return Err(Error::InvalidClock);
+    }
+    Ok((sel, div))
+}
+
+pub struct HdmiAudio<BUF> {
+    i2s: I2S2,
+    config: AudioConfig,
+    clock: AudioClock,
+    channel: Channel,
+    desc: Pin<&'static mut Descriptor>,
+    buffer: Pin<BUF>,
+}
+
+impl<BUF> HdmiAudio<BUF>
+where
+    BUF: Deref + 'static,
+{
+    /// Set up PLL_AUDIO, I2S2 and a DMA descriptor that loops over `buffer`
+    ///
+    /// `Word` is `u16` for 16-bit samples and `u32` for 24-bit ones,
+    /// channels interleaved.
+    pub fn new<Word>(
+        mut i2s: I2S2,
+        config: AudioConfig,
+        channel: Channel,
+        mut desc: Pin<&'static mut Descriptor>,
+        buffer: Pin<BUF>,
+        ccu: &mut Ccu,
+    ) -> Result<Self, Error>
+    where
+        BUF::Target: Buffer<Word>,
+        Word: SupportedWordSize,
+    {
+        if mem::size_of::<Word>() != config.width.container_size()
+            || buffer.size() == 0
+            || buffer.size() % config.frame_size() != 0
+            || u32::try_from(buffer.size()).is_err()
+        {
+            return Err(Error::BufferFormat);
+        }
+
+        ccu.enable_and_reset::<I2S2>();
+        let pll = ccu
+            .set_pll_rate(Pll::Audio, config.rate.pll_rate(), PLL_TOLERANCE)
+            .map_err(|_| Error::InvalidClock)?;
+        let i2s_clock = ccu
+            .set_module_clock(ModuleClock::I2sPcm2, ClockSource::PllAudio, pll)
+            .map_err(|_| Error::InvalidClock)?;
+        let (bclk_div_sel, bclk_div) = bclk_divider(i2s_clock, config.rate)?;
+
+        i2s.ctl.modify(Control::GlobalEnable::Clear);
+        i2s.ctl.modify(
+            Control::Mode::LeftJustified
+                + Control::LrckOut::Set
+                + Control::BclkOut::Set
+                + Control::OutMute::Clear,
+        );
+        let lines = config.lines();
+        i2s.ctl.modify(
+            Control::Sdo0Enable::Set
+                + Control::Sdo1Enable::Field::new(u32::from(lines > 1)).unwrap()
+                + Control::Sdo2Enable::Field::new(u32::from(lines > 2)).unwrap()
+                + Control::Sdo3Enable::Field::new(u32::from(lines > 3)).unwrap(),
+        );
+
+        // Left channel while LRCK is low
+        i2s.fmt0.modify(
+            Format0::SlotWidth::Field::new(SLOT_WIDTH / 4 - 1).unwrap()
+                + Format0::SampleResolution::Field::new(u32::from(config.width.bits()) / 4 - 1)
+                    .unwrap()
+                + Format0::BclkPolarity::Normal
+                + Format0::LrckPeriod::Field::new(SLOT_WIDTH - 1).unwrap()
+                + Format0::LrckPolarity::Inverted,
+        );
+        i2s.fmt1
+            .modify(Format1::TxPdm::Linear + Format1::TxLsbFirst::Clear);
+
+        i2s.clkd.modify(
+            ClockDivide::MclkDiv::Field::new(1).unwrap()
+                + ClockDivide::BclkDiv::Field::new(bclk_div_sel + 1).unwrap()
+                + ClockDivide::MclkOutEnable::Clear,
+        );
+
+        // Every sample of a frame is in the FIFO, each line picks its pair
+        i2s.chcfg
+            .modify(ChannelConfig::TxSlotNum::Field::new(u32::from(config.channels) - 1).unwrap());
+        for line in 0..usize::from(lines) {
+            let first = 2 * line as u32;
+            i2s.tx_chsel[line].modify(
+                ChannelSelect::Channels::Field::new(1).unwrap()
+                    + ChannelSelect::SlotEnable::Field::new(0b11).unwrap()
+                    + ChannelSelect::Offset::Field::new(1).unwrap(),
+            );
+            i2s.tx_chmap[line]
+                .modify(ChannelMap::Map::Field::new(first | ((first + 1) << 4)).unwrap());
+        }
+
+        i2s.fctl.modify(
+            FifoControl::TxMode::Lsb
+                + FifoControl::TxTriggerLevel::Field::new(0x40).unwrap()
+                + FifoControl::FlushTx::Set,
+        );
+        i2s.txcnt.write(0);
+        i2s.int.modify(IntControl::TxDrqEnable::Set);
+
+        let mut dma_config = Config(0);
+        dma_config.set_src_drq_port(DrqPort::SdRam);
+        dma_config.set_dst_drq_port(DrqPort::I2s2);
+        dma_config.set_src_address_mode(AddressMode::Linear);
+        dma_config.set_dst_address_mode(AddressMode::Io);
+        dma_config.set_src_burst_length(BurstLength::Bytes4);
+        dma_config.set_dst_burst_length(BurstLength::Bytes4);
+        dma_config.set_src_data_width(Word::data_width());
+        dma_config.set_dst_data_width(Word::data_width());
+
+        let mut param = Param(0);
+        param.set_wait(Param::NORMAL_WAIT);
+
+        desc.config = dma_config;
+        desc.src_addr = buffer.as_ptr() as u32;
+        desc.dst_addr = (i2s2::PADDR + TXFIFO_OFFSET) as u32;
+        desc.length = buffer.size() as u32;
+        desc.param = param;
+        // Loop over the ring until stopped
+        desc.next_addr = desc.as_ptr() as u32;
+
+        Ok(HdmiAudio {
+            i2s,
+            config,
+            clock: AudioClock {
+                i2s_clock,
+                clocks_per_frame: bclk_div * BCLKS_PER_FRAME,
+            },
+            channel,
+            desc,
+            buffer,
+        })
+    }
+
+    pub fn config(&self) -> &AudioConfig {
+        &self.config
+    }
+
+    pub(crate) fn clock(&self) -> &AudioClock {
+        &self.clock
+    }
+
+    pub fn start(&mut self) {
+        self.channel.set_nonsecure();
+        self.channel.set_desc_addr(&self.desc);
+        atomic::fence(Ordering::SeqCst);
+        self.channel.enable();
+
+        self.i2s
+            .ctl
+            .modify(Control::GlobalEnable::Set + Control::TxEnable::Set);
+    }
+
+    pub fn stop(&mut self) {
+        self.i2s.ctl.modify(Control::TxEnable::Clear);
+        self.channel.disable();
+        atomic::fence(Ordering::SeqCst);
+        self.i2s.fctl.modify(FifoControl::FlushTx::Set);
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.channel.is_active()
+    }
+
+    /// Byte offset in the ring the DMA reads next, samples behind it have
+    /// been sent to the FIFO and can be overwritten
+    pub fn position(&self) -> usize {
+        let offset = self
+            .channel
+            .current_src_addr()
+            .wrapping_sub(self.desc.src_addr);
+        (offset as usize).min(self.desc.length as usize) % self.desc.length as usize
+    }
+
+    pub fn free(mut self) -> (I2S2, Channel, Pin<&'static mut Descriptor>, Pin<BUF>) {
+        self.stop();
+        self.i2s.int.modify(IntControl::TxDrqEnable::Clear);
+        (self.i2s, self.channel, self.desc, self.buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// What PLL_AUDIO gets closest to 22.5792 MHz
+    const PLL_44_1: Hertz = Hertz(22_577_777);
+
+    #[test]
+    fn bclk_divider_rounds_to_nearest() {
+        assert_eq!(bclk_divider(PLL_44_1, SampleRate::Khz44_1), Ok((4, 8)));
+        assert_eq!(
+            bclk_divider(Hertz(24_576_000), SampleRate::Khz48),
+            Ok((4, 8))
+        );
+        assert_eq!(
+            bclk_divider(Hertz(24_576_000), SampleRate::Khz32),
+            Ok((5, 12))
+        );
+        assert_eq!(
+            bclk_divider(Hertz(24_000_000), SampleRate::Khz44_1),
+            Err(Error::InvalidClock)
+        );
+    }
+
+    #[test]
+    fn cts_follows_the_actual_rate() {
+        let clock = AudioClock {
+            i2s_clock: PLL_44_1,
+            clocks_per_frame: 8 * BCLKS_PER_FRAME,
+        };
+        let n = audio_n(148_500_000, SampleRate::Khz44_1);
+        assert_eq!(n, 6272);
+        assert_eq!(audio_cts(148_500_000, n, &clock), 165_010);
+    }
+}
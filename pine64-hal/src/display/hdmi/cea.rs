@@ -0,0 +1,506 @@
+//! CEA-861 EDID extension
+//!
+//! Revision 3, the data block collection and the native detailed timings.
+//! Like the base block parser it never panics on malformed input.
+//!
+//! https://en.wikipedia.org/wiki/Extended_Display_Identification_Data#CEA_EDID_Timing_Extension_Data_Format_-_Version_3
+
+use super::edid::{
+    parse_detailed_timing, verify_checksum, EdidError, IResult, DESCRIPTOR_SIZE, EDID_SIZE,
+};
+use crate::display::DisplayTiming;
+use bitfield::bitfield;
+use core::fmt;
+use nom::{bytes::complete::take, number::complete::le_u8};
+
+pub const CEA_EXTENSION_TAG: u8 = 0x02;
+
+/// IEEE OUI of HDMI Licensing, LLC
+const HDMI_OUI: u32 = 0x00_0C03;
+
+// Data block tags
+const AUDIO_BLOCK: u8 = 1;
+const VIDEO_BLOCK: u8 = 2;
+const VENDOR_SPECIFIC_BLOCK: u8 = 3;
+const SPEAKER_ALLOCATION_BLOCK: u8 = 4;
+
+/// A data block holds at most 31 bytes of payload
+const MAX_SVDS: usize = 31;
+const MAX_SADS: usize = 10;
+/// Detailed timings that fit after the smallest data block collection
+const MAX_DTDS: usize = 6;
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct CeaExtension {
+    pub revision: u8,
+    /// The sink underscans IT formats by default
+    pub underscan: bool,
+    pub basic_audio: bool,
+    pub ycbcr444: bool,
+    pub ycbcr422: bool,
+    /// Number of the detailed timings that are native formats
+    pub native_dtd_count: u8,
+    pub speaker_allocation: Option<SpeakerAllocation>,
+    /// Present for HDMI sinks, absent for DVI
+    pub hdmi: Option<HdmiVsdb>,
+    svds: [ShortVideoDescriptor; MAX_SVDS],
+    svd_count: u8,
+    sads: [ShortAudioDescriptor; MAX_SADS],
+    sad_count: u8,
+    dtds: [DisplayTiming; MAX_DTDS],
+    dtd_count: u8,
+}
+
+impl CeaExtension {
+    /// Parse and validate an extension block
+    pub fn parse(input: &[u8]) -> Result<Self, EdidError> {
+        let (_, cea) = parse_cea_extension(input)?;
+        Ok(cea)
+    }
+
+    pub fn is_hdmi(&self) -> bool {
+        self.hdmi.is_some()
+    }
+
+    /// CEA video formats in the sink's order of preference
+    pub fn video_formats(&self) -> &[ShortVideoDescriptor] {
+        &self.svds[..usize::from(self.svd_count)]
+    }
+
+    pub fn audio_formats(&self) -> &[ShortAudioDescriptor] {
+        &self.sads[..usize::from(self.sad_count)]
+    }
+
+    pub fn detailed_timings(&self) -> &[DisplayTiming] {
+        &self.dtds[..usize::from(self.dtd_count)]
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct ShortVideoDescriptor {
+    /// Video identification code
+    pub vic: u8,
+    pub native: bool,
+}
+
+impl ShortVideoDescriptor {
+    fn from_byte(b: u8) -> Self {
+        // Bit 7 only flags a native format for VICs 1 through 64
+        if (129..=192).contains(&b) {
+            ShortVideoDescriptor {
+                vic: b & 0x7F,
+                native: true,
+            }
+        } else {
+            ShortVideoDescriptor {
+                vic: b,
+                native: false,
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum AudioFormat {
+    Lpcm,
+    Ac3,
+    Mpeg1,
+    Mp3,
+    Mpeg2,
+    AacLc,
+    Dts,
+    Atrac,
+    OneBitAudio,
+    EnhancedAc3,
+    DtsHd,
+    MatMlp,
+    Dst,
+    WmaPro,
+    /// Reserved or extended format code
+    Other(u8),
+}
+
+impl AudioFormat {
+    fn from_code(code: u8) -> Self {
+        use AudioFormat::*;
+        match code {
+            1 => Lpcm,
+            2 => Ac3,
+            3 => Mpeg1,
+            4 => Mp3,
+            5 => Mpeg2,
+            6 => AacLc,
+            7 => Dts,
+            8 => Atrac,
+            9 => OneBitAudio,
+            10 => EnhancedAc3,
+            11 => DtsHd,
+            12 => MatMlp,
+            13 => Dst,
+            14 => WmaPro,
+            c => Other(c),
+        }
+    }
+}
+
+impl Default for AudioFormat {
+    fn default() -> Self {
+        AudioFormat::Lpcm
+    }
+}
+
+bitfield! {
+    #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+    pub struct SampleRates(u8);
+    u8;
+    pub khz_32, _ : 0;
+    pub khz_44_1, _ : 1;
+    pub khz_48, _ : 2;
+    pub khz_88_2, _ : 3;
+    pub khz_96, _ : 4;
+    pub khz_176_4, _ : 5;
+    pub khz_192, _ : 6;
+}
+
+bitfield! {
+    #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+    pub struct LpcmBitDepths(u8);
+    u8;
+    pub bits_16, _ : 0;
+    pub bits_20, _ : 1;
+    pub bits_24, _ : 2;
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct ShortAudioDescriptor {
+    pub format: AudioFormat,
+    pub max_channels: u8,
+    pub sample_rates: SampleRates,
+    /// Bit depths for LPCM, max bit rate / 8 kHz for the compressed formats
+    pub detail: u8,
+}
+
+impl ShortAudioDescriptor {
+    fn from_bytes(sad: &[u8]) -> Self {
+        ShortAudioDescriptor {
+            format: AudioFormat::from_code((sad[0] >> 3) & 0xF),
+            max_channels: (sad[0] & 0x7) + 1,
+            sample_rates: SampleRates(sad[1] & 0x7F),
+            detail: sad[2],
+        }
+    }
+
+    pub fn lpcm_bit_depths(&self) -> Option<LpcmBitDepths> {
+        match self.format {
+            AudioFormat::Lpcm => Some(LpcmBitDepths(self.detail & 0x7)),
+            _ => None,
+        }
+    }
+}
+
+bitfield! {
+    #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+    pub struct SpeakerAllocation(u8);
+    u8;
+    pub front_left_right, _ : 0;
+    pub low_frequency_effects, _ : 1;
+    pub front_center, _ : 2;
+    pub rear_left_right, _ : 3;
+    pub rear_center, _ : 4;
+    pub front_left_right_center, _ : 5;
+    pub rear_left_right_center, _ : 6;
+}
+
+/// CEC physical address, A.B.C.D
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct PhysicalAddress(pub u16);
+
+impl fmt::Display for PhysicalAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}",
+            self.0 >> 12,
+            (self.0 >> 8) & 0xF,
+            (self.0 >> 4) & 0xF,
+            self.0 & 0xF
+        )
+    }
+}
+
+bitfield! {
+    #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+    pub struct DeepColor(u8);
+    u8;
+    pub dvi_dual, _ : 0;
+    /// The deep colour modes also apply to YCbCr 4:4:4
+    pub ycbcr444, _ : 3;
+    pub bits_30, _ : 4;
+    pub bits_36, _ : 5;
+    pub bits_48, _ : 6;
+    pub supports_ai, _ : 7;
+}
+
+/// HDMI vendor-specific data block
+///
+/// The OUI alone marks an HDMI sink, fields a short block leaves out are
+/// unknown.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct HdmiVsdb {
+    pub physical_address: PhysicalAddress,
+    pub deep_color: DeepColor,
+    /// Hz, `None` when not given
+    pub max_tmds_clock: Option<u32>,
+}
+
+impl HdmiVsdb {
+    /// F.F.F.F, no valid physical address
+    pub const UNKNOWN_ADDRESS: PhysicalAddress = PhysicalAddress(0xFFFF);
+
+    /// `payload` follows the OUI
+    fn from_payload(payload: &[u8]) -> Self {
+        let physical_address = match payload.get(0..2) {
+            Some(pa) => PhysicalAddress(u16::from_be_bytes([pa[0], pa[1]])),
+            None => Self::UNKNOWN_ADDRESS,
+        };
+        let deep_color = DeepColor(payload.get(2).copied().unwrap_or(0));
+        let max_tmds_clock = match payload.get(3) {
+            None | Some(0) => None,
+            // 5 MHz units
+            Some(&clk) => Some(u32::from(clk) * 5_000_000),
+        };
+        HdmiVsdb {
+            physical_address,
+            deep_color,
+            max_tmds_clock,
+        }
+    }
+}
+
+pub fn parse_cea_extension(input: &[u8]) -> IResult<&[u8], CeaExtension> {
+    verify_checksum(input).map_err(nom::Err::Failure)?;
+    let (input, block) = take(EDID_SIZE)(input)?;
+
+    let (rest, tag) = le_u8(block)?;
+    if tag != CEA_EXTENSION_TAG {
+        return Err(nom::Err::Failure(EdidError::UnsupportedExtension));
+    }
+    let (rest, revision) = le_u8(rest)?;
+    if revision == 0 {
+        return Err(nom::Err::Failure(EdidError::UnsupportedVersion));
+    }
+    let (rest, dtd_offset) = le_u8(rest)?;
+    let (_, support) = le_u8(rest)?;
+
+    let mut cea = CeaExtension {
+        revision,
+        underscan: support & 0x80 != 0,
+        basic_audio: support & 0x40 != 0,
+        ycbcr444: support & 0x20 != 0,
+        ycbcr422: support & 0x10 != 0,
+        native_dtd_count: support & 0x0F,
+        speaker_allocation: None,
+        hdmi: None,
+        svds: [ShortVideoDescriptor::default(); MAX_SVDS],
+        svd_count: 0,
+        sads: [ShortAudioDescriptor::default(); MAX_SADS],
+        sad_count: 0,
+        dtds: [DisplayTiming::default(); MAX_DTDS],
+        dtd_count: 0,
+    };
+
+    // An offset of 0 means neither data blocks nor timings
+    let dtd_offset = usize::from(dtd_offset);
+    if dtd_offset == 0 {
+        return Ok((input, cea));
+    }
+    // Revisions 1 and 2 have no data block collection
+    let collection_end = if revision >= 3 { dtd_offset } else { 4 };
+    let collection = block
+        .get(4..collection_end)
+        .ok_or(nom::Err::Failure(EdidError::Truncated))?;
+    parse_data_blocks(collection, &mut cea)?;
+
+    // Up to the checksum, a zero pixel clock ends the list early
+    let dtds = block
+        .get(dtd_offset..EDID_SIZE - 1)
+        .ok_or(nom::Err::Failure(EdidError::Truncated))?;
+    for dtd in dtds.chunks_exact(DESCRIPTOR_SIZE).take(MAX_DTDS) {
+        if dtd[0] == 0 && dtd[1] == 0 {
+            break;
+        }
+        cea.dtds[usize::from(cea.dtd_count)] = parse_detailed_timing(dtd);
+        cea.dtd_count += 1;
+    }
+
+    Ok((input, cea))
+}
+
+fn parse_data_blocks<'a>(mut input: &'a [u8], cea: &mut CeaExtension) -> IResult<&'a [u8], ()> {
+    while !input.is_empty() {
+        let (rest, header) = le_u8(input)?;
+        let (rest, payload) = take(usize::from(header & 0x1F))(rest)?;
+        input = rest;
+
+        match header >> 5 {
+            AUDIO_BLOCK => {
+                for sad in payload.chunks_exact(3) {
+                    if usize::from(cea.sad_count) < MAX_SADS {
+                        cea.sads[usize::from(cea.sad_count)] =
+                            ShortAudioDescriptor::from_bytes(sad);
+                        cea.sad_count += 1;
+                    }
+                }
+            }
+            VIDEO_BLOCK => {
+                for b in payload.iter().copied() {
+                    if usize::from(cea.svd_count) < MAX_SVDS {
+                        cea.svds[usize::from(cea.svd_count)] = ShortVideoDescriptor::from_byte(b);
+                        cea.svd_count += 1;
+                    }
+                }
+            }
+            VENDOR_SPECIFIC_BLOCK if payload.len() >= 3 => {
+                let oui = u32::from_le_bytes([payload[0], payload[1], payload[2], 0]);
+                if oui == HDMI_OUI {
+                    cea.hdmi = Some(HdmiVsdb::from_payload(&payload[3..]));
+                }
+            }
+            SPEAKER_ALLOCATION_BLOCK if !payload.is_empty() => {
+                cea.speaker_allocation = Some(SpeakerAllocation(payload[0]));
+            }
+            // TODO - extended tags, colorimetry and YCbCr 4:2:0
+            _ => (),
+        }
+    }
+    Ok((input, ()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VIDEO: u8 = VIDEO_BLOCK << 5;
+    const AUDIO: u8 = AUDIO_BLOCK << 5;
+    const VENDOR: u8 = VENDOR_SPECIFIC_BLOCK << 5;
+    const HDMI_OUI_BYTES: [u8; 3] = [0x03, 0x0C, 0x00];
+
+    /// Extension block with `data` from byte 4 and a valid checksum
+    fn cea_block(revision: u8, dtd_offset: u8, data: &[u8]) -> [u8; EDID_SIZE] {
+        let mut block = [0; EDID_SIZE];
+        block[0] = CEA_EXTENSION_TAG;
+        block[1] = revision;
+        block[2] = dtd_offset;
+        block[4..4 + data.len()].copy_from_slice(data);
+        let sum = block[..EDID_SIZE - 1]
+            .iter()
+            .fold(0u8, |sum, b| sum.wrapping_add(*b));
+        block[EDID_SIZE - 1] = 0u8.wrapping_sub(sum);
+        block
+    }
+
+    fn collection_offset(data: &[u8]) -> u8 {
+        4 + data.len() as u8
+    }
+
+    #[test]
+    fn dtd_offset_zero() {
+        let data = [VIDEO | 2, 16, 4];
+        let cea = CeaExtension::parse(&cea_block(3, 0, &data)).unwrap();
+        assert!(cea.video_formats().is_empty());
+        assert!(cea.detailed_timings().is_empty());
+    }
+
+    #[test]
+    fn dtd_offset_out_of_range() {
+        for &offset in [128, 200, 255].iter() {
+            assert_eq!(
+                CeaExtension::parse(&cea_block(3, offset, &[])),
+                Err(EdidError::Truncated)
+            );
+        }
+        // Inside the header
+        assert_eq!(
+            CeaExtension::parse(&cea_block(3, 2, &[])),
+            Err(EdidError::Truncated)
+        );
+        // Right before the checksum, no room for a timing
+        let cea = CeaExtension::parse(&cea_block(3, 127, &[])).unwrap();
+        assert!(cea.detailed_timings().is_empty());
+    }
+
+    #[test]
+    fn truncated_data_block() {
+        // Claims 3 SVDs but the collection ends after 2
+        let data = [VIDEO | 3, 16, 4];
+        assert_eq!(
+            CeaExtension::parse(&cea_block(3, collection_offset(&data), &data)),
+            Err(EdidError::Truncated)
+        );
+    }
+
+    #[test]
+    fn oversized_data_blocks() {
+        let mut data = [0; 2 * 32];
+        data[0] = VIDEO | 31;
+        data[32] = VIDEO | 31;
+        for (i, b) in data.iter_mut().enumerate() {
+            if i % 32 != 0 {
+                *b = (i % 64) as u8;
+            }
+        }
+        let cea = CeaExtension::parse(&cea_block(3, collection_offset(&data), &data)).unwrap();
+        assert_eq!(cea.video_formats().len(), MAX_SVDS);
+        assert_eq!(cea.video_formats()[30].vic, 31);
+
+        // 12 SADs over two blocks
+        let mut data = [0; 1 + 30 + 1 + 6];
+        data[0] = AUDIO | 30;
+        data[31] = AUDIO | 6;
+        for n in 0..12 {
+            let at = if n < 10 { 1 + 3 * n } else { 32 + 3 * (n - 10) };
+            data[at..at + 3].copy_from_slice(&[0x09, 0x07, 0x07]);
+        }
+        let cea = CeaExtension::parse(&cea_block(3, collection_offset(&data), &data)).unwrap();
+        assert_eq!(cea.audio_formats().len(), MAX_SADS);
+        assert_eq!(cea.audio_formats()[0].max_channels, 2);
+    }
+
+    #[test]
+    fn revision_2_has_no_data_blocks() {
+        let data = [VIDEO | 2, 16, 4];
+        let cea = CeaExtension::parse(&cea_block(2, collection_offset(&data), &data)).unwrap();
+        assert!(cea.video_formats().is_empty());
+    }
+
+    #[test]
+    fn short_hdmi_vsdb() {
+        // The OUI alone
+        let mut data = [VENDOR | 3, 0, 0, 0];
+        data[1..].copy_from_slice(&HDMI_OUI_BYTES);
+        let cea = CeaExtension::parse(&cea_block(3, collection_offset(&data), &data)).unwrap();
+        assert!(cea.is_hdmi());
+        let vsdb = cea.hdmi.unwrap();
+        assert_eq!(vsdb.physical_address, HdmiVsdb::UNKNOWN_ADDRESS);
+        assert_eq!(vsdb.max_tmds_clock, None);
+
+        // Half a physical address
+        let data = [VENDOR | 4, 0x03, 0x0C, 0x00, 0x10];
+        let cea = CeaExtension::parse(&cea_block(3, collection_offset(&data), &data)).unwrap();
+        assert_eq!(
+            cea.hdmi.map(|vsdb| vsdb.physical_address),
+            Some(HdmiVsdb::UNKNOWN_ADDRESS)
+        );
+
+        let data = [VENDOR | 5, 0x03, 0x0C, 0x00, 0x10, 0x00];
+        let cea = CeaExtension::parse(&cea_block(3, collection_offset(&data), &data)).unwrap();
+        let vsdb = cea.hdmi.unwrap();
+        assert_eq!(vsdb.physical_address, PhysicalAddress(0x1000));
+        assert_eq!(vsdb.deep_color, DeepColor(0));
+
+        // Another vendor's block isn't HDMI
+        let data = [VENDOR | 5, 0xD8, 0x5D, 0xC4, 0x01, 0x00];
+        let cea = CeaExtension::parse(&cea_block(3, collection_offset(&data), &data)).unwrap();
+        assert!(!cea.is_hdmi());
+    }
+}
@@ -0,0 +1,163 @@
+//! HDMI output colour format
+//!
+//! The DE and TCON1 always produce full range 8-bit RGB, the DW-HDMI colour
+//! space converter and video packetizer turn that into what the sink is
+//! sent.
+
+use super::cea::CeaExtension;
+use crate::display::{dw_hdmi, modes, DisplayTiming};
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum ColorFormat {
+    Rgb,
+    YCbCr444,
+    YCbCr422,
+}
+
+impl Default for ColorFormat {
+    fn default() -> Self {
+        ColorFormat::Rgb
+    }
+}
+
+/// YCbCr coefficients, unused for RGB
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Colorimetry {
+    /// SD formats
+    Bt601,
+    /// HD formats
+    Bt709,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Quantization {
+    /// 0-255
+    Full,
+    /// 16-235, and 16-240 for chroma
+    Limited,
+}
+
+/// Bits per component
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum ColorDepth {
+    Bits8,
+    Bits10,
+    Bits12,
+}
+
+impl ColorDepth {
+    pub fn bits(self) -> u32 {
+        match self {
+            ColorDepth::Bits8 => 8,
+            ColorDepth::Bits10 => 10,
+            ColorDepth::Bits12 => 12,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct OutputFormat {
+    pub color: ColorFormat,
+    pub colorimetry: Colorimetry,
+    pub quantization: Quantization,
+    /// Always 8 bits for 4:2:2, which carries up to 12 bits at the pixel
+    /// clock
+    pub depth: ColorDepth,
+}
+
+impl OutputFormat {
+    /// What DVI sinks get
+    pub const RGB_FULL: OutputFormat = OutputFormat {
+        color: ColorFormat::Rgb,
+        colorimetry: Colorimetry::Bt709,
+        quantization: Quantization::Full,
+        depth: ColorDepth::Bits8,
+    };
+
+    /// The `preferred` format when the sink accepts it, otherwise RGB, at
+    /// the deepest colour the sink and the TMDS clock allow
+    ///
+    /// CE formats are sent limited range, IT formats full range, following
+    /// CEA-861. `max_tmds_clock` is in Hz.
+    pub fn for_sink(
+        preferred: ColorFormat,
+        timing: &DisplayTiming,
+        vic: Option<u8>,
+        cea: Option<&CeaExtension>,
+        max_tmds_clock: u32,
+    ) -> Self {
+        let cea = match cea {
+            Some(cea) if timing.hdmi_monitor => cea,
+            _ => return Self::RGB_FULL,
+        };
+
+        let color = match preferred {
+            ColorFormat::YCbCr444 if cea.ycbcr444 => ColorFormat::YCbCr444,
+            ColorFormat::YCbCr422 if cea.ycbcr422 => ColorFormat::YCbCr422,
+            _ => ColorFormat::Rgb,
+        };
+
+        // VIC 1 is the only IT format with a code
+        let ce_format = vic.map_or(false, |vic| vic != 1);
+        let quantization = if ce_format || color != ColorFormat::Rgb {
+            Quantization::Limited
+        } else {
+            Quantization::Full
+        };
+
+        // 4:3 formats are the SD ones
+        let colorimetry = match vic.and_then(modes::picture_aspect) {
+            Some(modes::PictureAspect::FourThree) => Colorimetry::Bt601,
+            _ if timing.vactive.typ < 720 => Colorimetry::Bt601,
+            _ => Colorimetry::Bt709,
+        };
+
+        let mut format = OutputFormat {
+            color,
+            colorimetry,
+            quantization,
+            depth: ColorDepth::Bits8,
+        };
+        if color == ColorFormat::YCbCr422 {
+            return format;
+        }
+
+        let deep_color = match cea.hdmi {
+            Some(vsdb) if color == ColorFormat::Rgb || vsdb.deep_color.ycbcr444() => {
+                vsdb.deep_color
+            }
+            _ => return format,
+        };
+        let depths = [
+            (ColorDepth::Bits12, deep_color.bits_36()),
+            (ColorDepth::Bits10, deep_color.bits_30()),
+        ];
+        for &(depth, supported) in depths.iter() {
+            format.depth = depth;
+            let clock = format.tmds_clock(timing.pixel_clock.typ);
+            if supported && clock <= max_tmds_clock && dw_hdmi::tmds_clock_supported(clock, depth) {
+                return format;
+            }
+        }
+        format.depth = ColorDepth::Bits8;
+        format
+    }
+
+    pub fn needs_csc(&self) -> bool {
+        self.color != ColorFormat::Rgb || self.quantization != Quantization::Full
+    }
+
+    /// TMDS character rate for `pixel_clock`, both in Hz
+    pub fn tmds_clock(&self, pixel_clock: u32) -> u32 {
+        match self.color {
+            ColorFormat::YCbCr422 => pixel_clock,
+            _ => (u64::from(pixel_clock) * u64::from(self.depth.bits()) / 8) as u32,
+        }
+    }
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        Self::RGB_FULL
+    }
+}
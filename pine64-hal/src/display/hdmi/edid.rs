@@ -3,21 +3,63 @@
 //! 1.3
 //!
 //! https://glenwing.github.io/docs/VESA-EEDID-A1.pdf
+//!
+//! The parsers don't touch the hardware and return an error rather than
+//! panic on any input, so they can be run and tested on the host.
 
+use crate::display::{DisplayFlags, DisplayTiming, TimingEntry};
+use core::fmt;
 use nom::{
+    bytes::complete::take,
+    error::{ErrorKind, ParseError},
     number::complete::{be_u16, le_u16, le_u32, le_u64, le_u8},
-    IResult,
 };
 
 // TODO
-// - Error type https://github.com/Geal/nom/blob/master/examples/custom_error.rs
-// - checksum check
 // - split up the sub-byte fields using bits mod stuff bits::complete as bits
 // - do the unit conversions
 
 // https://en.wikipedia.org/wiki/Extended_Display_Identification_Data
 // http://www.drhdmi.eu/dictionary/edid.html
 
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum EdidError {
+    /// The block doesn't start with the fixed header pattern
+    BadHeader,
+    /// The bytes of the block don't sum to zero
+    ChecksumMismatch,
+    /// The input ended before the block did
+    Truncated,
+    /// Not a 1.x structure
+    UnsupportedVersion,
+    /// An extension block with a tag the parser doesn't handle
+    UnsupportedExtension,
+    /// The DDC read timed out or the sink didn't acknowledge it
+    Ddc,
+}
+
+impl<I> ParseError<I> for EdidError {
+    fn from_error_kind(_input: I, _kind: ErrorKind) -> Self {
+        // The only failure of the complete parsers used here is running out
+        EdidError::Truncated
+    }
+
+    fn append(_input: I, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+pub type IResult<I, O> = nom::IResult<I, O, EdidError>;
+
+impl From<nom::Err<EdidError>> for EdidError {
+    fn from(e: nom::Err<EdidError>) -> Self {
+        match e {
+            nom::Err::Incomplete(_) => EdidError::Truncated,
+            nom::Err::Error(e) | nom::Err::Failure(e) => e,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct Edid {
     pub header: EdidHeader,
@@ -25,6 +67,51 @@ pub struct Edid {
     pub color_characteristics: ColorCharacteristics,
     pub established_timings: EstablishedTimings,
     pub standard_timings: StandardTimings,
+    pub descriptors: [Descriptor; 4],
+    /// Number of extension blocks that follow
+    pub extension_count: u8,
+    pub checksum: u8,
+}
+
+impl Edid {
+    /// Parse and validate the base block
+    pub fn parse(input: &[u8]) -> Result<Self, EdidError> {
+        let (_, edid) = parse_edid(input)?;
+        Ok(edid)
+    }
+
+    /// The first detailed timing is the preferred mode
+    pub fn preferred_timing(&self) -> Option<&DisplayTiming> {
+        self.detailed_timings().next()
+    }
+
+    pub fn detailed_timings(&self) -> impl Iterator<Item = &DisplayTiming> {
+        self.descriptors.iter().filter_map(|d| match d {
+            Descriptor::DetailedTiming(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    pub fn monitor_name(&self) -> Option<&str> {
+        self.descriptors.iter().find_map(|d| match d {
+            Descriptor::MonitorName(name) => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn serial_number(&self) -> Option<&str> {
+        self.descriptors.iter().find_map(|d| match d {
+            Descriptor::SerialNumber(serial) => Some(serial.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn range_limits(&self) -> Option<&MonitorRangeLimits> {
+        self.descriptors.iter().find_map(|d| match d {
+            Descriptor::MonitorRange(limits) => Some(limits),
+            _ => None,
+        })
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
@@ -38,6 +125,38 @@ pub struct EdidHeader {
     pub revision: u8,
 }
 
+impl EdidHeader {
+    pub fn manufacturer(&self) -> Manufacturer {
+        Manufacturer::from_pnp_id(self.manufacturer_name)
+    }
+}
+
+/// Three letter PnP ID of the manufacturer
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Manufacturer([u8; 3]);
+
+impl Manufacturer {
+    /// Compressed ASCII, three 5-bit letters where 1 is 'A'
+    fn from_pnp_id(id: u16) -> Self {
+        let letter = |shift: u16| match ((id >> shift) & 0x1F) as u8 {
+            l @ 1..=26 => b'A' + l - 1,
+            _ => b'?',
+        };
+        Manufacturer([letter(10), letter(5), letter(0)])
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Always ASCII
+        core::str::from_utf8(&self.0).unwrap_or("???")
+    }
+}
+
+impl fmt::Display for Manufacturer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct BasicDisplayParams {
     pub video_input_definition: u8,
@@ -61,22 +180,111 @@ pub struct ColorCharacteristics {
     pub white_point_y: u8,
 }
 
+/// Resolution and refresh rate of a timing without the full detail
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct VideoMode {
+    pub hactive: u16,
+    pub vactive: u16,
+    /// Hz
+    pub refresh_rate: u16,
+    pub interlaced: bool,
+}
+
+impl VideoMode {
+    const fn new(hactive: u16, vactive: u16, refresh_rate: u16) -> Self {
+        VideoMode {
+            hactive,
+            vactive,
+            refresh_rate,
+            interlaced: false,
+        }
+    }
+
+    const fn interlaced(hactive: u16, vactive: u16, refresh_rate: u16) -> Self {
+        VideoMode {
+            hactive,
+            vactive,
+            refresh_rate,
+            interlaced: true,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct EstablishedTimings {
     pub timing_modes: [u8; 3],
 }
 
+/// Established timings I, II and the one defined bit of the manufacturer
+/// timings, most significant bit of the first byte first
+const ESTABLISHED_MODES: [VideoMode; 17] = [
+    VideoMode::new(720, 400, 70),
+    VideoMode::new(720, 400, 88),
+    VideoMode::new(640, 480, 60),
+    VideoMode::new(640, 480, 67),
+    VideoMode::new(640, 480, 72),
+    VideoMode::new(640, 480, 75),
+    VideoMode::new(800, 600, 56),
+    VideoMode::new(800, 600, 60),
+    VideoMode::new(800, 600, 72),
+    VideoMode::new(800, 600, 75),
+    VideoMode::new(832, 624, 75),
+    VideoMode::interlaced(1024, 768, 87),
+    VideoMode::new(1024, 768, 60),
+    VideoMode::new(1024, 768, 70),
+    VideoMode::new(1024, 768, 75),
+    VideoMode::new(1280, 1024, 75),
+    VideoMode::new(1152, 870, 75),
+];
+
+impl EstablishedTimings {
+    pub fn modes(&self) -> impl Iterator<Item = VideoMode> + '_ {
+        ESTABLISHED_MODES
+            .iter()
+            .enumerate()
+            .filter(move |(bit, _)| self.timing_modes[bit / 8] & (0x80 >> (bit % 8)) != 0)
+            .map(|(_, mode)| *mode)
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct StandardTimings {
     pub display_modes: [StandardTimingInfo; 8],
 }
 
+impl StandardTimings {
+    pub fn modes(&self) -> impl Iterator<Item = VideoMode> + '_ {
+        self.display_modes
+            .iter()
+            .filter_map(StandardTimingInfo::mode)
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct StandardTimingInfo {
     pub xresolution: u8,
     pub aspect_vfreq: u8,
 }
 
+impl StandardTimingInfo {
+    /// `None` for an unused slot
+    pub fn mode(&self) -> Option<VideoMode> {
+        if (self.xresolution == 0x01 && self.aspect_vfreq == 0x01) || self.xresolution == 0 {
+            return None;
+        }
+        let hactive = (u16::from(self.xresolution) + 31) * 8;
+        // 1.3 and later, 1:1 for earlier versions
+        let vactive = match self.aspect_vfreq >> 6 {
+            0 => hactive * 10 / 16,
+            1 => hactive * 3 / 4,
+            2 => hactive * 4 / 5,
+            _ => hactive * 9 / 16,
+        };
+        let refresh_rate = u16::from(self.aspect_vfreq & 0x3F) + 60;
+        Some(VideoMode::new(hactive, vactive, refresh_rate))
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub enum DescriptorType {
     SerialNumber = 0xFF,
@@ -85,17 +293,91 @@ pub enum DescriptorType {
     MonitorName = 0xFC,
 }
 
+impl DescriptorType {
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0xFF => Some(DescriptorType::SerialNumber),
+            0xFE => Some(DescriptorType::Text),
+            0xFD => Some(DescriptorType::MonitorRange),
+            0xFC => Some(DescriptorType::MonitorName),
+            _ => None,
+        }
+    }
+}
+
+/// One of the four 18-byte descriptors
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Descriptor {
+    DetailedTiming(DisplayTiming),
+    SerialNumber(DescriptorText),
+    Text(DescriptorText),
+    MonitorRange(MonitorRangeLimits),
+    MonitorName(DescriptorText),
+    /// Display descriptor not decoded, by tag
+    Other(u8),
+}
+
+/// ASCII string, terminated by a line feed and padded with spaces
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct DescriptorText {
+    bytes: [u8; 13],
+}
+
+impl DescriptorText {
+    pub fn as_str(&self) -> &str {
+        let end = self
+            .bytes
+            .iter()
+            .position(|&b| b == b'\n' || !b.is_ascii())
+            .unwrap_or(self.bytes.len());
+        core::str::from_utf8(&self.bytes[..end])
+            .unwrap_or("")
+            .trim_end()
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct MonitorRangeLimits {
+    /// Hz
+    pub min_vertical_rate: u16,
+    pub max_vertical_rate: u16,
+    /// kHz
+    pub min_horizontal_rate: u16,
+    pub max_horizontal_rate: u16,
+    /// Hz, `None` when not given
+    pub max_pixel_clock: Option<u32>,
+}
+
 pub const EDID_SIZE: usize = 128;
 pub const EDID_EXT_SIZE: usize = 256;
 
 const HEADER_PREAMBLE: u64 = 0x00_FF_FF_FF_FF_FF_FF_00;
+pub(crate) const DESCRIPTOR_SIZE: usize = 18;
+
+/// The bytes of a 128 byte block sum to zero
+pub fn verify_checksum(block: &[u8]) -> Result<(), EdidError> {
+    let block = block.get(..EDID_SIZE).ok_or(EdidError::Truncated)?;
+    if block.iter().fold(0_u8, |sum, b| sum.wrapping_add(*b)) == 0 {
+        Ok(())
+    } else {
+        Err(EdidError::ChecksumMismatch)
+    }
+}
 
 pub fn parse_edid(input: &[u8]) -> IResult<&[u8], Edid> {
+    verify_checksum(input).map_err(nom::Err::Failure)?;
     let (input, header) = parse_edid_header(input)?;
     let (input, info) = parse_basic_display_params(input)?;
     let (input, color_characteristics) = parse_color_characteristics(input)?;
     let (input, established_timings) = parse_established_timings(input)?;
     let (input, standard_timings) = parse_standard_timings(input)?;
+    let (input, d0) = parse_descriptor(input)?;
+    let (input, d1) = parse_descriptor(input)?;
+    let (input, d2) = parse_descriptor(input)?;
+    let (input, d3) = parse_descriptor(input)?;
+    let descriptors = [d0, d1, d2, d3];
+    let (input, extension_count) = le_u8(input)?;
+    let (input, checksum) = le_u8(input)?;
 
     Ok((
         input,
@@ -105,18 +387,18 @@ pub fn parse_edid(input: &[u8]) -> IResult<&[u8], Edid> {
             color_characteristics,
             established_timings,
             standard_timings,
+            descriptors,
+            extension_count,
+            checksum,
         },
     ))
 }
 
 pub fn parse_edid_header(input: &[u8]) -> IResult<&[u8], EdidHeader> {
     let (input, header) = le_u64(input)?;
-
-    assert_eq!(
-        header, HEADER_PREAMBLE,
-        "Bad header preabmle: 0x{:X}",
-        header
-    );
+    if header != HEADER_PREAMBLE {
+        return Err(nom::Err::Failure(EdidError::BadHeader));
+    }
 
     let (input, manufacturer_name) = be_u16(input)?;
     let (input, product_code) = le_u16(input)?;
@@ -125,6 +407,9 @@ pub fn parse_edid_header(input: &[u8]) -> IResult<&[u8], EdidHeader> {
     let (input, year) = le_u8(input)?;
     let (input, version) = le_u8(input)?;
     let (input, revision) = le_u8(input)?;
+    if version != 1 {
+        return Err(nom::Err::Failure(EdidError::UnsupportedVersion));
+    }
 
     Ok((
         input,
@@ -223,3 +508,231 @@ fn parse_standard_timing_info(input: &[u8]) -> IResult<&[u8], StandardTimingInfo
         },
     ))
 }
+
+pub fn parse_descriptor(input: &[u8]) -> IResult<&[u8], Descriptor> {
+    let (rest, bytes) = take(DESCRIPTOR_SIZE)(input)?;
+
+    // A zero pixel clock marks a display descriptor
+    let descriptor = if bytes[0] != 0 || bytes[1] != 0 {
+        Descriptor::DetailedTiming(parse_detailed_timing(bytes))
+    } else {
+        let mut text = DescriptorText { bytes: [0; 13] };
+        text.bytes.copy_from_slice(&bytes[5..DESCRIPTOR_SIZE]);
+        match DescriptorType::from_tag(bytes[3]) {
+            Some(DescriptorType::SerialNumber) => Descriptor::SerialNumber(text),
+            Some(DescriptorType::Text) => Descriptor::Text(text),
+            Some(DescriptorType::MonitorName) => Descriptor::MonitorName(text),
+            Some(DescriptorType::MonitorRange) => {
+                Descriptor::MonitorRange(parse_range_limits(bytes))
+            }
+            None => Descriptor::Other(bytes[3]),
+        }
+    };
+
+    Ok((rest, descriptor))
+}
+
+/// Detailed timing descriptor, `dtd` is 18 bytes
+///
+/// Interlaced descriptors give the vertical timing of one field, it's
+/// doubled to the frame, with the extra half line in the back porch.
+pub(crate) fn parse_detailed_timing(dtd: &[u8]) -> DisplayTiming {
+    let hi4 = |b: u8| u32::from(b >> 4);
+    let lo4 = |b: u8| u32::from(b & 0x0F);
+
+    // 10 kHz units
+    let pixel_clock = u32::from(u16::from_le_bytes([dtd[0], dtd[1]])) * 10_000;
+
+    let hactive = u32::from(dtd[2]) | (hi4(dtd[4]) << 8);
+    let hblank = u32::from(dtd[3]) | (lo4(dtd[4]) << 8);
+    let vactive = u32::from(dtd[5]) | (hi4(dtd[7]) << 8);
+    let vblank = u32::from(dtd[6]) | (lo4(dtd[7]) << 8);
+
+    let hfront_porch = u32::from(dtd[8]) | (u32::from(dtd[11] >> 6) << 8);
+    let hsync_len = u32::from(dtd[9]) | (u32::from((dtd[11] >> 4) & 0x3) << 8);
+    let vfront_porch = hi4(dtd[10]) | (u32::from((dtd[11] >> 2) & 0x3) << 4);
+    let vsync_len = lo4(dtd[10]) | (u32::from(dtd[11] & 0x3) << 4);
+    let vback_porch = vblank.saturating_sub(vfront_porch + vsync_len);
+
+    let features = dtd[17];
+    let interlaced = features & 0x80 != 0;
+    let (vactive, vfront_porch, vsync_len, vback_porch) = if interlaced {
+        (
+            vactive * 2,
+            vfront_porch * 2,
+            vsync_len * 2,
+            vback_porch * 2 + 1,
+        )
+    } else {
+        (vactive, vfront_porch, vsync_len, vback_porch)
+    };
+
+    let mut flags = DisplayFlags(0);
+    flags.set_interlaced(interlaced);
+    // Only digital separate sync carries both polarities, others are active low
+    let separate_sync = (features >> 3) & 0x3 == 0x3;
+    let hsync_high = separate_sync && features & 0x02 != 0;
+    let vsync_high = separate_sync && features & 0x04 != 0;
+    flags.set_hsync_high(hsync_high);
+    flags.set_hsync_low(!hsync_high);
+    flags.set_vsync_high(vsync_high);
+    flags.set_vsync_low(!vsync_high);
+
+    DisplayTiming {
+        pixel_clock: entry(pixel_clock),
+        hactive: entry(hactive),
+        hfront_porch: entry(hfront_porch),
+        hback_porch: entry(hblank.saturating_sub(hfront_porch + hsync_len)),
+        hsync_len: entry(hsync_len),
+        vactive: entry(vactive),
+        vfront_porch: entry(vfront_porch),
+        vback_porch: entry(vback_porch),
+        vsync_len: entry(vsync_len),
+        flags,
+        hdmi_monitor: false,
+    }
+}
+
+/// Display range limits descriptor, `desc` is 18 bytes
+fn parse_range_limits(desc: &[u8]) -> MonitorRangeLimits {
+    // 1.4 extends the rates past 255 with offset flags
+    let offsets = desc[4];
+    let rate = |val: u8, offset: bool| u16::from(val) + if offset { 255 } else { 0 };
+    let max_pixel_clock = match desc[9] {
+        0 => None,
+        clk => Some(u32::from(clk) * 10_000_000),
+    };
+
+    MonitorRangeLimits {
+        min_vertical_rate: rate(desc[5], offsets & 0x3 == 0x3),
+        max_vertical_rate: rate(desc[6], offsets & 0x2 != 0),
+        min_horizontal_rate: rate(desc[7], (offsets >> 2) & 0x3 == 0x3),
+        max_horizontal_rate: rate(desc[8], offsets & 0x8 != 0),
+        max_pixel_clock,
+    }
+}
+
+fn entry(val: u32) -> TimingEntry {
+    TimingEntry::new(val, val, val)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// CEA-861 1920x1080p60
+    const DTD_1080P: [u8; DESCRIPTOR_SIZE] = [
+        0x02, 0x3A, 0x80, 0x18, 0x71, 0x38, 0x2D, 0x40, 0x58, 0x2C, 0x45, 0x00, 0xC4, 0x8E, 0x21,
+        0x00, 0x00, 0x1E,
+    ];
+
+    /// CEA-861 1920x1080i60
+    const DTD_1080I: [u8; DESCRIPTOR_SIZE] = [
+        0x01, 0x1D, 0x80, 0x18, 0x71, 0x1C, 0x16, 0x20, 0x58, 0x2C, 0x25, 0x00, 0xC4, 0x8E, 0x21,
+        0x00, 0x00, 0x9E,
+    ];
+
+    /// Base block with the 1080p60 DTD as the preferred timing
+    fn base_block() -> [u8; EDID_SIZE] {
+        let mut block = [0; EDID_SIZE];
+        block[..8].copy_from_slice(&HEADER_PREAMBLE.to_le_bytes());
+        block[18] = 1;
+        block[19] = 3;
+        block[54..54 + DESCRIPTOR_SIZE].copy_from_slice(&DTD_1080P);
+        fix_checksum(&mut block);
+        block
+    }
+
+    fn fix_checksum(block: &mut [u8; EDID_SIZE]) {
+        let sum = block[..EDID_SIZE - 1]
+            .iter()
+            .fold(0_u8, |sum, b| sum.wrapping_add(*b));
+        block[EDID_SIZE - 1] = sum.wrapping_neg();
+    }
+
+    #[test]
+    fn parse_base_block() {
+        let edid = Edid::parse(&base_block()).unwrap();
+        assert_eq!((edid.header.version, edid.header.revision), (1, 3));
+        let preferred = edid.preferred_timing().unwrap();
+        assert_eq!(*preferred, parse_detailed_timing(&DTD_1080P));
+    }
+
+    #[test]
+    fn bad_header() {
+        let mut block = base_block();
+        block[0] = 0x01;
+        fix_checksum(&mut block);
+        assert_eq!(Edid::parse(&block), Err(EdidError::BadHeader));
+    }
+
+    #[test]
+    fn checksum_mismatch() {
+        let mut block = base_block();
+        block[EDID_SIZE - 1] ^= 0x01;
+        assert_eq!(Edid::parse(&block), Err(EdidError::ChecksumMismatch));
+    }
+
+    #[test]
+    fn truncated() {
+        let block = base_block();
+        assert_eq!(Edid::parse(&block[..100]), Err(EdidError::Truncated));
+        assert_eq!(Edid::parse(&[]), Err(EdidError::Truncated));
+        assert_eq!(
+            parse_descriptor(&DTD_1080P[..10]).map(|(_, d)| d),
+            Err(nom::Err::Error(EdidError::Truncated))
+        );
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let mut block = base_block();
+        block[18] = 2;
+        fix_checksum(&mut block);
+        assert_eq!(Edid::parse(&block), Err(EdidError::UnsupportedVersion));
+    }
+
+    #[test]
+    fn detailed_timing_1080p() {
+        let t = parse_detailed_timing(&DTD_1080P);
+        assert_eq!(t.pixel_clock.typ, 148_500_000);
+        assert_eq!(
+            [
+                t.hactive.typ,
+                t.hfront_porch.typ,
+                t.hsync_len.typ,
+                t.hback_porch.typ
+            ],
+            [1920, 88, 44, 148]
+        );
+        assert_eq!(
+            [
+                t.vactive.typ,
+                t.vfront_porch.typ,
+                t.vsync_len.typ,
+                t.vback_porch.typ
+            ],
+            [1080, 4, 5, 36]
+        );
+        assert!(!t.flags.interlaced());
+        assert!(t.flags.hsync_high() && t.flags.vsync_high());
+    }
+
+    #[test]
+    fn detailed_timing_interlaced_is_per_frame() {
+        let t = parse_detailed_timing(&DTD_1080I);
+        assert_eq!(t.pixel_clock.typ, 74_250_000);
+        assert_eq!(
+            [
+                t.vactive.typ,
+                t.vfront_porch.typ,
+                t.vsync_len.typ,
+                t.vback_porch.typ
+            ],
+            [1080, 4, 10, 31]
+        );
+        let vtotal = t.vactive.typ + t.vfront_porch.typ + t.vsync_len.typ + t.vback_porch.typ;
+        assert_eq!(vtotal, 1125);
+        assert!(t.flags.interlaced());
+    }
+}
@@ -1,44 +1,117 @@
 //! HDMI display
 
-// TODO - error type, EDID variant, ...
-
 use super::{de2::DisplayEngine2, dw_hdmi::DwHdmi, lcdc::LcdController};
-use super::{BitsPerPixel, DisplayTiming};
+use super::{fb_addr, modes, BitsPerPixel, Display, DisplayTiming};
 use crate::ccu::Ccu;
 use crate::delay::{delay_ms, delay_us};
-use crate::pac::ccu::{
-    BusClockGating1, BusSoftReset1, HdmiClockConfig, HdmiSlowClock, Tcon1ClockConfig, CCU,
-};
+use crate::pac::ccu::{HdmiClockConfig, HdmiSlowClock, Tcon1ClockConfig, CCU};
 use crate::pac::hdmi::{PhyControl, PhyPll, PhyStatus};
 use crate::pac::{de::DE, de_mixer::MIXER1, hdmi::HDMI, tcon1::TCON1};
+use audio::{AudioClock, AudioConfig, HdmiAudio};
+use cea::{CeaExtension, CEA_EXTENSION_TAG};
+use color::{ColorFormat, OutputFormat};
+use core::ops::Deref;
 use core::pin::Pin;
 use cortex_a::asm;
-use edid::Edid;
+use edid::{Edid, EdidError};
 use embedded_time::rate::Hertz;
+use mode::{ModeLimits, ModeList, ModeSelection};
 
+pub mod audio;
+pub mod cea;
+pub mod color;
 pub mod edid;
+pub mod mode;
 pub use super::dw_hdmi::HDMI_EDID_BLOCK_SIZE;
 
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Error {
+    /// The requested timing is beyond the hardware or the frame buffer
+    UnsupportedMode,
+    /// No sink, a DVI sink, or the sink doesn't accept the audio format
+    UnsupportedAudio,
+    /// The frame buffer is smaller than the mode, or not addressable by
+    /// the DE
+    FrameBuffer,
+}
+
+/// A change of the PHY's hot plug detect state
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum HotplugEvent {
+    /// The EDID was read and the sink is being driven
+    Connected,
+    Disconnected,
+}
+
+/// Time for the sink's DDC to come up once HPD is asserted
+const HPD_SETTLE_MS: usize = 100;
+
 pub struct HdmiDisplay {
-    _lcdc: LcdController,
-    _de: DisplayEngine2,
-    _hdmi: DwHdmi,
-    timing: DisplayTiming,
-    edid: Edid,
+    lcdc: LcdController,
+    de: DisplayEngine2<MIXER1>,
+    hdmi: DwHdmi,
+    fb_addr: u32,
+    selection: ModeSelection,
+    hw_limits: ModeLimits,
+    limits: ModeLimits,
+    connected: bool,
+    powered: bool,
+    blanked: bool,
+    timing: Option<DisplayTiming>,
+    color_format: ColorFormat,
+    format: OutputFormat,
+    edid: Option<Result<Edid, EdidError>>,
+    cea: Option<CeaExtension>,
+    modes: ModeList,
+    audio: Option<(AudioConfig, AudioClock)>,
 }
 
 impl HdmiDisplay {
+    /// Drive the sink's preferred mode
     pub fn new(
+        tcon: TCON1,
+        mixer: MIXER1,
+        de: DE,
+        hdmi: HDMI,
+        frame_buffer: &Pin<&'static mut [u32]>,
+        ccu: &mut Ccu,
+    ) -> Result<Self, Error> {
+        Self::with_mode(
+            tcon,
+            mixer,
+            de,
+            hdmi,
+            frame_buffer,
+            ModeSelection::Preferred,
+            ccu,
+        )
+    }
+
+    /// Falls back to the preferred mode when the sink doesn't offer the
+    /// selected one, and to 640x480@60 when the EDID is unusable
+    ///
+    /// The display starts disconnected, the sink is driven once
+    /// `poll_hotplug` reports it.
+    pub fn with_mode(
         tcon: TCON1,
         mixer: MIXER1,
         de: DE,
         mut hdmi: HDMI,
         frame_buffer: &Pin<&'static mut [u32]>,
+        selection: ModeSelection,
         ccu: &mut Ccu,
-    ) -> Self {
-        let fb_addr = frame_buffer.as_ptr() as usize;
-        assert!(fb_addr <= core::u32::MAX as usize);
-        let fb_addr: u32 = fb_addr as u32;
+    ) -> Result<Self, Error> {
+        let hw_limits = ModeLimits {
+            max_pixels: frame_buffer.len() as u32,
+            ..ModeLimits::HARDWARE
+        };
+        if let ModeSelection::Timing(timing) = &selection {
+            if !hw_limits.supports(timing) {
+                return Err(Error::UnsupportedMode);
+            }
+        }
+
+        let fb_addr = fb_addr(frame_buffer).ok_or(Error::FrameBuffer)?;
         let raw_ccu = unsafe { &mut *CCU::mut_ptr() };
 
         // Set pll3 to 297 MHz
@@ -50,12 +123,7 @@ impl HdmiDisplay {
             .modify(HdmiClockConfig::ClockSel::Pll3Video0x1);
 
         // Set AHB gating to pass
-        ccu.bsr1.rstr().modify(BusSoftReset1::Hdmi1::Clear);
-        ccu.bsr1.rstr().modify(BusSoftReset1::Hdmi1::Set);
-        ccu.bsr1.rstr().modify(BusSoftReset1::Hdmi0::Clear);
-        ccu.bsr1.rstr().modify(BusSoftReset1::Hdmi0::Set);
-
-        ccu.bcg1.enr().modify(BusClockGating1::Hdmi::Set);
+        ccu.enable_and_reset::<HDMI>();
         raw_ccu
             .hdmi_slow_clk_cfg
             .modify(HdmiSlowClock::DdcClockGating::Set);
@@ -67,66 +135,348 @@ impl HdmiDisplay {
 
         phy_init(&mut hdmi);
 
-        // TODO - error handle, can't get hpd signal
-        wait_for_hpd(&mut hdmi).expect("TODO - Errors");
-
         let mut hdmi = DwHdmi::new(hdmi);
+        hdmi.lock();
+
+        let de = DisplayEngine2::new(mixer, de, ccu);
+
+        // Reset, clock on
+        ccu.enable_and_reset::<TCON1>();
+        let lcdc = LcdController::new(tcon);
+
+        Ok(HdmiDisplay {
+            lcdc,
+            de,
+            hdmi,
+            fb_addr,
+            selection,
+            hw_limits,
+            limits: hw_limits,
+            connected: false,
+            powered: true,
+            blanked: false,
+            timing: None,
+            color_format: ColorFormat::Rgb,
+            format: OutputFormat::RGB_FULL,
+            edid: None,
+            cea: None,
+            modes: ModeList::new(),
+            audio: None,
+        })
+    }
 
-        // TODO - use the parsed Edid data
-        let timing = DisplayTiming::default();
-        let bpp: BitsPerPixel = 32;
+    /// Check the HPD signal, reading the EDID and setting the mode when a
+    /// sink was plugged in
+    ///
+    /// A sink whose mode can't be set is still reported as connected
+    /// through `is_connected`, with the output left off, and the modeset
+    /// error is returned
+    pub fn poll_hotplug(&mut self, ccu: &mut Ccu) -> Result<Option<HotplugEvent>, Error> {
+        if self.hpd() == self.connected {
+            return Ok(None);
+        }
+
+        if self.connected {
+            self.disconnect();
+            Ok(Some(HotplugEvent::Disconnected))
+        } else {
+            delay_ms(HPD_SETTLE_MS);
+            // Bounced while plugging in
+            if !self.hpd() {
+                return Ok(None);
+            }
+            self.connect(ccu)?;
+            Ok(Some(HotplugEvent::Connected))
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    /// Change the mode, applied now when a sink is connected and on every
+    /// later connect otherwise
+    pub fn set_mode(&mut self, selection: ModeSelection, ccu: &mut Ccu) -> Result<(), Error> {
+        if let ModeSelection::Timing(timing) = &selection {
+            if !self.hw_limits.supports(timing) {
+                return Err(Error::UnsupportedMode);
+            }
+        }
+
+        self.selection = selection;
+        if self.connected {
+            self.modeset(ccu)?;
+        }
+        Ok(())
+    }
+
+    /// Send `color_format` when the sink accepts it, RGB otherwise
+    pub fn set_color_format(
+        &mut self,
+        color_format: ColorFormat,
+        ccu: &mut Ccu,
+    ) -> Result<(), Error> {
+        self.color_format = color_format;
+        if self.connected {
+            self.modeset(ccu)?;
+        }
+        Ok(())
+    }
 
-        let mut edid_block = [0; HDMI_EDID_BLOCK_SIZE];
-        hdmi.read_edid_block(&mut edid_block);
-        let (_, edid) = edid::parse_edid(&edid_block).expect("TODO errors");
+    /// What the sink is sent, only meaningful while connected
+    pub fn output_format(&self) -> &OutputFormat {
+        &self.format
+    }
 
-        let mut de = DisplayEngine2::new(mixer, de, ccu);
+    /// The timing being driven, `None` while disconnected
+    pub fn timing(&self) -> Option<&DisplayTiming> {
+        self.timing.as_ref()
+    }
 
-        de.set_mode(fb_addr, bpp, &timing);
+    /// Why the EDID couldn't be used, when the safe mode was picked
+    ///
+    /// `None` while disconnected.
+    pub fn edid(&self) -> Option<Result<&Edid, EdidError>> {
+        self.edid.as_ref().map(|edid| edid.as_ref().map_err(|e| *e))
+    }
 
-        hdmi.enable(&timing, ccu);
+    /// The sink's modes the hardware can drive, in order of preference,
+    /// including those too large for the current frame buffer
+    pub fn modes(&self) -> &[DisplayTiming] {
+        self.modes.as_slice()
+    }
 
-        let lcdc = dw_hdmi_lcdc_init(tcon, &timing, ccu);
+    /// The first CEA-861 extension, `None` for DVI sinks without one
+    pub fn cea_extension(&self) -> Option<&CeaExtension> {
+        self.cea.as_ref()
+    }
 
-        // TODO
-        // edid.flags = 10
-        // doesn't have h/v sync low bits...
+    /// Send the stream `audio` plays to the sink
+    ///
+    /// The stream is sent again after every mode change and reconnect,
+    /// until `disable_audio`, when the sink accepts it.
+    pub fn enable_audio<BUF>(&mut self, audio: &HdmiAudio<BUF>) -> Result<(), Error>
+    where
+        BUF: Deref + 'static,
+    {
+        let config = *audio.config();
+        let tmds_clock = match &self.timing {
+            Some(timing) if self.accepts_audio(&config) => {
+                self.format.tmds_clock(timing.pixel_clock.typ)
+            }
+            _ => return Err(Error::UnsupportedAudio),
+        };
+
+        let clock = *audio.clock();
+        self.hdmi.unlock();
+        self.hdmi.enable_audio(tmds_clock, &config, &clock);
+        self.hdmi.lock();
+        self.audio = Some((config, clock));
+        Ok(())
+    }
 
-        hdmi.hdmi.phy_ctrl.modify(PhyControl::F2::Full);
+    pub fn disable_audio(&mut self) {
+        if self.audio.take().is_some() && self.connected {
+            self.hdmi.unlock();
+            self.hdmi.disable_audio();
+            self.hdmi.lock();
+        }
+    }
 
-        // This is last hdmi access before boot, so scramble addresses
-        // again or othwerwise BSP driver won't work. Dummy read is
-        // needed or otherwise last write doesn't get written correctly.
-        let _ = hdmi.hdmi.version.read();
-        hdmi.hdmi.phy_unscramble.write(0);
+    pub fn audio_config(&self) -> Option<&AudioConfig> {
+        self.audio.as_ref().map(|(config, _)| config)
+    }
+
+    fn hpd(&self) -> bool {
+        self.hdmi.hdmi.phy_status.is_set(PhyStatus::PlugIn::Read)
+    }
+
+    fn accepts_audio(&self, config: &AudioConfig) -> bool {
+        let hdmi_monitor = self.timing.map_or(false, |t| t.hdmi_monitor);
+        match &self.cea {
+            Some(cea) => hdmi_monitor && config.is_supported_by(cea),
+            None => false,
+        }
+    }
+
+    fn connect(&mut self, ccu: &mut Ccu) -> Result<(), Error> {
+        self.hdmi.unlock();
+        let (edid, cea) = read_edid(&mut self.hdmi);
+        self.hdmi.lock();
+
+        let (mut modes, limits) = match &edid {
+            Ok(edid) => (
+                ModeList::from_edid(edid, cea.as_ref()),
+                ModeLimits {
+                    max_pixels: self.hw_limits.max_pixels,
+                    ..ModeLimits::for_sink(edid, cea.as_ref())
+                },
+            ),
+            // No modes, the safe mode is picked unless a timing is forced
+            Err(_) => (ModeList::new(), self.hw_limits),
+        };
+        // The frame buffer can change, its size is left to mode selection
+        modes.retain_supported(&ModeLimits {
+            max_pixels: ModeLimits::HARDWARE.max_pixels,
+            ..limits
+        });
+        self.modes = modes;
+        self.limits = limits;
+        self.edid = Some(edid);
+        self.cea = cea;
+        self.connected = true;
+
+        // A failure leaves the sink connected with the output off and no
+        // timing
+        self.modeset(ccu)
+    }
+
+    fn disconnect(&mut self) {
+        self.lcdc.disable();
+        self.connected = false;
+        self.timing = None;
+        self.edid = None;
+        self.cea = None;
+        self.modes = ModeList::new();
+    }
+
+    fn modeset(&mut self, ccu: &mut Ccu) -> Result<(), Error> {
+        let mut timing = match self.selection {
+            ModeSelection::Timing(timing) => timing,
+            _ => self.modes.select(&self.selection, &self.limits),
+        };
+        // DVI sinks have no HDMI vendor specific data block
+        timing.hdmi_monitor = self.cea.as_ref().map_or(false, CeaExtension::is_hdmi);
+        let vic = cea_vic(&timing, self.cea.as_ref());
+        let format = OutputFormat::for_sink(
+            self.color_format,
+            &timing,
+            vic,
+            self.cea.as_ref(),
+            self.limits.max_pixel_clock,
+        );
+        let bpp: BitsPerPixel = 32;
+        self.timing = None;
+
+        self.lcdc.disable();
+
+        self.de.set_mode(self.fb_addr, bpp, &timing);
+        if self.blanked {
+            self.de.set_blanking(true);
+        }
+
+        self.hdmi.unlock();
+        if let Err(e) = self.hdmi.enable(&timing, vic, &format, ccu) {
+            self.hdmi.lock();
+            return Err(e);
+        }
+        self.timing = Some(timing);
+        self.format = format;
+
+        set_tcon1_clock(&timing, ccu);
+        self.lcdc.set_mode(&timing);
+        if self.powered {
+            self.lcdc.enable();
+        }
+
+        self.hdmi.hdmi.phy_ctrl.modify(PhyControl::F2::Full);
+
+        // The N/CTS values follow the TMDS clock
+        if let Some((config, clock)) = self.audio {
+            if self.accepts_audio(&config) {
+                let tmds_clock = format.tmds_clock(timing.pixel_clock.typ);
+                self.hdmi.enable_audio(tmds_clock, &config, &clock);
+            }
+        }
+
+        self.hdmi.lock();
 
         delay_ms(5);
+        Ok(())
+    }
+}
+
+impl Display for HdmiDisplay {
+    type Error = Error;
 
-        HdmiDisplay {
-            _lcdc: lcdc,
-            _de: de,
-            _hdmi: hdmi,
-            timing,
-            edid,
+    fn timing(&self) -> Option<&DisplayTiming> {
+        HdmiDisplay::timing(self)
+    }
+
+    /// Also bounds the modes picked by later mode changes and connects
+    fn attach_framebuffer(&mut self, frame_buffer: &Pin<&'static mut [u32]>) -> Result<(), Error> {
+        let max_pixels = frame_buffer.len() as u32;
+        if let Some(timing) = &self.timing {
+            if timing.hactive.typ * timing.vactive.typ > max_pixels {
+                return Err(Error::FrameBuffer);
+            }
         }
+
+        self.fb_addr = fb_addr(frame_buffer).ok_or(Error::FrameBuffer)?;
+        self.hw_limits.max_pixels = max_pixels;
+        self.limits.max_pixels = max_pixels;
+        self.de.set_framebuffer(self.fb_addr);
+        Ok(())
     }
 
-    pub fn timing(&self) -> &DisplayTiming {
-        &self.timing
+    fn set_blanking(&mut self, blank: bool) {
+        self.blanked = blank;
+        self.de.set_blanking(blank);
+    }
+
+    fn wait_for_vblank(&mut self) {
+        self.lcdc.wait_for_vblank();
+    }
+
+    /// TCON1 runs again, once a sink is connected
+    fn power_on(&mut self) {
+        self.powered = true;
+        if self.connected {
+            self.lcdc.enable();
+        }
     }
 
-    pub fn edid(&self) -> &Edid {
-        &self.edid
+    /// Stops TCON1, the sink sees no video and can go to standby
+    fn power_off(&mut self) {
+        self.powered = false;
+        self.lcdc.disable();
     }
 }
 
-fn wait_for_hpd(hdmi: &mut HDMI) -> Result<(), ()> {
-    // TODO - timeout 300 us
-    while !hdmi.phy_status.is_set(PhyStatus::PlugIn::Read) {
-        delay_us(100);
+/// The code the sink lists, to pick between the 4:3 and 16:9 variants
+fn cea_vic(timing: &DisplayTiming, cea: Option<&CeaExtension>) -> Option<u8> {
+    let vics = modes::vics(timing);
+    let listed = |vic: &&u8| {
+        cea.map_or(false, |cea| {
+            cea.video_formats().iter().any(|svd| svd.vic == **vic)
+        })
+    };
+    vics.iter().find(listed).or_else(|| vics.last()).copied()
+}
+
+/// Base block and the first CEA-861 extension, other extensions are skipped
+///
+/// A bad or unreadable extension only loses the extension.
+fn read_edid(hdmi: &mut DwHdmi) -> (Result<Edid, EdidError>, Option<CeaExtension>) {
+    let mut block = [0; HDMI_EDID_BLOCK_SIZE];
+    let edid = match hdmi
+        .read_edid(0, &mut block)
+        .and_then(|_| Edid::parse(&block))
+    {
+        Ok(edid) => edid,
+        Err(e) => return (Err(e), None),
+    };
+
+    for index in 1..=usize::from(edid.extension_count) {
+        if hdmi.read_edid(index, &mut block).is_err() {
+            break;
+        }
+        if block[0] == CEA_EXTENSION_TAG {
+            return (Ok(edid), CeaExtension::parse(&block).ok());
+        }
     }
 
-    Ok(())
+    (Ok(edid), None)
 }
 
 fn phy_init(hdmi: &mut HDMI) {
@@ -176,32 +526,16 @@ fn phy_init(hdmi: &mut HDMI) {
     hdmi.phy_unscramble.write(0x42494E47);
 }
 
-fn dw_hdmi_lcdc_init(tcon: TCON1, timing: &DisplayTiming, ccu: &mut Ccu) -> LcdController {
+/// TCON1 runs from PLL_VIDEO0, which `DwHdmi::enable` sets for the mode
+fn set_tcon1_clock(timing: &DisplayTiming, ccu: &mut Ccu) {
     // Assumes mux=1, HDMI
     let div = div_round_up(ccu.pll_video0().0, timing.pixel_clock.typ);
-    let mux = 1;
 
     let raw_ccu = unsafe { &mut *CCU::mut_ptr() };
-
-    if mux == 0 {
-        unimplemented!();
-    } else {
-        // Reset
-        ccu.bsr1.rstr().modify(BusSoftReset1::Tcon1::Clear);
-        ccu.bsr1.rstr().modify(BusSoftReset1::Tcon1::Set);
-
-        // Clock on
-        ccu.bcg1.enr().modify(BusClockGating1::Tcon1::Set);
-        raw_ccu.tcon1_clk_cfg.modify(
-            Tcon1ClockConfig::DivRatioM::Field::new(div - 1).unwrap()
-                + Tcon1ClockConfig::SClockGating::Set,
-        );
-    }
-
-    let mut lcdc = LcdController::new(tcon);
-    lcdc.set_mode(timing);
-    lcdc.enable();
-    lcdc
+    raw_ccu.tcon1_clk_cfg.modify(
+        Tcon1ClockConfig::DivRatioM::Field::new(div - 1).unwrap()
+            + Tcon1ClockConfig::SClockGating::Set,
+    );
 }
 
 fn div_round_up(n: u32, d: u32) -> u32 {
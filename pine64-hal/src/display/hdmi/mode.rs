@@ -0,0 +1,271 @@
+//! HDMI mode selection
+//!
+//! Candidate modes are gathered from the EDID in the sink's order of
+//! preference, then filtered by what the clocks, TCON1 and the HDMI PHY can
+//! produce.
+
+use super::cea::CeaExtension;
+use super::color::ColorDepth;
+use super::edid::{Edid, VideoMode};
+use crate::display::modes::{self, safe_mode, Blanking};
+use crate::display::{dw_hdmi, DisplayTiming};
+
+/// Enough for every descriptor, VIC and standard/established timing
+pub const MAX_MODES: usize = 64;
+
+/// Which mode `HdmiDisplay` should drive
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum ModeSelection {
+    /// The sink's preferred mode, the first supported candidate
+    Preferred,
+    /// The first supported candidate of this size and refresh rate, or the
+    /// preferred mode when the sink doesn't offer it
+    Resolution(VideoMode),
+    /// Exactly this timing, regardless of the EDID
+    Timing(DisplayTiming),
+}
+
+impl Default for ModeSelection {
+    fn default() -> Self {
+        ModeSelection::Preferred
+    }
+}
+
+/// Candidate timings, deduplicated, in order of preference
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct ModeList {
+    modes: [DisplayTiming; MAX_MODES],
+    len: usize,
+}
+
+impl ModeList {
+    pub fn new() -> Self {
+        ModeList {
+            modes: [DisplayTiming::default(); MAX_MODES],
+            len: 0,
+        }
+    }
+
+    /// Every mode the EDID describes and that has a known timing
+    pub fn from_edid(edid: &Edid, cea: Option<&CeaExtension>) -> Self {
+        let mut list = ModeList::new();
+
+        // The first base block DTD is the preferred mode
+        for timing in edid.detailed_timings() {
+            list.push(*timing);
+        }
+        if let Some(cea) = cea {
+            for timing in cea.detailed_timings() {
+                list.push(*timing);
+            }
+            // Native formats first
+            for svd in cea.video_formats().iter().filter(|svd| svd.native) {
+                list.push_opt(modes::by_vic(svd.vic));
+            }
+            for svd in cea.video_formats().iter().filter(|svd| !svd.native) {
+                list.push_opt(modes::by_vic(svd.vic));
+            }
+        }
+        for mode in edid.standard_timings.modes() {
+            list.push_opt(video_mode_timing(&mode));
+        }
+        for mode in edid.established_timings.modes() {
+            list.push_opt(video_mode_timing(&mode));
+        }
+
+        list
+    }
+
+    pub fn push(&mut self, timing: DisplayTiming) {
+        if self.len < MAX_MODES && !self.as_slice().contains(&timing) {
+            self.modes[self.len] = timing;
+            self.len += 1;
+        }
+    }
+
+    fn push_opt(&mut self, timing: Option<DisplayTiming>) {
+        if let Some(timing) = timing {
+            self.push(timing);
+        }
+    }
+
+    pub fn as_slice(&self) -> &[DisplayTiming] {
+        &self.modes[..self.len]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &DisplayTiming> {
+        self.as_slice().iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Only keep the modes `limits` allow
+    pub fn retain_supported(&mut self, limits: &ModeLimits) {
+        let mut kept = 0;
+        for i in 0..self.len {
+            if limits.supports(&self.modes[i]) {
+                self.modes[kept] = self.modes[i];
+                kept += 1;
+            }
+        }
+        self.len = kept;
+    }
+
+    /// `selection` when it's supported, otherwise the preferred mode and
+    /// lastly the safe mode
+    pub fn select(&self, selection: &ModeSelection, limits: &ModeLimits) -> DisplayTiming {
+        let supported = || self.iter().filter(|t| limits.supports(t));
+        let wanted = match selection {
+            ModeSelection::Timing(timing) if limits.supports(timing) => Some(*timing),
+            ModeSelection::Resolution(mode) => supported().find(|t| matches(t, mode)).copied(),
+            _ => None,
+        };
+        wanted
+            .or_else(|| supported().next().copied())
+            .unwrap_or_else(safe_mode)
+    }
+}
+
+impl Default for ModeList {
+    fn default() -> Self {
+        ModeList::new()
+    }
+}
+
+/// What the display pipeline and the sink can handle
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct ModeLimits {
+    /// Hz
+    pub min_pixel_clock: u32,
+    pub max_pixel_clock: u32,
+    /// TCON1 horizontal total
+    pub max_htotal: u32,
+    /// TCON1 counts vertical half-lines in 12 bits
+    pub max_vtotal: u32,
+    /// The HDMI frame composer's vertical blanking register is a byte
+    pub max_vblank: u32,
+    /// TCON1 sync widths
+    pub max_sync_len: u32,
+    /// Frame buffer capacity in pixels
+    pub max_pixels: u32,
+}
+
+impl ModeLimits {
+    /// PLL_VIDEO0 spans 192-912 MHz, divided by at most 16 for TCON1, and
+    /// the PHY tops out at 297 MHz
+    pub const HARDWARE: ModeLimits = ModeLimits {
+        min_pixel_clock: 192_000_000 / 16,
+        max_pixel_clock: 297_000_000,
+        max_htotal: 4096,
+        max_vtotal: 2047,
+        max_vblank: 255,
+        max_sync_len: 1024,
+        max_pixels: u32::MAX,
+    };
+
+    /// Narrow the hardware limits by the sink's EDID
+    pub fn for_sink(edid: &Edid, cea: Option<&CeaExtension>) -> Self {
+        let mut limits = Self::HARDWARE;
+        if let Some(max) = edid.range_limits().and_then(|r| r.max_pixel_clock) {
+            limits.max_pixel_clock = limits.max_pixel_clock.min(max);
+        }
+        if let Some(max) = cea.and_then(|c| c.hdmi).and_then(|h| h.max_tmds_clock) {
+            limits.max_pixel_clock = limits.max_pixel_clock.min(max);
+        }
+        limits
+    }
+
+    pub fn supports(&self, timing: &DisplayTiming) -> bool {
+        let hblank = timing.hfront_porch.typ + timing.hsync_len.typ + timing.hback_porch.typ;
+        let vblank = timing.vfront_porch.typ + timing.vsync_len.typ + timing.vback_porch.typ;
+        let htotal = timing.hactive.typ + hblank;
+        let vtotal = timing.vactive.typ + vblank;
+
+        // TODO - interlaced output
+        !timing.flags.interlaced()
+            && timing.pixel_clock.typ >= self.min_pixel_clock
+            && timing.pixel_clock.typ <= self.max_pixel_clock
+            && timing.hactive.typ != 0
+            && timing.vactive.typ != 0
+            && timing.hsync_len.typ != 0
+            && timing.vsync_len.typ != 0
+            && timing.hsync_len.typ <= self.max_sync_len
+            && timing.vsync_len.typ <= self.max_sync_len
+            // TCON1 start delay needs a couple of blanking lines
+            && vblank > 2
+            && vblank <= self.max_vblank
+            && htotal <= self.max_htotal
+            && vtotal <= self.max_vtotal
+            && timing.hactive.typ * timing.vactive.typ <= self.max_pixels
+            // Deep colour is only picked when it also fits
+            && dw_hdmi::tmds_clock_supported(timing.pixel_clock.typ, ColorDepth::Bits8)
+    }
+}
+
+/// Vertical refresh rate in Hz, rounded
+pub fn refresh_rate(timing: &DisplayTiming) -> u32 {
+    let htotal = timing.hactive.typ
+        + timing.hfront_porch.typ
+        + timing.hsync_len.typ
+        + timing.hback_porch.typ;
+    let vtotal = timing.vactive.typ
+        + timing.vfront_porch.typ
+        + timing.vsync_len.typ
+        + timing.vback_porch.typ;
+    let pixels = u64::from(htotal) * u64::from(vtotal);
+    if pixels == 0 {
+        return 0;
+    }
+    ((u64::from(timing.pixel_clock.typ) + pixels / 2) / pixels) as u32
+}
+
+fn matches(timing: &DisplayTiming, mode: &VideoMode) -> bool {
+    timing.hactive.typ == u32::from(mode.hactive)
+        && timing.vactive.typ == u32::from(mode.vactive)
+        && timing.flags.interlaced() == mode.interlaced
+        && refresh_rate(timing) == u32::from(mode.refresh_rate)
+}
+
+/// Standard timings outside the DMT table are CVT generated
+fn video_mode_timing(mode: &VideoMode) -> Option<DisplayTiming> {
+    if mode.interlaced {
+        return None;
+    }
+    let (h, v, rate) = (
+        u32::from(mode.hactive),
+        u32::from(mode.vactive),
+        u32::from(mode.refresh_rate),
+    );
+    modes::lookup(h, v, rate).or_else(|| modes::cvt(h, v, rate, Blanking::Normal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select_bounds_by_frame_buffer() {
+        let mut list = ModeList::new();
+        list.push(modes::by_vic(16).unwrap());
+        list.push(modes::by_vic(4).unwrap());
+        let mut limits = ModeLimits::HARDWARE;
+
+        let preferred = list.select(&ModeSelection::Preferred, &limits);
+        assert_eq!(preferred.hactive.typ, 1920);
+
+        // Too small for 1080p, the list itself is left alone
+        limits.max_pixels = 1280 * 720;
+        let bounded = list.select(&ModeSelection::Preferred, &limits);
+        assert_eq!(bounded.hactive.typ, 1280);
+        assert_eq!(list.len(), 2);
+
+        limits.max_pixels = 0;
+        assert_eq!(list.select(&ModeSelection::Preferred, &limits), safe_mode());
+    }
+}
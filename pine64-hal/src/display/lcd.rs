@@ -0,0 +1,178 @@
+//! Parallel RGB LCD on TCON0
+//!
+//! The panel's 18 data lines, pixel clock, DE, HSYNC and VSYNC are PD0-PD21
+//! in AF0, the 24-bit frame buffer is dithered down to RGB666. Panels have
+//! no EDID, the timing comes from the panel's datasheet.
+//!
+//! MIXER0 feeds TCON0. The DE is shared with `HdmiDisplay`, only one of the
+//! two can be used at a time.
+
+use super::de2::DisplayEngine2;
+use super::lcdc::{Tcon0Controller, TCON0_MAX_DIV, TCON0_MIN_DIV};
+use super::{fb_addr, BitsPerPixel, Display, DisplayTiming};
+use crate::ccu::{Ccu, ClockSource, ModuleClock, Pll, PllFactors};
+use crate::gpio::{Alternate, Pin as GpioPin, Port, AF0};
+use crate::pac::{de::DE, de_mixer::MIXER0, tcon0::TCON0};
+use as_slice::AsSlice;
+use core::pin::Pin;
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Error {
+    /// PLL_VIDEO0 and the TCON0 divider can't produce the pixel clock, or
+    /// the timing is interlaced
+    UnsupportedMode,
+    /// The frame buffer is smaller than the mode, or not addressable by
+    /// the DE
+    FrameBuffer,
+    /// PD0-PD21 are needed, each once
+    Pins,
+}
+
+/// PD0-PD21
+const LCD_PIN_MASK: u32 = 0x003F_FFFF;
+
+/// Pixel clock error allowed, in 1/1000
+const PIXEL_CLOCK_TOLERANCE: u32 = 5;
+
+pub struct LcdDisplay<PINS> {
+    tcon: Tcon0Controller,
+    de: DisplayEngine2<MIXER0>,
+    _pins: PINS,
+    timing: DisplayTiming,
+}
+
+impl<PINS> LcdDisplay<PINS>
+where
+    PINS: AsSlice<Element = GpioPin<Alternate<AF0>>>,
+{
+    pub fn new(
+        tcon: TCON0,
+        mixer: MIXER0,
+        de: DE,
+        pins: PINS,
+        frame_buffer: &Pin<&'static mut [u32]>,
+        timing: DisplayTiming,
+        ccu: &mut Ccu,
+    ) -> Result<Self, Error> {
+        let mut mask = 0;
+        for pin in pins.as_slice() {
+            let bit = 1 << pin.pin_id();
+            if pin.port() != Port::D || mask & bit != 0 {
+                return Err(Error::Pins);
+            }
+            mask |= bit;
+        }
+        if mask != LCD_PIN_MASK {
+            return Err(Error::Pins);
+        }
+
+        // TODO - interlaced output
+        if timing.flags.interlaced() {
+            return Err(Error::UnsupportedMode);
+        }
+        if timing.hactive.typ * timing.vactive.typ > frame_buffer.len() as u32 {
+            return Err(Error::FrameBuffer);
+        }
+
+        let fb_addr = fb_addr(frame_buffer).ok_or(Error::FrameBuffer)?;
+
+        let div = set_tcon0_clock(&timing, ccu)?;
+
+        let bpp: BitsPerPixel = 32;
+        let mut de = DisplayEngine2::new(mixer, de, ccu);
+        de.set_mode(fb_addr, bpp, &timing);
+
+        // Reset, clock on
+        ccu.enable_and_reset::<TCON0>();
+        let mut tcon = Tcon0Controller::new(tcon);
+        tcon.set_mode(&timing, div);
+        tcon.enable();
+
+        Ok(LcdDisplay {
+            tcon,
+            de,
+            _pins: pins,
+            timing,
+        })
+    }
+}
+
+impl<PINS> Display for LcdDisplay<PINS> {
+    type Error = Error;
+
+    fn timing(&self) -> Option<&DisplayTiming> {
+        Some(&self.timing)
+    }
+
+    fn attach_framebuffer(&mut self, frame_buffer: &Pin<&'static mut [u32]>) -> Result<(), Error> {
+        if self.timing.hactive.typ * self.timing.vactive.typ > frame_buffer.len() as u32 {
+            return Err(Error::FrameBuffer);
+        }
+        let fb_addr = fb_addr(frame_buffer).ok_or(Error::FrameBuffer)?;
+        self.de.set_framebuffer(fb_addr);
+        Ok(())
+    }
+
+    fn set_blanking(&mut self, blank: bool) {
+        self.de.set_blanking(blank);
+    }
+
+    fn wait_for_vblank(&mut self) {
+        self.tcon.wait_for_vblank();
+    }
+
+    /// The panel's supply and backlight are up to the board
+    fn power_on(&mut self) {
+        self.tcon.enable();
+    }
+
+    fn power_off(&mut self) {
+        self.tcon.disable();
+    }
+}
+
+/// Set PLL_VIDEO0 and the TCON0 module clock for the pixel clock, returns
+/// the TCON0 data clock divider
+fn set_tcon0_clock(timing: &DisplayTiming, ccu: &mut Ccu) -> Result<u32, Error> {
+    let clk_khz = timing.pixel_clock.typ / 1000;
+    let mut best = None;
+    let mut best_diff = 0x0FFFFFFF;
+
+    for div in TCON0_MIN_DIV..=TCON0_MAX_DIV {
+        // TCON0 runs from PLL_VIDEO0 x2
+        let target = clk_khz * div / 2;
+
+        if target < 192000 {
+            continue;
+        }
+        if target > 912000 {
+            continue;
+        }
+
+        for m in 1..=16 {
+            let n = (m * target) / 24000;
+
+            if (1..=128).contains(&n) {
+                let value = (2 * 24000 * n) / m / div;
+                let diff = clk_khz - value;
+                if diff < best_diff {
+                    best_diff = diff;
+                    best = Some((div, n, m));
+                }
+            }
+        }
+    }
+
+    let (div, n, m) = best.ok_or(Error::UnsupportedMode)?;
+    if best_diff * 1000 > clk_khz * PIXEL_CLOCK_TOLERANCE {
+        return Err(Error::UnsupportedMode);
+    }
+
+    ccu.set_pll_factors(Pll::Video0, PllFactors::nm(n, m))
+        .map_err(|_| Error::UnsupportedMode)?;
+    let rate = ccu.clock_source_rate(ClockSource::PllVideo0x2);
+    ccu.set_module_clock(ModuleClock::Tcon0, ClockSource::PllVideo0x2, rate)
+        .map_err(|_| Error::UnsupportedMode)?;
+
+    Ok(div)
+}
@@ -3,9 +3,20 @@
 // TODO - generic over TCON0/TCON1
 
 use super::DisplayTiming;
+use crate::pac::tcon0::{self, TCON0};
 use crate::pac::tcon1::{
-    Control, DataClock, GlobalControl, Timing0, Timing1, Timing2, Timing3, Timing4, Timing5, TCON1,
+    Control, DataClock, GlobalControl, GlobalInt0, Timing0, Timing1, Timing2, Timing3, Timing4,
+    Timing5, TCON1,
 };
+use cortex_a::asm;
+
+/// FRM dithering down to the 18 data lines, values from u-boot
+const FRM_SEED: u32 = 0x1111_1111;
+const FRM_TABLE: [u32; 4] = [0x0101_0000, 0x1515_1111, 0x5757_5555, 0x7F7F_7777];
+
+/// TCON0 data clock divider range for the parallel RGB interface
+pub(crate) const TCON0_MIN_DIV: u32 = 6;
+pub(crate) const TCON0_MAX_DIV: u32 = 127;
 
 pub struct LcdController {
     tcon: TCON1,
@@ -81,6 +92,21 @@ impl LcdController {
         self.tcon.gctrl.modify(GlobalControl::Enable::Set);
     }
 
+    pub(crate) fn disable(&mut self) {
+        self.tcon.gctrl.modify(GlobalControl::Enable::Clear);
+    }
+
+    /// Returns at once while disabled
+    pub(crate) fn wait_for_vblank(&mut self) {
+        if !self.tcon.gctrl.is_set(GlobalControl::Enable::Read) {
+            return;
+        }
+        self.tcon.gint0.modify(GlobalInt0::Tcon1VblankFlag::Clear);
+        while !self.tcon.gint0.is_set(GlobalInt0::Tcon1VblankFlag::Read) {
+            asm::nop();
+        }
+    }
+
     fn clock_delay(&mut self, timing: &DisplayTiming) -> u32 {
         let mut delay = timing.vfront_porch.typ + timing.vsync_len.typ + timing.vback_porch.typ;
 
@@ -98,3 +124,130 @@ impl LcdController {
         }
     }
 }
+
+/// TCON0 driving a parallel RGB666 panel
+pub struct Tcon0Controller {
+    tcon: TCON0,
+}
+
+impl Tcon0Controller {
+    pub(crate) fn new(mut tcon: TCON0) -> Self {
+        tcon.gctrl.write(0);
+        tcon.gint0.write(0);
+
+        // Set all io lines to tristate
+        tcon.io_tristate.write(0xFFFF_FFFF);
+
+        Tcon0Controller { tcon }
+    }
+
+    /// `div` divides the TCON0 module clock down to the pixel clock
+    pub(crate) fn set_mode(&mut self, timing: &DisplayTiming, div: u32) {
+        let clk_delay = self.clock_delay(timing);
+
+        self.tcon
+            .gctrl
+            .modify(tcon0::GlobalControl::IoMapSelect::Clear);
+
+        self.tcon.ctrl.modify(
+            tcon0::Control::StartDelay::Field::new(clk_delay).unwrap()
+                + tcon0::Control::Enable::Set,
+        );
+        // All four data clock outputs
+        self.tcon.dclk.modify(
+            tcon0::DataClock::Divider::Field::new(div).unwrap()
+                + tcon0::DataClock::Enable::Field::new(0xF).unwrap(),
+        );
+
+        self.tcon.timing_active.modify(
+            tcon0::Timing0::X::Field::new(timing.hactive.typ - 1).unwrap()
+                + tcon0::Timing0::Y::Field::new(timing.vactive.typ - 1).unwrap(),
+        );
+
+        let bp = timing.hsync_len.typ + timing.hback_porch.typ;
+        let total = timing.hactive.typ + timing.hfront_porch.typ + bp;
+        self.tcon.timing_h.modify(
+            tcon0::Timing1::HorizBackPorch::Field::new(bp - 1).unwrap()
+                + tcon0::Timing1::Ht::Field::new(total - 1).unwrap(),
+        );
+
+        // Vertical total is in half-lines
+        let bp = timing.vsync_len.typ + timing.vback_porch.typ;
+        let total = timing.vactive.typ + timing.vfront_porch.typ + bp;
+        self.tcon.timing_v.modify(
+            tcon0::Timing2::VertBackPorch::Field::new(bp - 1).unwrap()
+                + tcon0::Timing2::Vt::Field::new(total * 2).unwrap(),
+        );
+
+        self.tcon.timing_sync.modify(
+            tcon0::Timing3::Vspw::Field::new(timing.vsync_len.typ - 1).unwrap()
+                + tcon0::Timing3::Hspw::Field::new(timing.hsync_len.typ - 1).unwrap(),
+        );
+
+        self.tcon.hv_iface.write(0);
+        self.tcon.cpu_iface.write(0);
+
+        for seed in self.tcon.frm_seed.iter_mut() {
+            seed.write(FRM_SEED);
+        }
+        for (table, value) in self.tcon.frm_table.iter_mut().zip(FRM_TABLE.iter()) {
+            table.write(*value);
+        }
+        self.tcon.frm_ctrl.modify(
+            tcon0::FrmControl::ModeR::SixBit
+                + tcon0::FrmControl::ModeG::SixBit
+                + tcon0::FrmControl::ModeB::SixBit
+                + tcon0::FrmControl::Enable::Set,
+        );
+
+        // IO0 is VSYNC and IO1 is HSYNC
+        self.tcon.io_polarity.write(0);
+        if timing.flags.vsync_low() {
+            self.tcon
+                .io_polarity
+                .modify(tcon0::IoPolarity::Io0Invert::Set);
+        }
+        if timing.flags.hsync_low() {
+            self.tcon
+                .io_polarity
+                .modify(tcon0::IoPolarity::Io1Invert::Set);
+        }
+
+        self.tcon.io_tristate.write(0);
+    }
+
+    pub(crate) fn enable(&mut self) {
+        self.tcon.gctrl.modify(tcon0::GlobalControl::Enable::Set);
+    }
+
+    pub(crate) fn disable(&mut self) {
+        self.tcon.gctrl.modify(tcon0::GlobalControl::Enable::Clear);
+    }
+
+    /// Returns at once while disabled
+    pub(crate) fn wait_for_vblank(&mut self) {
+        if !self.tcon.gctrl.is_set(tcon0::GlobalControl::Enable::Read) {
+            return;
+        }
+        self.tcon
+            .gint0
+            .modify(tcon0::GlobalInt0::Tcon0VblankFlag::Clear);
+        while !self
+            .tcon
+            .gint0
+            .is_set(tcon0::GlobalInt0::Tcon0VblankFlag::Read)
+        {
+            asm::nop();
+        }
+    }
+
+    fn clock_delay(&mut self, timing: &DisplayTiming) -> u32 {
+        let delay = timing.vfront_porch.typ + timing.vsync_len.typ + timing.vback_porch.typ;
+
+        if delay > 30 {
+            30
+        } else {
+            delay
+        }
+    }
+}
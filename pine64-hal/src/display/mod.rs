@@ -1,18 +1,60 @@
 //! Display pipeline
 
 // TODO
-// - Display trait to be impl'd by HdmiDisplay and LcdDisplay
 // - proper Delay trait and impl from a timer
 
 use bitfield::bitfield;
+use core::convert::TryFrom;
+use core::pin::Pin;
 
 mod de2;
 mod dw_hdmi;
 pub mod hdmi;
+pub mod lcd;
 mod lcdc;
+pub mod modes;
 
 pub type BitsPerPixel = u32;
 
+/// An output drawn from an XRGB8888 frame buffer, whatever the connector
+pub trait Display {
+    type Error;
+
+    /// The mode being driven, `None` without a sink
+    fn timing(&self) -> Option<&DisplayTiming>;
+
+    /// Scan out `frame_buffer` from the next frame on
+    ///
+    /// Fails when it's smaller than the mode. The frame buffer is kept for
+    /// later mode changes.
+    fn attach_framebuffer(
+        &mut self,
+        frame_buffer: &Pin<&'static mut [u32]>,
+    ) -> Result<(), Self::Error>;
+
+    /// Attach `frame_buffer` and wait until it's being scanned out, the
+    /// previous one can be drawn to once this returns
+    fn swap_framebuffer(
+        &mut self,
+        frame_buffer: &Pin<&'static mut [u32]>,
+    ) -> Result<(), Self::Error> {
+        self.attach_framebuffer(frame_buffer)?;
+        self.wait_for_vblank();
+        Ok(())
+    }
+
+    /// Show black instead of the frame buffer
+    fn set_blanking(&mut self, blank: bool);
+
+    /// Wait for the start of the next vertical blanking period, returns at
+    /// once while the output is off
+    fn wait_for_vblank(&mut self);
+
+    fn power_on(&mut self);
+
+    fn power_off(&mut self);
+}
+
 bitfield! {
     #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
     pub struct DisplayFlags(u16);
@@ -36,6 +78,15 @@ bitfield! {
     pub double_clock, set_double_clock : 10;
 }
 
+/// Scan out address, `None` when the frame buffer doesn't fit in the DE's
+/// 32-bit address space
+pub(crate) fn fb_addr(frame_buffer: &Pin<&'static mut [u32]>) -> Option<u32> {
+    let start = frame_buffer.as_ptr() as usize;
+    let end = start.checked_add(frame_buffer.len() * 4)?;
+    u32::try_from(end.saturating_sub(1)).ok()?;
+    u32::try_from(start).ok()
+}
+
 // TODO - this is a u-boot idiom, only the typ is used
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct TimingEntry {
@@ -0,0 +1,430 @@
+//! Standard display modes
+//!
+//! VESA DMT and CEA-861 progressive timings, and the VESA CVT formula for
+//! everything else.
+//!
+//! ```ignore
+//! let timing = modes::by_vic(16).unwrap();
+//! let timing = modes::lookup(1280, 1024, 60).unwrap();
+//! let timing = modes::cvt(2560, 1080, 60, Blanking::Reduced).unwrap();
+//! ```
+
+use super::{DisplayFlags, DisplayTiming, TimingEntry};
+use core::convert::TryFrom;
+
+/// CVT blanking
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Blanking {
+    /// CRT compatible
+    Normal,
+    /// Shorter blanking for digital sinks, CVT-RB
+    Reduced,
+}
+
+/// CEA-861 picture aspect ratio
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum PictureAspect {
+    FourThree,
+    SixteenNine,
+}
+
+/// Compact mode line, (active, front porch, sync, back porch)
+struct ModeLine {
+    /// CEA-861 video identification codes, 0 when unused
+    vics: [u8; 2],
+    clock_khz: u32,
+    h: [u16; 4],
+    v: [u16; 4],
+    /// Nominal, 60 for the 59.94 Hz variants
+    refresh_rate: u16,
+    hsync_high: bool,
+    vsync_high: bool,
+}
+
+impl ModeLine {
+    fn timing(&self) -> DisplayTiming {
+        let h = self.h;
+        let v = self.v;
+        timing(
+            self.clock_khz * 1000,
+            [h[0].into(), h[1].into(), h[2].into(), h[3].into()],
+            [v[0].into(), v[1].into(), v[2].into(), v[3].into()],
+            self.hsync_high,
+            self.vsync_high,
+        )
+    }
+
+    /// Same blanking, and a pixel clock within 0.5 %
+    fn matches(&self, timing: &DisplayTiming) -> bool {
+        let h = [
+            timing.hactive.typ,
+            timing.hfront_porch.typ,
+            timing.hsync_len.typ,
+            timing.hback_porch.typ,
+        ];
+        let v = [
+            timing.vactive.typ,
+            timing.vfront_porch.typ,
+            timing.vsync_len.typ,
+            timing.vback_porch.typ,
+        ];
+        let clock = self.clock_khz * 1000;
+        let diff = if clock > timing.pixel_clock.typ {
+            clock - timing.pixel_clock.typ
+        } else {
+            timing.pixel_clock.typ - clock
+        };
+        !timing.flags.interlaced()
+            && h.iter()
+                .zip(self.h.iter())
+                .all(|(a, b)| *a == u32::from(*b))
+            && v.iter()
+                .zip(self.v.iter())
+                .all(|(a, b)| *a == u32::from(*b))
+            && diff <= clock / 200
+    }
+}
+
+const fn cea(
+    vics: [u8; 2],
+    clock_khz: u32,
+    h: [u16; 4],
+    v: [u16; 4],
+    refresh_rate: u16,
+    sync_high: bool,
+) -> ModeLine {
+    ModeLine {
+        vics,
+        clock_khz,
+        h,
+        v,
+        refresh_rate,
+        hsync_high: sync_high,
+        vsync_high: sync_high,
+    }
+}
+
+const fn dmt(
+    clock_khz: u32,
+    h: [u16; 4],
+    v: [u16; 4],
+    refresh_rate: u16,
+    hsync_high: bool,
+    vsync_high: bool,
+) -> ModeLine {
+    ModeLine {
+        vics: [0, 0],
+        clock_khz,
+        h,
+        v,
+        refresh_rate,
+        hsync_high,
+        vsync_high,
+    }
+}
+
+// CEA-861-F progressive formats, then the DMT ones CEA doesn't cover
+#[rustfmt::skip]
+const MODE_LINES: [ModeLine; 44] = [
+    cea([1, 0], 25_175, [640, 16, 96, 48], [480, 10, 2, 33], 60, false),
+    cea([2, 3], 27_000, [720, 16, 62, 60], [480, 9, 6, 30], 60, false),
+    cea([4, 0], 74_250, [1280, 110, 40, 220], [720, 5, 5, 20], 60, true),
+    cea([14, 15], 54_000, [1440, 32, 124, 120], [480, 9, 6, 30], 60, false),
+    cea([16, 0], 148_500, [1920, 88, 44, 148], [1080, 4, 5, 36], 60, true),
+    cea([17, 18], 27_000, [720, 12, 64, 68], [576, 5, 5, 39], 50, false),
+    cea([19, 0], 74_250, [1280, 440, 40, 220], [720, 5, 5, 20], 50, true),
+    cea([29, 30], 54_000, [1440, 24, 128, 136], [576, 5, 5, 39], 50, false),
+    cea([31, 0], 148_500, [1920, 528, 44, 148], [1080, 4, 5, 36], 50, true),
+    cea([32, 0], 74_250, [1920, 638, 44, 148], [1080, 4, 5, 36], 24, true),
+    cea([33, 0], 74_250, [1920, 528, 44, 148], [1080, 4, 5, 36], 25, true),
+    cea([34, 0], 74_250, [1920, 88, 44, 148], [1080, 4, 5, 36], 30, true),
+    cea([60, 0], 59_400, [1280, 1760, 40, 220], [720, 5, 5, 20], 24, true),
+    cea([61, 0], 74_250, [1280, 2420, 40, 220], [720, 5, 5, 20], 25, true),
+    cea([62, 0], 74_250, [1280, 1760, 40, 220], [720, 5, 5, 20], 30, true),
+    cea([63, 0], 297_000, [1920, 88, 44, 148], [1080, 4, 5, 36], 120, true),
+    cea([64, 0], 297_000, [1920, 528, 44, 148], [1080, 4, 5, 36], 100, true),
+    cea([93, 0], 297_000, [3840, 1276, 88, 296], [2160, 8, 10, 72], 24, true),
+    cea([94, 0], 297_000, [3840, 1056, 88, 296], [2160, 8, 10, 72], 25, true),
+    cea([95, 0], 297_000, [3840, 176, 88, 296], [2160, 8, 10, 72], 30, true),
+    dmt(31_500, [640, 24, 40, 128], [480, 9, 3, 28], 72, false, false),
+    dmt(31_500, [640, 16, 64, 120], [480, 1, 3, 16], 75, false, false),
+    dmt(28_322, [720, 18, 108, 54], [400, 12, 2, 35], 70, false, true),
+    dmt(36_000, [800, 24, 72, 128], [600, 1, 2, 22], 56, true, true),
+    dmt(40_000, [800, 40, 128, 88], [600, 1, 4, 23], 60, true, true),
+    dmt(50_000, [800, 56, 120, 64], [600, 37, 6, 23], 72, true, true),
+    dmt(49_500, [800, 16, 80, 160], [600, 1, 3, 21], 75, true, true),
+    // Apple, listed in the EDID established timings
+    dmt(57_284, [832, 32, 64, 224], [624, 1, 3, 39], 75, false, false),
+    dmt(65_000, [1024, 24, 136, 160], [768, 3, 6, 29], 60, false, false),
+    dmt(75_000, [1024, 24, 136, 144], [768, 3, 6, 29], 70, false, false),
+    dmt(78_750, [1024, 16, 96, 176], [768, 1, 3, 28], 75, true, true),
+    dmt(108_000, [1152, 64, 128, 256], [864, 1, 3, 32], 75, true, true),
+    dmt(83_500, [1280, 72, 128, 200], [800, 3, 6, 22], 60, false, true),
+    dmt(108_000, [1280, 96, 112, 312], [960, 1, 3, 36], 60, true, true),
+    dmt(108_000, [1280, 48, 112, 248], [1024, 1, 3, 38], 60, true, true),
+    dmt(135_000, [1280, 16, 144, 248], [1024, 1, 3, 38], 75, true, true),
+    dmt(85_500, [1360, 64, 112, 256], [768, 3, 6, 18], 60, true, true),
+    dmt(85_500, [1366, 70, 143, 213], [768, 3, 3, 24], 60, true, true),
+    dmt(121_750, [1400, 88, 144, 232], [1050, 3, 4, 32], 60, false, true),
+    dmt(106_500, [1440, 80, 152, 232], [900, 3, 6, 25], 60, false, true),
+    dmt(108_000, [1600, 24, 80, 96], [900, 1, 3, 96], 60, true, true),
+    dmt(162_000, [1600, 64, 192, 304], [1200, 1, 3, 46], 60, true, true),
+    dmt(146_250, [1680, 104, 176, 280], [1050, 3, 6, 30], 60, false, true),
+    // Reduced blanking
+    dmt(154_000, [1920, 48, 32, 80], [1200, 3, 6, 26], 60, true, false),
+];
+
+/// Every timing in the table
+pub fn iter() -> impl Iterator<Item = DisplayTiming> {
+    MODE_LINES.iter().map(ModeLine::timing)
+}
+
+/// CEA-861 format by video identification code
+pub fn by_vic(vic: u8) -> Option<DisplayTiming> {
+    if vic == 0 {
+        return None;
+    }
+    MODE_LINES
+        .iter()
+        .find(|m| m.vics.contains(&vic))
+        .map(ModeLine::timing)
+}
+
+/// DMT or CEA-861 format by size and nominal refresh rate
+pub fn lookup(hactive: u32, vactive: u32, refresh_rate: u32) -> Option<DisplayTiming> {
+    MODE_LINES
+        .iter()
+        .find(|m| {
+            u32::from(m.h[0]) == hactive
+                && u32::from(m.v[0]) == vactive
+                && u32::from(m.refresh_rate) == refresh_rate
+        })
+        .map(ModeLine::timing)
+}
+
+/// 640x480@60, every sink has to accept it
+pub fn safe_mode() -> DisplayTiming {
+    MODE_LINES[0].timing()
+}
+
+/// CEA-861 video identification codes for `timing`, the 4:3 one first
+///
+/// Empty for non-CEA formats. The 59.94 Hz variants of the 60 Hz formats
+/// share their codes.
+pub fn vics(timing: &DisplayTiming) -> &'static [u8] {
+    MODE_LINES
+        .iter()
+        .find(|m| m.vics[0] != 0 && m.matches(timing))
+        .map(|m| {
+            let count = m.vics.iter().filter(|vic| **vic != 0).count();
+            &m.vics[..count]
+        })
+        .unwrap_or(&[])
+}
+
+/// `None` when `vic` isn't in the table
+pub fn picture_aspect(vic: u8) -> Option<PictureAspect> {
+    match vic {
+        0 => None,
+        1 | 2 | 14 | 17 | 29 => Some(PictureAspect::FourThree),
+        _ if MODE_LINES.iter().any(|m| m.vics.contains(&vic)) => Some(PictureAspect::SixteenNine),
+        _ => None,
+    }
+}
+
+// CVT 1.1 constants, times in picoseconds
+const CELL_GRAN: u64 = 8;
+const MIN_V_PORCH: u64 = 3;
+const MIN_V_BPORCH: u64 = 6;
+const MIN_VSYNC_BP_PS: u128 = 550_000_000;
+const CLOCK_STEP: u128 = 250_000;
+const RB_MIN_V_BLANK_PS: u128 = 460_000_000;
+const RB_H_BLANK: u64 = 160;
+const RB_H_SYNC: u64 = 32;
+const RB_H_BACK_PORCH: u64 = 80;
+const PS_PER_SEC: u128 = 1_000_000_000_000;
+
+/// Generate a VESA CVT timing, `None` when the size or rate is degenerate
+///
+/// The estimated line period and blanking duty cycle are kept as exact
+/// fractions, the results are only rounded down where the formula does.
+pub fn cvt(
+    hactive: u32,
+    vactive: u32,
+    refresh_rate: u32,
+    blanking: Blanking,
+) -> Option<DisplayTiming> {
+    let h = u64::from(hactive) / CELL_GRAN * CELL_GRAN;
+    let v = u64::from(vactive);
+    let rate = u128::from(refresh_rate);
+    if h == 0 || v == 0 || rate == 0 {
+        return None;
+    }
+    let vsync = cvt_vsync(h, v);
+
+    let (clock, hs, vs) = match blanking {
+        Blanking::Normal => {
+            // H_PERIOD_EST = period_num / period_den ps
+            let period_num = PS_PER_SEC.checked_sub(MIN_VSYNC_BP_PS * rate)?;
+            let period_den = rate * u128::from(v + MIN_V_PORCH);
+            if period_num == 0 {
+                return None;
+            }
+            let vsync_bp = u64::try_from(MIN_VSYNC_BP_PS * period_den / period_num + 1)
+                .ok()?
+                .max(vsync + MIN_V_BPORCH);
+
+            // Ideal blanking duty cycle in percent, C' - M' * H_PERIOD_EST
+            // with the period in us, at least 20 %, as duty_num / duty_den
+            let duty_den = 10_000_000 * period_den;
+            let duty_num = (30 * duty_den)
+                .saturating_sub(3 * period_num)
+                .max(20 * duty_den);
+            let h_cells = u128::from(h) * duty_num
+                / ((100 * duty_den - duty_num) * u128::from(2 * CELL_GRAN));
+            let hblank = u64::try_from(h_cells).ok()? * (2 * CELL_GRAN);
+            let htotal = h + hblank;
+            let hsync = htotal * 8 / 100 / CELL_GRAN * CELL_GRAN;
+            let hback = hblank / 2;
+
+            let clock =
+                u128::from(htotal) * PS_PER_SEC * period_den / period_num / CLOCK_STEP * CLOCK_STEP;
+            let hs = [h, hblank.checked_sub(hsync + hback)?, hsync, hback];
+            let vs = [v, MIN_V_PORCH, vsync, vsync_bp - vsync];
+            (clock, hs, vs)
+        }
+        Blanking::Reduced => {
+            let period_num = PS_PER_SEC.checked_sub(RB_MIN_V_BLANK_PS * rate)?;
+            let period_den = rate * u128::from(v);
+            if period_num == 0 {
+                return None;
+            }
+            let vblank = u64::try_from(RB_MIN_V_BLANK_PS * period_den / period_num + 1)
+                .ok()?
+                .max(MIN_V_PORCH + vsync + MIN_V_BPORCH);
+            let htotal = h + RB_H_BLANK;
+            let vtotal = v + vblank;
+
+            let clock = rate * u128::from(vtotal) * u128::from(htotal) / CLOCK_STEP * CLOCK_STEP;
+            let hfront = RB_H_BLANK - RB_H_SYNC - RB_H_BACK_PORCH;
+            let hs = [h, hfront, RB_H_SYNC, RB_H_BACK_PORCH];
+            let vs = [v, MIN_V_PORCH, vsync, vblank - MIN_V_PORCH - vsync];
+            (clock, hs, vs)
+        }
+    };
+
+    let narrow = |vals: [u64; 4]| -> Option<[u32; 4]> {
+        let mut out = [0; 4];
+        for (o, val) in out.iter_mut().zip(vals.iter()) {
+            *o = u32::try_from(*val).ok()?;
+        }
+        Some(out)
+    };
+    let clock = u32::try_from(clock).ok()?;
+    // Normal blanking has a negative hsync, reduced a negative vsync
+    let reduced = blanking == Blanking::Reduced;
+    Some(timing(clock, narrow(hs)?, narrow(vs)?, reduced, !reduced))
+}
+
+/// Vsync width encodes the aspect ratio
+fn cvt_vsync(h: u64, v: u64) -> u64 {
+    if v == h * 3 / 4 {
+        4
+    } else if v == h * 9 / 16 {
+        5
+    } else if v == h * 10 / 16 {
+        6
+    } else if v == h * 4 / 5 || v == h * 9 / 15 {
+        7
+    } else {
+        10
+    }
+}
+
+/// (active, front porch, sync, back porch)
+fn timing(
+    clock: u32,
+    h: [u32; 4],
+    v: [u32; 4],
+    hsync_high: bool,
+    vsync_high: bool,
+) -> DisplayTiming {
+    let entry = |val: u32| TimingEntry::new(val, val, val);
+    let mut flags = DisplayFlags(0);
+    flags.set_hsync_high(hsync_high);
+    flags.set_hsync_low(!hsync_high);
+    flags.set_vsync_high(vsync_high);
+    flags.set_vsync_low(!vsync_high);
+    DisplayTiming {
+        pixel_clock: entry(clock),
+        hactive: entry(h[0]),
+        hfront_porch: entry(h[1]),
+        hsync_len: entry(h[2]),
+        hback_porch: entry(h[3]),
+        vactive: entry(v[0]),
+        vfront_porch: entry(v[1]),
+        vsync_len: entry(v[2]),
+        vback_porch: entry(v[3]),
+        flags,
+        hdmi_monitor: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (clock, h, v) as in the DMT and the VESA CVT calculator
+    fn assert_timing(t: DisplayTiming, clock: u32, h: [u32; 4], v: [u32; 4]) {
+        assert_eq!(t.pixel_clock.typ, clock);
+        assert_eq!(
+            [
+                t.hactive.typ,
+                t.hfront_porch.typ,
+                t.hsync_len.typ,
+                t.hback_porch.typ
+            ],
+            h
+        );
+        assert_eq!(
+            [
+                t.vactive.typ,
+                t.vfront_porch.typ,
+                t.vsync_len.typ,
+                t.vback_porch.typ
+            ],
+            v
+        );
+    }
+
+    #[test]
+    fn cvt_1920x1080_60() {
+        let t = cvt(1920, 1080, 60, Blanking::Normal).unwrap();
+        assert_timing(t, 173_000_000, [1920, 128, 200, 328], [1080, 3, 5, 32]);
+        assert!(t.flags.hsync_low() && t.flags.vsync_high());
+    }
+
+    #[test]
+    fn cvt_rb_1920x1080_60() {
+        let t = cvt(1920, 1080, 60, Blanking::Reduced).unwrap();
+        assert_timing(t, 138_500_000, [1920, 48, 32, 80], [1080, 3, 5, 23]);
+        assert!(t.flags.hsync_high() && t.flags.vsync_low());
+    }
+
+    #[test]
+    fn cvt_matches_dmt_1280x768_60() {
+        let t = cvt(1280, 768, 60, Blanking::Normal).unwrap();
+        assert_timing(t, 79_500_000, [1280, 64, 128, 192], [768, 3, 7, 20]);
+        let t = cvt(1280, 768, 60, Blanking::Reduced).unwrap();
+        assert_timing(t, 68_250_000, [1280, 48, 32, 80], [768, 3, 7, 12]);
+    }
+
+    #[test]
+    fn cvt_rejects_degenerate() {
+        assert!(cvt(0, 1080, 60, Blanking::Normal).is_none());
+        assert!(cvt(1920, 0, 60, Blanking::Reduced).is_none());
+        assert!(cvt(1920, 1080, 0, Blanking::Normal).is_none());
+        // The minimum vertical blanking alone is longer than the field
+        assert!(cvt(1920, 1080, 2000, Blanking::Reduced).is_none());
+    }
+}
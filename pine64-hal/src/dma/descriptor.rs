@@ -132,6 +132,11 @@ pub enum DataWidth {
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub enum DrqPort {
     SdRam,
+    I2s0,
+    I2s1,
+    I2s2,
+    Spi0,
+    Spi1,
 }
 
 impl Config {
@@ -141,12 +146,22 @@ impl Config {
     pub fn set_src_drq_port(&mut self, drq: DrqPort) {
         match drq {
             DrqPort::SdRam => self.set_src_drq(1),
+            DrqPort::I2s0 => self.set_src_drq(3),
+            DrqPort::I2s1 => self.set_src_drq(4),
+            DrqPort::I2s2 => self.set_src_drq(27),
+            DrqPort::Spi0 => self.set_src_drq(23),
+            DrqPort::Spi1 => self.set_src_drq(24),
         }
     }
 
     pub fn set_dst_drq_port(&mut self, drq: DrqPort) {
         match drq {
             DrqPort::SdRam => self.set_dst_drq(1),
+            DrqPort::I2s0 => self.set_dst_drq(3),
+            DrqPort::I2s1 => self.set_dst_drq(4),
+            DrqPort::I2s2 => self.set_dst_drq(27),
+            DrqPort::Spi0 => self.set_dst_drq(23),
+            DrqPort::Spi1 => self.set_dst_drq(24),
         }
     }
 
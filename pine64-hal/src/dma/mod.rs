@@ -12,7 +12,6 @@
 
 use crate::ccu::Ccu;
 use crate::pac::{
-    ccu::{BusClockGating0, BusSoftReset0},
     dma::channel::ChannelEnable,
     dma::{AutoGating, Security, Status, DMA},
 };
@@ -56,9 +55,7 @@ impl DmaExt for DMA {
     type Parts = Dma;
 
     fn split(self, ccu: &mut Ccu) -> Self::Parts {
-        ccu.bsr0.rstr().modify(BusSoftReset0::Dma::Clear);
-        ccu.bsr0.rstr().modify(BusSoftReset0::Dma::Set);
-        ccu.bcg0.enr().modify(BusClockGating0::Dma::Set);
+        ccu.enable_and_reset::<DMA>();
 
         let mut dma = unsafe { DMA::from_paddr() };
 
@@ -210,26 +207,39 @@ impl ChannelNumber {
 }
 
 impl Channel {
-    fn is_active(&self) -> bool {
+    pub(crate) fn is_active(&self) -> bool {
         match self.chan_num {
             ChannelNumber::Ch0 => self.dma.status.is_set(Status::Ch0Busy::Read),
         }
     }
 
-    fn enable(&mut self) {
+    pub(crate) fn enable(&mut self) {
         let chan = self.chan_num.into_index();
         self.dma.channels[chan]
             .enable
             .modify(ChannelEnable::Enable::Set);
     }
 
-    fn set_desc_addr(&mut self, desc: &Descriptor) {
+    pub(crate) fn disable(&mut self) {
+        let chan = self.chan_num.into_index();
+        self.dma.channels[chan]
+            .enable
+            .modify(ChannelEnable::Enable::Clear);
+    }
+
+    /// Address the channel is currently reading from
+    pub(crate) fn current_src_addr(&self) -> u32 {
+        let chan = self.chan_num.into_index();
+        self.dma.channels[chan].cur_src.read()
+    }
+
+    pub(crate) fn set_desc_addr(&mut self, desc: &Descriptor) {
         let addr = desc.as_ptr() as u32;
         let chan = self.chan_num.into_index();
         self.dma.channels[chan].desc_addr.write(addr);
     }
 
-    fn set_nonsecure(&mut self) {
+    pub(crate) fn set_nonsecure(&mut self) {
         match self.chan_num {
             ChannelNumber::Ch0 => self.dma.security.modify(Security::Ch0::NonSecure),
         }
@@ -0,0 +1,241 @@
+//! Pins whose mode is chosen at runtime
+//!
+//! ```ignore
+//! let mut pin = gpio.pb.pb2.into_dynamic();
+//! pin.make_push_pull_output();
+//! pin.set_high()?;
+//! pin.make_pull_up_input();
+//! let strap = pin.is_high()?;
+//! pin.make_floating_input();
+//! let pin = PB2::<Input<Floating>>::try_from(pin)?;
+//! ```
+
+use super::erased::{Port, RawPin, CFG_DISABLED, CFG_INPUT, CFG_OUTPUT};
+use super::{Disabled, Floating, Input, OpenDrain, Output, Pull, PullDown, PullUp, PushPull};
+use crate::hal::digital::v2::{InputPin, OutputPin, StatefulOutputPin};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Dynamic {
+    InputFloating,
+    InputPullUp,
+    InputPullDown,
+    OutputPushPull,
+    OutputOpenDrain,
+    Disabled,
+}
+
+impl Dynamic {
+    pub fn is_input(self) -> bool {
+        use Dynamic::*;
+        match self {
+            InputFloating | InputPullUp | InputPullDown | OutputOpenDrain => true,
+            OutputPushPull | Disabled => false,
+        }
+    }
+
+    pub fn is_output(self) -> bool {
+        use Dynamic::*;
+        match self {
+            OutputPushPull | OutputOpenDrain => true,
+            InputFloating | InputPullUp | InputPullDown | Disabled => false,
+        }
+    }
+}
+
+/// Typed pin modes with a runtime equivalent
+pub trait DynamicMode {
+    const DYNAMIC: Dynamic;
+}
+
+impl DynamicMode for Input<Floating> {
+    const DYNAMIC: Dynamic = Dynamic::InputFloating;
+}
+
+impl DynamicMode for Input<PullUp> {
+    const DYNAMIC: Dynamic = Dynamic::InputPullUp;
+}
+
+impl DynamicMode for Input<PullDown> {
+    const DYNAMIC: Dynamic = Dynamic::InputPullDown;
+}
+
+impl DynamicMode for Output<PushPull> {
+    const DYNAMIC: Dynamic = Dynamic::OutputPushPull;
+}
+
+impl DynamicMode for Output<OpenDrain> {
+    const DYNAMIC: Dynamic = Dynamic::OutputOpenDrain;
+}
+
+impl DynamicMode for Disabled {
+    const DYNAMIC: Dynamic = Dynamic::Disabled;
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum PinModeError {
+    /// The pin's current mode doesn't support the operation, or doesn't
+    /// match the typed pin it's converted to
+    IncorrectMode,
+    /// The pin isn't the one it's converted to
+    IncorrectPin,
+}
+
+/// Type-erased pin with a runtime mode
+pub struct DynamicPin {
+    pin: RawPin,
+    mode: Dynamic,
+}
+
+impl DynamicPin {
+    pub(crate) fn new(port: Port, i: u8, mode: Dynamic) -> Self {
+        DynamicPin {
+            pin: RawPin::new(port, i),
+            mode,
+        }
+    }
+
+    pub fn port(&self) -> Port {
+        self.pin.port
+    }
+
+    /// Pin index within the port
+    pub fn pin_id(&self) -> u8 {
+        self.pin.i
+    }
+
+    pub fn mode(&self) -> Dynamic {
+        self.mode
+    }
+
+    /// Check the pin can become the typed pin `i` of `port` in `MODE`
+    pub(crate) fn check<MODE: DynamicMode>(&self, port: Port, i: u8) -> Result<(), PinModeError> {
+        if self.pin != RawPin::new(port, i) {
+            Err(PinModeError::IncorrectPin)
+        } else if self.mode != MODE::DYNAMIC {
+            Err(PinModeError::IncorrectMode)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn make_floating_input(&mut self) {
+        self.pin.set_cfg(CFG_INPUT);
+        self.pin.set_pull(Pull::Disabled);
+        self.mode = Dynamic::InputFloating;
+    }
+
+    pub fn make_pull_up_input(&mut self) {
+        self.pin.set_cfg(CFG_INPUT);
+        self.pin.set_pull(Pull::Up);
+        self.mode = Dynamic::InputPullUp;
+    }
+
+    pub fn make_pull_down_input(&mut self) {
+        self.pin.set_cfg(CFG_INPUT);
+        self.pin.set_pull(Pull::Down);
+        self.mode = Dynamic::InputPullDown;
+    }
+
+    pub fn make_push_pull_output(&mut self) {
+        self.pin.set_cfg(CFG_OUTPUT);
+        self.pin.set_pull(Pull::Disabled);
+        self.mode = Dynamic::OutputPushPull;
+    }
+
+    /// Released, with the internal pull-up enabled
+    pub fn make_open_drain_output(&mut self) {
+        self.pin.set_low();
+        self.pin.set_cfg(CFG_INPUT);
+        self.pin.set_pull(Pull::Up);
+        self.mode = Dynamic::OutputOpenDrain;
+    }
+
+    pub fn make_disabled(&mut self) {
+        self.pin.set_cfg(CFG_DISABLED);
+        self.pin.set_pull(Pull::Disabled);
+        self.mode = Dynamic::Disabled;
+    }
+}
+
+impl OutputPin for DynamicPin {
+    type Error = PinModeError;
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        match self.mode {
+            Dynamic::OutputPushPull => self.pin.set_high(),
+            Dynamic::OutputOpenDrain => self.pin.release(),
+            _ => return Err(PinModeError::IncorrectMode),
+        }
+        Ok(())
+    }
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        match self.mode {
+            Dynamic::OutputPushPull => self.pin.set_low(),
+            Dynamic::OutputOpenDrain => self.pin.drive_low(),
+            _ => return Err(PinModeError::IncorrectMode),
+        }
+        Ok(())
+    }
+}
+
+impl StatefulOutputPin for DynamicPin {
+    fn is_set_high(&self) -> Result<bool, Self::Error> {
+        self.is_set_low().map(|b| !b)
+    }
+
+    fn is_set_low(&self) -> Result<bool, Self::Error> {
+        match self.mode {
+            Dynamic::OutputPushPull => Ok(!self.pin.is_high()),
+            Dynamic::OutputOpenDrain => Ok(self.pin.cfg() == CFG_OUTPUT),
+            _ => Err(PinModeError::IncorrectMode),
+        }
+    }
+}
+
+impl InputPin for DynamicPin {
+    type Error = PinModeError;
+
+    /// Outputs read back the level they drive
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        if self.mode.is_input() || self.mode.is_output() {
+            Ok(self.pin.is_high())
+        } else {
+            Err(PinModeError::IncorrectMode)
+        }
+    }
+
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        self.is_high().map(|b| !b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_conversion_checks_pin_and_mode() {
+        let pin = DynamicPin::new(Port::B, 2, Dynamic::InputFloating);
+        assert_eq!(pin.check::<Input<Floating>>(Port::B, 2), Ok(()));
+        assert_eq!(
+            pin.check::<Input<PullUp>>(Port::B, 2),
+            Err(PinModeError::IncorrectMode)
+        );
+        assert_eq!(
+            pin.check::<Input<Floating>>(Port::B, 3),
+            Err(PinModeError::IncorrectPin)
+        );
+        assert_eq!(
+            pin.check::<Input<Floating>>(Port::C, 2),
+            Err(PinModeError::IncorrectPin)
+        );
+
+        let pin = DynamicPin::new(Port::L, 10, Dynamic::OutputOpenDrain);
+        assert_eq!(pin.check::<Output<OpenDrain>>(Port::L, 10), Ok(()));
+        assert_eq!(
+            pin.check::<Output<PushPull>>(Port::L, 10),
+            Err(PinModeError::IncorrectMode)
+        );
+    }
+}
@@ -0,0 +1,483 @@
+//! Type-erased pins
+//!
+//! ```ignore
+//! let mut leds = [
+//!     gpio.pd.pd18.into_push_pull_output().downgrade().downgrade(),
+//!     rgpio.pl.pl10.into_push_pull_output().downgrade().downgrade(),
+//! ];
+//! ```
+//!
+//! `PXx` pins keep their port in the type, `Pin` carries it at runtime.
+//! The PAC register is picked from the port and pin index, its fields are
+//! then accessed by shift and mask rather than through the PAC field types.
+
+use super::{DriveStrength, Input, OpenDrain, Output, Pull, PushPull};
+use crate::hal::digital::v2::{toggleable, InputPin, OutputPin, StatefulOutputPin};
+use crate::pac::pio::PIO;
+use crate::pac::r_pio::RPIO;
+use core::convert::Infallible;
+use core::marker::PhantomData;
+use cortex_a::regs::*;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Port {
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    L,
+}
+
+/// A register of a port's group, the index selects which of the config,
+/// drive or pull registers
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub(crate) enum Reg {
+    Cfg(usize),
+    Data,
+    Driv(usize),
+    Pull(usize),
+}
+
+// PxY_Select values
+pub(crate) const CFG_INPUT: u32 = 0b000;
+pub(crate) const CFG_OUTPUT: u32 = 0b001;
+pub(crate) const CFG_DISABLED: u32 = 0b111;
+
+impl Port {
+    /// Number of pins on the port
+    pub fn width(self) -> u8 {
+        match self {
+            Port::B => 10,
+            Port::C => 17,
+            Port::D => 25,
+            Port::E => 18,
+            Port::F => 7,
+            Port::G => 14,
+            Port::H => 12,
+            Port::L => 13,
+        }
+    }
+
+    /// Read-modify-write with interrupts masked, so a handler's update of
+    /// other pins in the register isn't lost
+    pub(crate) fn modify<F: FnOnce(u32) -> u32>(self, reg: Reg, f: F) {
+        interrupt_free(|| {
+            let value = self.read(reg);
+            self.write(reg, f(value));
+        });
+    }
+}
+
+/// Run `f` with IRQs and FIQs masked, restoring the previous masks
+pub(crate) fn interrupt_free<R, F: FnOnce() -> R>(f: F) -> R {
+    let daif = DAIF.get();
+    DAIF.modify(DAIF::I::Masked + DAIF::F::Masked);
+    let r = f();
+    DAIF.set(daif);
+    r
+}
+
+/// Registers of each port, a pin index within `Port::width` never selects
+/// one that is missing
+macro_rules! port_registers {
+    ($($port:ident: $PIO:ident, [$($reg:pat => $field:ident,)+],)+) => {
+        impl Port {
+            pub(crate) fn read(self, reg: Reg) -> u32 {
+                match (self, reg) {
+                    $($((Port::$port, $reg) => unsafe { (*$PIO::ptr()).$field.read() },)+)+
+                    (port, reg) => unreachable!("{:?} has no {:?}", port, reg),
+                }
+            }
+
+            pub(crate) fn write(self, reg: Reg, value: u32) {
+                match (self, reg) {
+                    $($((Port::$port, $reg) => unsafe { (*$PIO::mut_ptr()).$field.write(value) },)+)+
+                    (port, reg) => unreachable!("{:?} has no {:?}", port, reg),
+                }
+            }
+        }
+    };
+}
+
+port_registers! {
+    B: PIO, [
+        Reg::Cfg(0) => pb_cfg0,
+        Reg::Cfg(1) => pb_cfg1,
+        Reg::Data => pb_data,
+        Reg::Driv(0) => pb_driv0,
+        Reg::Pull(0) => pb_pull0,
+    ],
+    C: PIO, [
+        Reg::Cfg(0) => pc_cfg0,
+        Reg::Cfg(1) => pc_cfg1,
+        Reg::Cfg(2) => pc_cfg2,
+        Reg::Data => pc_data,
+        Reg::Driv(0) => pc_driv0,
+        Reg::Driv(1) => pc_driv1,
+        Reg::Pull(0) => pc_pull0,
+        Reg::Pull(1) => pc_pull1,
+    ],
+    D: PIO, [
+        Reg::Cfg(0) => pd_cfg0,
+        Reg::Cfg(1) => pd_cfg1,
+        Reg::Cfg(2) => pd_cfg2,
+        Reg::Cfg(3) => pd_cfg3,
+        Reg::Data => pd_data,
+        Reg::Driv(0) => pd_driv0,
+        Reg::Driv(1) => pd_driv1,
+        Reg::Pull(0) => pd_pull0,
+        Reg::Pull(1) => pd_pull1,
+    ],
+    E: PIO, [
+        Reg::Cfg(0) => pe_cfg0,
+        Reg::Cfg(1) => pe_cfg1,
+        Reg::Cfg(2) => pe_cfg2,
+        Reg::Data => pe_data,
+        Reg::Driv(0) => pe_driv0,
+        Reg::Driv(1) => pe_driv1,
+        Reg::Pull(0) => pe_pull0,
+        Reg::Pull(1) => pe_pull1,
+    ],
+    F: PIO, [
+        Reg::Cfg(0) => pf_cfg0,
+        Reg::Data => pf_data,
+        Reg::Driv(0) => pf_driv0,
+        Reg::Pull(0) => pf_pull0,
+    ],
+    G: PIO, [
+        Reg::Cfg(0) => pg_cfg0,
+        Reg::Cfg(1) => pg_cfg1,
+        Reg::Data => pg_data,
+        Reg::Driv(0) => pg_driv0,
+        Reg::Pull(0) => pg_pull0,
+    ],
+    H: PIO, [
+        Reg::Cfg(0) => ph_cfg0,
+        Reg::Cfg(1) => ph_cfg1,
+        Reg::Data => ph_data,
+        Reg::Driv(0) => ph_driv0,
+        Reg::Pull(0) => ph_pull0,
+    ],
+    L: RPIO, [
+        Reg::Cfg(0) => pl_cfg0,
+        Reg::Cfg(1) => pl_cfg1,
+        Reg::Data => pl_data,
+        Reg::Driv(0) => pl_driv0,
+        Reg::Driv(1) => pl_driv1,
+        Reg::Pull(0) => pl_pull0,
+        Reg::Pull(1) => pl_pull1,
+    ],
+}
+
+/// Runtime register access to a single pin
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub(crate) struct RawPin {
+    pub(crate) port: Port,
+    pub(crate) i: u8,
+}
+
+impl RawPin {
+    pub(crate) const fn new(port: Port, i: u8) -> Self {
+        RawPin { port, i }
+    }
+
+    /// Register and bit shift of a field `width` bits wide
+    fn field(&self, reg: fn(usize) -> Reg, width: usize) -> (Reg, u32) {
+        let per_reg = 32 / width;
+        let i = usize::from(self.i);
+        (reg(i / per_reg), ((i % per_reg) * width) as u32)
+    }
+
+    pub(crate) fn cfg(&self) -> u32 {
+        let (reg, shift) = self.field(Reg::Cfg, 4);
+        (self.port.read(reg) >> shift) & 0b111
+    }
+
+    pub(crate) fn set_cfg(&self, cfg: u32) {
+        let (reg, shift) = self.field(Reg::Cfg, 4);
+        self.port
+            .modify(reg, |r| (r & !(0b111 << shift)) | ((cfg & 0b111) << shift));
+    }
+
+    pub(crate) fn set_pull(&self, pull: Pull) {
+        let val = match pull {
+            Pull::Disabled => 0,
+            Pull::Up => 1,
+            Pull::Down => 2,
+        };
+        self.set_pull_bits(val);
+    }
+
+    pub(crate) fn set_pull_bits(&self, val: u32) {
+        let (reg, shift) = self.field(Reg::Pull, 2);
+        self.port
+            .modify(reg, |r| (r & !(0b11 << shift)) | ((val & 0b11) << shift));
+    }
+
+    pub(crate) fn set_drive_strength(&self, level: DriveStrength) {
+        let val = match level {
+            DriveStrength::L0_10mA => 0,
+            DriveStrength::L1_20mA => 1,
+            DriveStrength::L2_30mA => 2,
+            DriveStrength::L3_40mA => 3,
+        };
+        self.set_drive_bits(val);
+    }
+
+    pub(crate) fn set_drive_bits(&self, val: u32) {
+        let (reg, shift) = self.field(Reg::Driv, 2);
+        self.port
+            .modify(reg, |r| (r & !(0b11 << shift)) | ((val & 0b11) << shift));
+    }
+
+    pub(crate) fn is_high(&self) -> bool {
+        self.port.read(Reg::Data) & (1 << self.i) != 0
+    }
+
+    pub(crate) fn set_high(&self) {
+        self.port.modify(Reg::Data, |r| r | (1 << self.i));
+    }
+
+    pub(crate) fn set_low(&self) {
+        self.port.modify(Reg::Data, |r| r & !(1 << self.i));
+    }
+
+    pub(crate) fn state(&self) -> (u32, u32, u32) {
+        let (pull_reg, pull_shift) = self.field(Reg::Pull, 2);
+        let (driv_reg, driv_shift) = self.field(Reg::Driv, 2);
+        (
+            self.cfg(),
+            (self.port.read(pull_reg) >> pull_shift) & 0b11,
+            (self.port.read(driv_reg) >> driv_shift) & 0b11,
+        )
+    }
+
+    /// Restores drive and pull before the config, so an output comes back
+    /// fully set up
+    pub(crate) fn set_state(&self, (cfg, pull, drive): (u32, u32, u32)) {
+        self.set_drive_bits(drive);
+        self.set_pull_bits(pull);
+        self.set_cfg(cfg);
+    }
+
+    /// Emulated open drain, release the line
+    pub(crate) fn release(&self) {
+        self.set_cfg(CFG_INPUT);
+    }
+
+    /// Emulated open drain, drive the line low
+    pub(crate) fn drive_low(&self) {
+        // Other pins' read-modify-write cycles may have latched a 1
+        self.set_low();
+        self.set_cfg(CFG_OUTPUT);
+    }
+}
+
+/// Saved config, pull and drive strength fields of a pin in mode `MODE`
+pub struct PinSnapshot<MODE> {
+    state: (u32, u32, u32),
+    _mode: PhantomData<MODE>,
+}
+
+impl<MODE> PinSnapshot<MODE> {
+    pub(crate) fn take(pin: RawPin) -> Self {
+        PinSnapshot {
+            state: pin.state(),
+            _mode: PhantomData,
+        }
+    }
+
+    pub(crate) fn apply(&self, pin: RawPin) {
+        pin.set_state(self.state);
+    }
+}
+
+impl<MODE> Clone for PinSnapshot<MODE> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<MODE> Copy for PinSnapshot<MODE> {}
+
+/// Digital trait impls for the erased pins, in terms of their `RawPin`
+macro_rules! erased_digital {
+    ($PXx:ident) => {
+        impl<MODE> $PXx<MODE> {
+            pub fn set_drive_strength(&mut self, level: DriveStrength) {
+                self.raw().set_drive_strength(level);
+            }
+        }
+
+        impl<MODE> $PXx<Output<MODE>> {
+            pub fn set_pull(&mut self, pull: Pull) {
+                self.raw().set_pull(pull);
+            }
+        }
+
+        impl OutputPin for $PXx<Output<PushPull>> {
+            type Error = Infallible;
+
+            fn set_high(&mut self) -> Result<(), Self::Error> {
+                Ok(self.raw().set_high())
+            }
+
+            fn set_low(&mut self) -> Result<(), Self::Error> {
+                Ok(self.raw().set_low())
+            }
+        }
+
+        impl StatefulOutputPin for $PXx<Output<PushPull>> {
+            fn is_set_high(&self) -> Result<bool, Self::Error> {
+                Ok(self.raw().is_high())
+            }
+
+            fn is_set_low(&self) -> Result<bool, Self::Error> {
+                self.is_set_high().map(|b| !b)
+            }
+        }
+
+        impl toggleable::Default for $PXx<Output<PushPull>> {}
+
+        impl InputPin for $PXx<Output<PushPull>> {
+            type Error = Infallible;
+
+            fn is_high(&self) -> Result<bool, Self::Error> {
+                Ok(self.raw().is_high())
+            }
+
+            fn is_low(&self) -> Result<bool, Self::Error> {
+                self.is_high().map(|b| !b)
+            }
+        }
+
+        impl OutputPin for $PXx<Output<OpenDrain>> {
+            type Error = Infallible;
+
+            fn set_high(&mut self) -> Result<(), Self::Error> {
+                Ok(self.raw().release())
+            }
+
+            fn set_low(&mut self) -> Result<(), Self::Error> {
+                Ok(self.raw().drive_low())
+            }
+        }
+
+        impl StatefulOutputPin for $PXx<Output<OpenDrain>> {
+            fn is_set_high(&self) -> Result<bool, Self::Error> {
+                self.is_set_low().map(|b| !b)
+            }
+
+            fn is_set_low(&self) -> Result<bool, Self::Error> {
+                Ok(self.raw().cfg() == CFG_OUTPUT)
+            }
+        }
+
+        impl toggleable::Default for $PXx<Output<OpenDrain>> {}
+
+        impl InputPin for $PXx<Output<OpenDrain>> {
+            type Error = Infallible;
+
+            fn is_high(&self) -> Result<bool, Self::Error> {
+                Ok(self.raw().is_high())
+            }
+
+            fn is_low(&self) -> Result<bool, Self::Error> {
+                self.is_high().map(|b| !b)
+            }
+        }
+
+        impl<MODE> InputPin for $PXx<Input<MODE>> {
+            type Error = Infallible;
+
+            fn is_high(&self) -> Result<bool, Self::Error> {
+                Ok(self.raw().is_high())
+            }
+
+            fn is_low(&self) -> Result<bool, Self::Error> {
+                self.is_high().map(|b| !b)
+            }
+        }
+    };
+}
+
+macro_rules! port_erased {
+    ($($PXx:ident: ($port:expr, $doc:expr),)+) => {
+        $(
+        #[doc = $doc]
+        pub struct $PXx<MODE> {
+            i: u8,
+            _mode: PhantomData<MODE>,
+        }
+
+        impl<MODE> $PXx<MODE> {
+            pub(crate) fn new(i: u8) -> Self {
+                $PXx {
+                    i,
+                    _mode: PhantomData,
+                }
+            }
+
+            fn raw(&self) -> RawPin {
+                RawPin::new($port, self.i)
+            }
+
+            /// Pin index within the port
+            pub fn pin_id(&self) -> u8 {
+                self.i
+            }
+
+            /// Erase the port
+            pub fn downgrade(self) -> Pin<MODE> {
+                Pin::new($port, self.i)
+            }
+        }
+
+        erased_digital!($PXx);
+        )+
+    }
+}
+
+port_erased! {
+    PBx: (Port::B, "Port B pin with the index erased"),
+    PCx: (Port::C, "Port C pin with the index erased"),
+    PDx: (Port::D, "Port D pin with the index erased"),
+    PEx: (Port::E, "Port E pin with the index erased"),
+    PFx: (Port::F, "Port F pin with the index erased"),
+    PGx: (Port::G, "Port G pin with the index erased"),
+    PHx: (Port::H, "Port H pin with the index erased"),
+    PLx: (Port::L, "Port L pin with the index erased"),
+}
+
+/// Pin with both the port and index erased
+pub struct Pin<MODE> {
+    pin: RawPin,
+    _mode: PhantomData<MODE>,
+}
+
+impl<MODE> Pin<MODE> {
+    pub(crate) fn new(port: Port, i: u8) -> Self {
+        Pin {
+            pin: RawPin::new(port, i),
+            _mode: PhantomData,
+        }
+    }
+
+    pub(crate) fn raw(&self) -> RawPin {
+        self.pin
+    }
+
+    pub fn port(&self) -> Port {
+        self.pin.port
+    }
+
+    /// Pin index within the port
+    pub fn pin_id(&self) -> u8 {
+        self.pin.i
+    }
+}
+
+erased_digital!(Pin);
@@ -0,0 +1,1413 @@
+//! General Purpose Input / Output
+//!
+//! Abstracted over the PIO (CPUx-PORT port controller)
+//!
+//! Port B (PB): 10 input/output port
+//! Port C (PC): 17 input/output port
+//! Port D (PD): 25 input/output port
+//! Port E (PE): 18 input/output port
+//! Port F (PF):  7 input/output port
+//! Port G (PG): 14 input/output port
+//! Port H (PH): 12 input/output port
+//!
+//! Abstracted over the R_PIO (CPUs-PORT port controller)
+//!
+//! Port L (PL): 13 input/output port
+//!
+//! PxY_Select variants mapped to alt functions:
+//!   * 000 (U0): input
+//!   * 001 (U1): output
+//!   * 010 (U2): AF0
+//!   * 011 (U3): AF1
+//!   * 100 (U4): AF2
+//!   * 101 (U5): AF3
+//!   * 110 (U6): AF4
+//!   * 111 (U7): disabled
+
+use crate::ccu::Ccu;
+use crate::hal::digital::v2::{toggleable, InputPin, OutputPin, StatefulOutputPin};
+use crate::pac::pio::{
+    Config0, Config1, Config2, Config3, Data, Driv0, Driv1, IntConfig0, IntConfig1, IntControl,
+    IntDebounce, IntStatus, Pull0, Pull1, PIO,
+};
+use crate::pac::r_pio::RPIO;
+use core::convert::{Infallible, TryFrom};
+use core::marker::PhantomData;
+
+pub use dynamic::{Dynamic, DynamicMode, DynamicPin, PinModeError};
+pub use erased::{PBx, PCx, PDx, PEx, PFx, PGx, PHx, PLx, Pin, PinSnapshot, Port};
+
+use erased::RawPin;
+pub use parallel::{BusMode, Error as ParallelBusError, ParallelBus};
+
+mod dynamic;
+mod erased;
+mod parallel;
+mod port;
+
+pub trait GpioExt {
+    type Parts;
+
+    fn split(self, ccu: &mut Ccu) -> Self::Parts;
+}
+
+pub struct AF0;
+pub struct AF1;
+pub struct AF2;
+pub struct AF3;
+pub struct AF4;
+
+/// Input mode
+pub struct Input<MODE> {
+    _mode: PhantomData<MODE>,
+}
+
+/// Floating input (type state)
+pub struct Floating;
+
+/// Pulled down input (type state)
+pub struct PullDown;
+
+/// Pulled up input (type state)
+pub struct PullUp;
+
+/// Output mode
+pub struct Output<MODE> {
+    _mode: PhantomData<MODE>,
+}
+
+/// Push pull output (type state)
+pub struct PushPull;
+
+/// Open drain output (type state)
+///
+/// Emulated, the pin is driven low as an output and released as an input
+pub struct OpenDrain;
+
+/// Alternate function mode
+pub struct Alternate<MODE> {
+    _mode: PhantomData<MODE>,
+}
+
+/// External interrupt mode, the pin is muxed to its EINT function
+///
+/// The data register doesn't reflect the level of a pin muxed to a
+/// function, so the level can't be read in this mode. `MODE` is the pull of
+/// the input the pin was switched from.
+pub struct Interrupt<MODE> {
+    _mode: PhantomData<MODE>,
+}
+
+/// Disabled mode
+pub struct Disabled;
+
+/// Pull configuration of output and alternate function pins
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Pull {
+    Disabled,
+    Up,
+    Down,
+}
+
+// See sun50i-a64.dtsi for drive strength values
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum DriveStrength {
+    L0_10mA,
+    L1_20mA,
+    L2_30mA,
+    L3_40mA,
+}
+
+/// Interrupt trigger
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Edge {
+    Rising,
+    Falling,
+    Both,
+    High,
+    Low,
+}
+
+/// Interrupt sample and debounce clock
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum DebounceClock {
+    LOsc32K,
+    HOsc24M,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ExtiError {
+    /// The debounce prescale is above 7
+    InvalidPrescale,
+}
+
+/// External interrupt (EINT) pin, ports B, G, H and L
+///
+/// Implemented by pins in `Interrupt` mode, see `into_interrupt_source`.
+pub trait ExtiPin {
+    fn trigger_on_edge(&mut self, edge: Edge);
+
+    fn enable_interrupt(&mut self);
+
+    fn disable_interrupt(&mut self);
+
+    fn clear_interrupt_pending_bit(&mut self);
+
+    fn check_interrupt(&self) -> bool;
+
+    /// Select the sample clock and its 2^prescale divider (0..=7)
+    ///
+    /// Shared by all the pins of the port.
+    fn set_debounce(&mut self, clock: DebounceClock, prescale: u8) -> Result<(), ExtiError>;
+}
+
+pub struct Gpio {
+    pub pb: PortB,
+    pub pc: PortC,
+    pub pd: PortD,
+    pub pe: PortE,
+    pub pf: PortF,
+    pub pg: PortG,
+    pub ph: PortH,
+}
+
+impl GpioExt for PIO {
+    type Parts = Gpio;
+
+    fn split(self, ccu: &mut Ccu) -> Self::Parts {
+        ccu.enable::<PIO>();
+        Gpio {
+            pb: PortB::_new(),
+            pc: PortC::_new(),
+            pd: PortD::_new(),
+            pe: PortE::_new(),
+            pf: PortF::_new(),
+            pg: PortG::_new(),
+            ph: PortH::_new(),
+        }
+    }
+}
+
+/// R_PIO ports
+pub struct RGpio {
+    pub pl: PortL,
+}
+
+impl GpioExt for RPIO {
+    type Parts = RGpio;
+
+    /// The R_PIO bus clock is gated in the R_PRCM, which the boot loader
+    /// leaves enabled
+    fn split(self, _ccu: &mut Ccu) -> Self::Parts {
+        RGpio { pl: PortL::_new() }
+    }
+}
+
+pub struct PortB {
+    pub pb0: PB0<Disabled>,
+    pub pb1: PB1<Disabled>,
+    pub pb2: PB2<Disabled>,
+    pub pb3: PB3<Disabled>,
+    pub pb4: PB4<Disabled>,
+    pub pb5: PB5<Disabled>,
+    pub pb6: PB6<Disabled>,
+    pub pb7: PB7<Disabled>,
+    pub pb8: PB8<Disabled>,
+    pub pb9: PB9<Disabled>,
+}
+
+impl PortB {
+    fn _new() -> Self {
+        PortB {
+            pb0: PB0 { _mode: PhantomData },
+            pb1: PB1 { _mode: PhantomData },
+            pb2: PB2 { _mode: PhantomData },
+            pb3: PB3 { _mode: PhantomData },
+            pb4: PB4 { _mode: PhantomData },
+            pb5: PB5 { _mode: PhantomData },
+            pb6: PB6 { _mode: PhantomData },
+            pb7: PB7 { _mode: PhantomData },
+            pb8: PB8 { _mode: PhantomData },
+            pb9: PB9 { _mode: PhantomData },
+        }
+    }
+}
+
+pub struct PortC {
+    pub pc0: PC0<Disabled>,
+    pub pc1: PC1<Disabled>,
+    pub pc2: PC2<Disabled>,
+    pub pc3: PC3<Disabled>,
+    pub pc4: PC4<Disabled>,
+    pub pc5: PC5<Disabled>,
+    pub pc6: PC6<Disabled>,
+    pub pc7: PC7<Disabled>,
+    pub pc8: PC8<Disabled>,
+    pub pc9: PC9<Disabled>,
+    pub pc10: PC10<Disabled>,
+    pub pc11: PC11<Disabled>,
+    pub pc12: PC12<Disabled>,
+    pub pc13: PC13<Disabled>,
+    pub pc14: PC14<Disabled>,
+    pub pc15: PC15<Disabled>,
+    pub pc16: PC16<Disabled>,
+}
+
+impl PortC {
+    fn _new() -> Self {
+        PortC {
+            pc0: PC0 { _mode: PhantomData },
+            pc1: PC1 { _mode: PhantomData },
+            pc2: PC2 { _mode: PhantomData },
+            pc3: PC3 { _mode: PhantomData },
+            pc4: PC4 { _mode: PhantomData },
+            pc5: PC5 { _mode: PhantomData },
+            pc6: PC6 { _mode: PhantomData },
+            pc7: PC7 { _mode: PhantomData },
+            pc8: PC8 { _mode: PhantomData },
+            pc9: PC9 { _mode: PhantomData },
+            pc10: PC10 { _mode: PhantomData },
+            pc11: PC11 { _mode: PhantomData },
+            pc12: PC12 { _mode: PhantomData },
+            pc13: PC13 { _mode: PhantomData },
+            pc14: PC14 { _mode: PhantomData },
+            pc15: PC15 { _mode: PhantomData },
+            pc16: PC16 { _mode: PhantomData },
+        }
+    }
+}
+
+pub struct PortD {
+    pub pd0: PD0<Disabled>,
+    pub pd1: PD1<Disabled>,
+    pub pd2: PD2<Disabled>,
+    pub pd3: PD3<Disabled>,
+    pub pd4: PD4<Disabled>,
+    pub pd5: PD5<Disabled>,
+    pub pd6: PD6<Disabled>,
+    pub pd7: PD7<Disabled>,
+    pub pd8: PD8<Disabled>,
+    pub pd9: PD9<Disabled>,
+    pub pd10: PD10<Disabled>,
+    pub pd11: PD11<Disabled>,
+    pub pd12: PD12<Disabled>,
+    pub pd13: PD13<Disabled>,
+    pub pd14: PD14<Disabled>,
+    pub pd15: PD15<Disabled>,
+    pub pd16: PD16<Disabled>,
+    pub pd17: PD17<Disabled>,
+    pub pd18: PD18<Disabled>,
+    pub pd19: PD19<Disabled>,
+    pub pd20: PD20<Disabled>,
+    pub pd21: PD21<Disabled>,
+    pub pd22: PD22<Disabled>,
+    pub pd23: PD23<Disabled>,
+    pub pd24: PD24<Disabled>,
+}
+
+impl PortD {
+    fn _new() -> Self {
+        PortD {
+            pd0: PD0 { _mode: PhantomData },
+            pd1: PD1 { _mode: PhantomData },
+            pd2: PD2 { _mode: PhantomData },
+            pd3: PD3 { _mode: PhantomData },
+            pd4: PD4 { _mode: PhantomData },
+            pd5: PD5 { _mode: PhantomData },
+            pd6: PD6 { _mode: PhantomData },
+            pd7: PD7 { _mode: PhantomData },
+            pd8: PD8 { _mode: PhantomData },
+            pd9: PD9 { _mode: PhantomData },
+            pd10: PD10 { _mode: PhantomData },
+            pd11: PD11 { _mode: PhantomData },
+            pd12: PD12 { _mode: PhantomData },
+            pd13: PD13 { _mode: PhantomData },
+            pd14: PD14 { _mode: PhantomData },
+            pd15: PD15 { _mode: PhantomData },
+            pd16: PD16 { _mode: PhantomData },
+            pd17: PD17 { _mode: PhantomData },
+            pd18: PD18 { _mode: PhantomData },
+            pd19: PD19 { _mode: PhantomData },
+            pd20: PD20 { _mode: PhantomData },
+            pd21: PD21 { _mode: PhantomData },
+            pd22: PD22 { _mode: PhantomData },
+            pd23: PD23 { _mode: PhantomData },
+            pd24: PD24 { _mode: PhantomData },
+        }
+    }
+}
+
+pub struct PortE {
+    pub pe0: PE0<Disabled>,
+    pub pe1: PE1<Disabled>,
+    pub pe2: PE2<Disabled>,
+    pub pe3: PE3<Disabled>,
+    pub pe4: PE4<Disabled>,
+    pub pe5: PE5<Disabled>,
+    pub pe6: PE6<Disabled>,
+    pub pe7: PE7<Disabled>,
+    pub pe8: PE8<Disabled>,
+    pub pe9: PE9<Disabled>,
+    pub pe10: PE10<Disabled>,
+    pub pe11: PE11<Disabled>,
+    pub pe12: PE12<Disabled>,
+    pub pe13: PE13<Disabled>,
+    pub pe14: PE14<Disabled>,
+    pub pe15: PE15<Disabled>,
+    pub pe16: PE16<Disabled>,
+    pub pe17: PE17<Disabled>,
+}
+
+impl PortE {
+    fn _new() -> Self {
+        PortE {
+            pe0: PE0 { _mode: PhantomData },
+            pe1: PE1 { _mode: PhantomData },
+            pe2: PE2 { _mode: PhantomData },
+            pe3: PE3 { _mode: PhantomData },
+            pe4: PE4 { _mode: PhantomData },
+            pe5: PE5 { _mode: PhantomData },
+            pe6: PE6 { _mode: PhantomData },
+            pe7: PE7 { _mode: PhantomData },
+            pe8: PE8 { _mode: PhantomData },
+            pe9: PE9 { _mode: PhantomData },
+            pe10: PE10 { _mode: PhantomData },
+            pe11: PE11 { _mode: PhantomData },
+            pe12: PE12 { _mode: PhantomData },
+            pe13: PE13 { _mode: PhantomData },
+            pe14: PE14 { _mode: PhantomData },
+            pe15: PE15 { _mode: PhantomData },
+            pe16: PE16 { _mode: PhantomData },
+            pe17: PE17 { _mode: PhantomData },
+        }
+    }
+}
+
+pub struct PortF {
+    pub pf0: PF0<Disabled>,
+    pub pf1: PF1<Disabled>,
+    pub pf2: PF2<Disabled>,
+    pub pf3: PF3<Disabled>,
+    pub pf4: PF4<Disabled>,
+    pub pf5: PF5<Disabled>,
+    pub pf6: PF6<Disabled>,
+}
+
+impl PortF {
+    fn _new() -> Self {
+        PortF {
+            pf0: PF0 { _mode: PhantomData },
+            pf1: PF1 { _mode: PhantomData },
+            pf2: PF2 { _mode: PhantomData },
+            pf3: PF3 { _mode: PhantomData },
+            pf4: PF4 { _mode: PhantomData },
+            pf5: PF5 { _mode: PhantomData },
+            pf6: PF6 { _mode: PhantomData },
+        }
+    }
+}
+
+pub struct PortG {
+    pub pg0: PG0<Disabled>,
+    pub pg1: PG1<Disabled>,
+    pub pg2: PG2<Disabled>,
+    pub pg3: PG3<Disabled>,
+    pub pg4: PG4<Disabled>,
+    pub pg5: PG5<Disabled>,
+    pub pg6: PG6<Disabled>,
+    pub pg7: PG7<Disabled>,
+    pub pg8: PG8<Disabled>,
+    pub pg9: PG9<Disabled>,
+    pub pg10: PG10<Disabled>,
+    pub pg11: PG11<Disabled>,
+    pub pg12: PG12<Disabled>,
+    pub pg13: PG13<Disabled>,
+}
+
+impl PortG {
+    fn _new() -> Self {
+        PortG {
+            pg0: PG0 { _mode: PhantomData },
+            pg1: PG1 { _mode: PhantomData },
+            pg2: PG2 { _mode: PhantomData },
+            pg3: PG3 { _mode: PhantomData },
+            pg4: PG4 { _mode: PhantomData },
+            pg5: PG5 { _mode: PhantomData },
+            pg6: PG6 { _mode: PhantomData },
+            pg7: PG7 { _mode: PhantomData },
+            pg8: PG8 { _mode: PhantomData },
+            pg9: PG9 { _mode: PhantomData },
+            pg10: PG10 { _mode: PhantomData },
+            pg11: PG11 { _mode: PhantomData },
+            pg12: PG12 { _mode: PhantomData },
+            pg13: PG13 { _mode: PhantomData },
+        }
+    }
+}
+
+pub struct PortH {
+    pub ph0: PH0<Disabled>,
+    pub ph1: PH1<Disabled>,
+    pub ph2: PH2<Disabled>,
+    pub ph3: PH3<Disabled>,
+    pub ph4: PH4<Disabled>,
+    pub ph5: PH5<Disabled>,
+    pub ph6: PH6<Disabled>,
+    pub ph7: PH7<Disabled>,
+    pub ph8: PH8<Disabled>,
+    pub ph9: PH9<Disabled>,
+    pub ph10: PH10<Disabled>,
+    pub ph11: PH11<Disabled>,
+}
+
+impl PortH {
+    fn _new() -> Self {
+        PortH {
+            ph0: PH0 { _mode: PhantomData },
+            ph1: PH1 { _mode: PhantomData },
+            ph2: PH2 { _mode: PhantomData },
+            ph3: PH3 { _mode: PhantomData },
+            ph4: PH4 { _mode: PhantomData },
+            ph5: PH5 { _mode: PhantomData },
+            ph6: PH6 { _mode: PhantomData },
+            ph7: PH7 { _mode: PhantomData },
+            ph8: PH8 { _mode: PhantomData },
+            ph9: PH9 { _mode: PhantomData },
+            ph10: PH10 { _mode: PhantomData },
+            ph11: PH11 { _mode: PhantomData },
+        }
+    }
+}
+
+pub struct PortL {
+    pub pl0: PL0<Disabled>,
+    pub pl1: PL1<Disabled>,
+    pub pl2: PL2<Disabled>,
+    pub pl3: PL3<Disabled>,
+    pub pl4: PL4<Disabled>,
+    pub pl5: PL5<Disabled>,
+    pub pl6: PL6<Disabled>,
+    pub pl7: PL7<Disabled>,
+    pub pl8: PL8<Disabled>,
+    pub pl9: PL9<Disabled>,
+    pub pl10: PL10<Disabled>,
+    pub pl11: PL11<Disabled>,
+    pub pl12: PL12<Disabled>,
+}
+
+impl PortL {
+    fn _new() -> Self {
+        PortL {
+            pl0: PL0 { _mode: PhantomData },
+            pl1: PL1 { _mode: PhantomData },
+            pl2: PL2 { _mode: PhantomData },
+            pl3: PL3 { _mode: PhantomData },
+            pl4: PL4 { _mode: PhantomData },
+            pl5: PL5 { _mode: PhantomData },
+            pl6: PL6 { _mode: PhantomData },
+            pl7: PL7 { _mode: PhantomData },
+            pl8: PL8 { _mode: PhantomData },
+            pl9: PL9 { _mode: PhantomData },
+            pl10: PL10 { _mode: PhantomData },
+            pl11: PL11 { _mode: PhantomData },
+            pl12: PL12 { _mode: PhantomData },
+        }
+    }
+}
+
+macro_rules! gpio_pins {
+    (
+        // port controller, struct field name (r), register type (t)
+        $PIO:ident,
+        $PXx:ident, $port:expr,
+        $CFGr:ident, $CFGt:ident,
+        $DATAr:ident,
+        $DRIVr:ident, $DRIVt:ident,
+        $PULLr:ident, $PULLt:ident,
+        [$($PXi:ident: ($pxi:ident, $px_field:ident, $i:expr, $MODE:ty),)+]
+        ) => {
+        $(
+        pub struct $PXi<MODE> {
+            _mode: PhantomData<MODE>,
+        }
+
+        impl<MODE> $PXi<MODE> {
+            /// Erase the pin index
+            pub fn downgrade(self) -> $PXx<MODE> {
+                $PXx::new($i)
+            }
+
+            /// Erase the pin type, starting out as a floating input
+            pub fn into_dynamic(self) -> DynamicPin {
+                self.into_floating_input();
+                DynamicPin::new($port, $i, Dynamic::InputFloating)
+            }
+
+            /// Save the pin's config, pull and drive strength
+            pub fn snapshot(&self) -> PinSnapshot<MODE> {
+                PinSnapshot::take(RawPin::new($port, $i))
+            }
+
+            pub fn restore(&mut self, snapshot: &PinSnapshot<MODE>) {
+                snapshot.apply(RawPin::new($port, $i));
+            }
+
+            /// Run `f` with the pin as a floating input, then put it back
+            pub fn with_input<R, F>(&mut self, f: F) -> R
+            where
+                F: FnOnce(&mut $PXi<Input<Floating>>) -> R,
+            {
+                let saved = PinSnapshot::<MODE>::take(RawPin::new($port, $i));
+                let mut pin = $PXi::<MODE> { _mode: PhantomData }.into_floating_input();
+                let r = f(&mut pin);
+                saved.apply(RawPin::new($port, $i));
+                r
+            }
+
+            /// Run `f` with the pin as a push pull output, then put it back
+            ///
+            /// The output starts at the level last latched in the data register.
+            pub fn with_output<R, F>(&mut self, f: F) -> R
+            where
+                F: FnOnce(&mut $PXi<Output<PushPull>>) -> R,
+            {
+                let saved = PinSnapshot::<MODE>::take(RawPin::new($port, $i));
+                let mut pin = $PXi::<MODE> { _mode: PhantomData }.into_push_pull_output();
+                let r = f(&mut pin);
+                saved.apply(RawPin::new($port, $i));
+                r
+            }
+
+            pub fn set_drive_strength(&mut self, level: DriveStrength) {
+                match level {
+                    DriveStrength::L0_10mA =>
+                        unsafe { (*$PIO::mut_ptr()).$DRIVr.modify($DRIVt::$px_field::Level0) },
+                    DriveStrength::L1_20mA =>
+                        unsafe { (*$PIO::mut_ptr()).$DRIVr.modify($DRIVt::$px_field::Level1) },
+                    DriveStrength::L2_30mA =>
+                        unsafe { (*$PIO::mut_ptr()).$DRIVr.modify($DRIVt::$px_field::Level2) },
+                    DriveStrength::L3_40mA =>
+                        unsafe { (*$PIO::mut_ptr()).$DRIVr.modify($DRIVt::$px_field::Level3) },
+                }
+            }
+
+            fn _set_pull(&mut self, pull: Pull) {
+                match pull {
+                    Pull::Disabled =>
+                        unsafe { (*$PIO::mut_ptr()).$PULLr.modify($PULLt::$px_field::Disabled) },
+                    Pull::Up =>
+                        unsafe { (*$PIO::mut_ptr()).$PULLr.modify($PULLt::$px_field::PullUp) },
+                    Pull::Down =>
+                        unsafe { (*$PIO::mut_ptr()).$PULLr.modify($PULLt::$px_field::PullDown) },
+                }
+            }
+        }
+
+        impl<MODE> $PXi<Output<MODE>> {
+            pub fn set_pull(&mut self, pull: Pull) {
+                self._set_pull(pull);
+            }
+        }
+
+        impl<MODE> $PXi<Alternate<MODE>> {
+            pub fn set_pull(&mut self, pull: Pull) {
+                self._set_pull(pull);
+            }
+        }
+
+        /// Back from `into_dynamic`, the pin must be in `MODE`
+        impl<MODE: DynamicMode> TryFrom<DynamicPin> for $PXi<MODE> {
+            type Error = PinModeError;
+
+            fn try_from(pin: DynamicPin) -> Result<Self, Self::Error> {
+                pin.check::<MODE>($port, $i)?;
+                Ok($PXi { _mode: PhantomData })
+            }
+        }
+
+        impl OutputPin for $PXi<Output<PushPull>> {
+            type Error = Infallible;
+
+            fn set_high(&mut self) -> Result<(), Self::Error> {
+                Ok(unsafe { (*$PIO::mut_ptr()).$DATAr.modify(Data::$px_field::Set) })
+            }
+
+            fn set_low(&mut self) -> Result<(), Self::Error> {
+                Ok(unsafe { (*$PIO::mut_ptr()).$DATAr.modify(Data::$px_field::Clear) })
+            }
+        }
+
+        impl<MODE> InputPin for $PXi<Input<MODE>> {
+            type Error = Infallible;
+
+            fn is_high(&self) -> Result<bool, Self::Error> {
+                self.is_low().map(|b| !b)
+            }
+
+            fn is_low(&self) -> Result<bool, Self::Error> {
+                Ok(unsafe { (*$PIO::ptr()).$DATAr.is_set(Data::$px_field::Read) } != true)
+            }
+        }
+
+        impl StatefulOutputPin for $PXi<Output<PushPull>> {
+            fn is_set_high(&self) -> Result<bool, Self::Error> {
+                self.is_set_low().map(|b| !b)
+            }
+
+            fn is_set_low(&self) -> Result<bool, Self::Error> {
+                Ok(unsafe { (*$PIO::ptr()).$DATAr.is_set(Data::$px_field::Read) } != true)
+            }
+        }
+
+        impl toggleable::Default for $PXi<Output<PushPull>> {}
+
+        /// Reads back the level the pin is driving
+        impl InputPin for $PXi<Output<PushPull>> {
+            type Error = Infallible;
+
+            fn is_high(&self) -> Result<bool, Self::Error> {
+                self.is_low().map(|b| !b)
+            }
+
+            fn is_low(&self) -> Result<bool, Self::Error> {
+                Ok(unsafe { (*$PIO::ptr()).$DATAr.is_set(Data::$px_field::Read) } != true)
+            }
+        }
+
+        impl OutputPin for $PXi<Output<OpenDrain>> {
+            type Error = Infallible;
+
+            /// Release the line
+            fn set_high(&mut self) -> Result<(), Self::Error> {
+                Ok(unsafe { (*$PIO::mut_ptr()).$CFGr.modify($CFGt::$px_field::Input) })
+            }
+
+            /// Drive the line low
+            fn set_low(&mut self) -> Result<(), Self::Error> {
+                // Other pins' read-modify-write cycles may have latched a 1
+                unsafe { (*$PIO::mut_ptr()).$DATAr.modify(Data::$px_field::Clear) };
+                Ok(unsafe { (*$PIO::mut_ptr()).$CFGr.modify($CFGt::$px_field::Output) })
+            }
+        }
+
+        impl StatefulOutputPin for $PXi<Output<OpenDrain>> {
+            fn is_set_high(&self) -> Result<bool, Self::Error> {
+                self.is_set_low().map(|b| !b)
+            }
+
+            fn is_set_low(&self) -> Result<bool, Self::Error> {
+                let cfg = unsafe { (*$PIO::ptr()).$CFGr.get_field($CFGt::$px_field::Read) };
+                Ok(cfg == Some($CFGt::$px_field::Output))
+            }
+        }
+
+        impl toggleable::Default for $PXi<Output<OpenDrain>> {}
+
+        /// Reads the line level, which another device may be holding low
+        impl InputPin for $PXi<Output<OpenDrain>> {
+            type Error = Infallible;
+
+            fn is_high(&self) -> Result<bool, Self::Error> {
+                self.is_low().map(|b| !b)
+            }
+
+            fn is_low(&self) -> Result<bool, Self::Error> {
+                Ok(unsafe { (*$PIO::ptr()).$DATAr.is_set(Data::$px_field::Read) } != true)
+            }
+        }
+
+        impl<MODE> $PXi<MODE> {
+            #[inline]
+            pub fn into_floating_input(self) -> $PXi<Input<Floating>> {
+                unsafe { (*$PIO::mut_ptr()).$CFGr.modify($CFGt::$px_field::Input) };
+                unsafe { (*$PIO::mut_ptr()).$PULLr.modify($PULLt::$px_field::Disabled) };
+                $PXi { _mode: PhantomData }
+            }
+
+            #[inline]
+            pub fn into_pull_down_input(self) -> $PXi<Input<PullDown>> {
+                unsafe { (*$PIO::mut_ptr()).$CFGr.modify($CFGt::$px_field::Input) };
+                unsafe { (*$PIO::mut_ptr()).$PULLr.modify($PULLt::$px_field::PullDown) };
+                $PXi { _mode: PhantomData }
+            }
+
+            #[inline]
+            pub fn into_pull_up_input(self) -> $PXi<Input<PullUp>> {
+                unsafe { (*$PIO::mut_ptr()).$CFGr.modify($CFGt::$px_field::Input) };
+                unsafe { (*$PIO::mut_ptr()).$PULLr.modify($PULLt::$px_field::PullUp) };
+                $PXi { _mode: PhantomData }
+            }
+
+            #[inline]
+            pub fn into_push_pull_output(self) -> $PXi<Output<PushPull>> {
+                unsafe { (*$PIO::mut_ptr()).$CFGr.modify($CFGt::$px_field::Output) };
+                unsafe { (*$PIO::mut_ptr()).$PULLr.modify($PULLt::$px_field::Disabled) };
+                $PXi { _mode: PhantomData }
+            }
+
+            /// Released, with the internal pull-up enabled
+            #[inline]
+            pub fn into_open_drain_output(self) -> $PXi<Output<OpenDrain>> {
+                unsafe { (*$PIO::mut_ptr()).$DATAr.modify(Data::$px_field::Clear) };
+                unsafe { (*$PIO::mut_ptr()).$CFGr.modify($CFGt::$px_field::Input) };
+                unsafe { (*$PIO::mut_ptr()).$PULLr.modify($PULLt::$px_field::PullUp) };
+                $PXi { _mode: PhantomData }
+            }
+
+            #[inline]
+            pub fn into_alternate_af0(self) -> $PXi<Alternate<AF0>> {
+                unsafe { (*$PIO::mut_ptr()).$CFGr.modify($CFGt::$px_field::Af0) };
+                unsafe { (*$PIO::mut_ptr()).$PULLr.modify($PULLt::$px_field::Disabled) };
+                $PXi { _mode: PhantomData }
+            }
+
+            #[inline]
+            pub fn into_alternate_af1(self) -> $PXi<Alternate<AF1>> {
+                unsafe { (*$PIO::mut_ptr()).$CFGr.modify($CFGt::$px_field::Af1) };
+                unsafe { (*$PIO::mut_ptr()).$PULLr.modify($PULLt::$px_field::Disabled) };
+                $PXi { _mode: PhantomData }
+            }
+
+            #[inline]
+            pub fn into_alternate_af2(self) -> $PXi<Alternate<AF2>> {
+                unsafe { (*$PIO::mut_ptr()).$CFGr.modify($CFGt::$px_field::Af2) };
+                unsafe { (*$PIO::mut_ptr()).$PULLr.modify($PULLt::$px_field::Disabled) };
+                $PXi { _mode: PhantomData }
+            }
+
+            #[inline]
+            pub fn into_alternate_af3(self) -> $PXi<Alternate<AF3>> {
+                unsafe { (*$PIO::mut_ptr()).$CFGr.modify($CFGt::$px_field::Af3) };
+                unsafe { (*$PIO::mut_ptr()).$PULLr.modify($PULLt::$px_field::Disabled) };
+                $PXi { _mode: PhantomData }
+            }
+
+            #[inline]
+            pub fn into_alternate_af4(self) -> $PXi<Alternate<AF4>> {
+                unsafe { (*$PIO::mut_ptr()).$CFGr.modify($CFGt::$px_field::Af4) };
+                unsafe { (*$PIO::mut_ptr()).$PULLr.modify($PULLt::$px_field::Disabled) };
+                $PXi { _mode: PhantomData }
+            }
+        }
+        )+
+    }
+}
+
+gpio_pins!(
+    PIO,
+    PBx,
+    Port::B,
+    pb_cfg0,
+    Config0,
+    pb_data,
+    pb_driv0,
+    Driv0,
+    pb_pull0,
+    Pull0,
+    [
+        PB0: (pb0, Pin0, 0, Disabled),
+        PB1: (pb1, Pin1, 1, Disabled),
+        PB2: (pb2, Pin2, 2, Disabled),
+        PB3: (pb3, Pin3, 3, Disabled),
+        PB4: (pb4, Pin4, 4, Disabled),
+        PB5: (pb5, Pin5, 5, Disabled),
+        PB6: (pb6, Pin6, 6, Disabled),
+        PB7: (pb7, Pin7, 7, Disabled),
+    ]
+);
+
+gpio_pins!(
+    PIO,
+    PBx,
+    Port::B,
+    pb_cfg1,
+    Config1,
+    pb_data,
+    pb_driv0,
+    Driv0,
+    pb_pull0,
+    Pull0,
+    [
+        PB8: (pb8, Pin8, 8, Disabled),
+        PB9: (pb9, Pin9, 9, Disabled),
+    ]
+);
+
+gpio_pins!(
+    PIO,
+    PCx,
+    Port::C,
+    pc_cfg0,
+    Config0,
+    pc_data,
+    pc_driv0,
+    Driv0,
+    pc_pull0,
+    Pull0,
+    [
+        PC0: (pc0, Pin0, 0, Disabled),
+        PC1: (pc1, Pin1, 1, Disabled),
+        PC2: (pc2, Pin2, 2, Disabled),
+        PC3: (pc3, Pin3, 3, Disabled),
+        PC4: (pc4, Pin4, 4, Disabled),
+        PC5: (pc5, Pin5, 5, Disabled),
+        PC6: (pc6, Pin6, 6, Disabled),
+        PC7: (pc7, Pin7, 7, Disabled),
+    ]
+);
+
+gpio_pins!(
+    PIO,
+    PCx,
+    Port::C,
+    pc_cfg1,
+    Config1,
+    pc_data,
+    pc_driv0,
+    Driv0,
+    pc_pull0,
+    Pull0,
+    [
+        PC8: (pc8, Pin8, 8, Disabled),
+        PC9: (pc9, Pin9, 9, Disabled),
+        PC10: (pc10, Pin10, 10, Disabled),
+        PC11: (pc11, Pin11, 11, Disabled),
+        PC12: (pc12, Pin12, 12, Disabled),
+        PC13: (pc13, Pin13, 13, Disabled),
+        PC14: (pc14, Pin14, 14, Disabled),
+        PC15: (pc15, Pin15, 15, Disabled),
+    ]
+);
+
+gpio_pins!(
+    PIO,
+    PCx,
+    Port::C,
+    pc_cfg2,
+    Config2,
+    pc_data,
+    pc_driv1,
+    Driv1,
+    pc_pull1,
+    Pull1,
+    [
+        PC16: (pc16, Pin16, 16, Disabled),
+    ]
+);
+
+gpio_pins!(
+    PIO,
+    PDx,
+    Port::D,
+    pd_cfg0,
+    Config0,
+    pd_data,
+    pd_driv0,
+    Driv0,
+    pd_pull0,
+    Pull0,
+    [
+        PD0: (pd0, Pin0, 0, Disabled),
+        PD1: (pd1, Pin1, 1, Disabled),
+        PD2: (pd2, Pin2, 2, Disabled),
+        PD3: (pd3, Pin3, 3, Disabled),
+        PD4: (pd4, Pin4, 4, Disabled),
+        PD5: (pd5, Pin5, 5, Disabled),
+        PD6: (pd6, Pin6, 6, Disabled),
+        PD7: (pd7, Pin7, 7, Disabled),
+    ]
+);
+
+gpio_pins!(
+    PIO,
+    PDx,
+    Port::D,
+    pd_cfg1,
+    Config1,
+    pd_data,
+    pd_driv0,
+    Driv0,
+    pd_pull0,
+    Pull0,
+    [
+        PD8: (pd8, Pin8, 8, Disabled),
+        PD9: (pd9, Pin9, 9, Disabled),
+        PD10: (pd10, Pin10, 10, Disabled),
+        PD11: (pd11, Pin11, 11, Disabled),
+        PD12: (pd12, Pin12, 12, Disabled),
+        PD13: (pd13, Pin13, 13, Disabled),
+        PD14: (pd14, Pin14, 14, Disabled),
+        PD15: (pd15, Pin15, 15, Disabled),
+    ]
+);
+
+gpio_pins!(
+    PIO,
+    PDx,
+    Port::D,
+    pd_cfg2,
+    Config2,
+    pd_data,
+    pd_driv1,
+    Driv1,
+    pd_pull1,
+    Pull1,
+    [
+        PD16: (pd16, Pin16, 16, Disabled),
+        PD17: (pd17, Pin17, 17, Disabled),
+        PD18: (pd18, Pin18, 18, Disabled),
+        PD19: (pd19, Pin19, 19, Disabled),
+        PD20: (pd20, Pin20, 20, Disabled),
+        PD21: (pd21, Pin21, 21, Disabled),
+        PD22: (pd22, Pin22, 22, Disabled),
+        PD23: (pd23, Pin23, 23, Disabled),
+    ]
+);
+
+gpio_pins!(
+    PIO,
+    PDx,
+    Port::D,
+    pd_cfg3,
+    Config3,
+    pd_data,
+    pd_driv1,
+    Driv1,
+    pd_pull1,
+    Pull1,
+    [
+        PD24: (pd24, Pin24, 24, Disabled),
+    ]
+);
+
+gpio_pins!(
+    PIO,
+    PEx,
+    Port::E,
+    pe_cfg0,
+    Config0,
+    pe_data,
+    pe_driv0,
+    Driv0,
+    pe_pull0,
+    Pull0,
+    [
+        PE0: (pe0, Pin0, 0, Disabled),
+        PE1: (pe1, Pin1, 1, Disabled),
+        PE2: (pe2, Pin2, 2, Disabled),
+        PE3: (pe3, Pin3, 3, Disabled),
+        PE4: (pe4, Pin4, 4, Disabled),
+        PE5: (pe5, Pin5, 5, Disabled),
+        PE6: (pe6, Pin6, 6, Disabled),
+        PE7: (pe7, Pin7, 7, Disabled),
+    ]
+);
+
+gpio_pins!(
+    PIO,
+    PEx,
+    Port::E,
+    pe_cfg1,
+    Config1,
+    pe_data,
+    pe_driv0,
+    Driv0,
+    pe_pull0,
+    Pull0,
+    [
+        PE8: (pe8, Pin8, 8, Disabled),
+        PE9: (pe9, Pin9, 9, Disabled),
+        PE10: (pe10, Pin10, 10, Disabled),
+        PE11: (pe11, Pin11, 11, Disabled),
+        PE12: (pe12, Pin12, 12, Disabled),
+        PE13: (pe13, Pin13, 13, Disabled),
+        PE14: (pe14, Pin14, 14, Disabled),
+        PE15: (pe15, Pin15, 15, Disabled),
+    ]
+);
+
+gpio_pins!(
+    PIO,
+    PEx,
+    Port::E,
+    pe_cfg2,
+    Config2,
+    pe_data,
+    pe_driv1,
+    Driv1,
+    pe_pull1,
+    Pull1,
+    [
+        PE16: (pe16, Pin16, 16, Disabled),
+        PE17: (pe17, Pin17, 17, Disabled),
+    ]
+);
+
+gpio_pins!(
+    PIO,
+    PFx,
+    Port::F,
+    pf_cfg0,
+    Config0,
+    pf_data,
+    pf_driv0,
+    Driv0,
+    pf_pull0,
+    Pull0,
+    [
+        PF0: (pf0, Pin0, 0, Disabled),
+        PF1: (pf1, Pin1, 1, Disabled),
+        PF2: (pf2, Pin2, 2, Disabled),
+        PF3: (pf3, Pin3, 3, Disabled),
+        PF4: (pf4, Pin4, 4, Disabled),
+        PF5: (pf5, Pin5, 5, Disabled),
+        PF6: (pf6, Pin6, 6, Disabled),
+    ]
+);
+
+gpio_pins!(
+    PIO,
+    PGx,
+    Port::G,
+    pg_cfg0,
+    Config0,
+    pg_data,
+    pg_driv0,
+    Driv0,
+    pg_pull0,
+    Pull0,
+    [
+        PG0: (pg0, Pin0, 0, Disabled),
+        PG1: (pg1, Pin1, 1, Disabled),
+        PG2: (pg2, Pin2, 2, Disabled),
+        PG3: (pg3, Pin3, 3, Disabled),
+        PG4: (pg4, Pin4, 4, Disabled),
+        PG5: (pg5, Pin5, 5, Disabled),
+        PG6: (pg6, Pin6, 6, Disabled),
+        PG7: (pg7, Pin7, 7, Disabled),
+    ]
+);
+
+gpio_pins!(
+    PIO,
+    PGx,
+    Port::G,
+    pg_cfg1,
+    Config1,
+    pg_data,
+    pg_driv0,
+    Driv0,
+    pg_pull0,
+    Pull0,
+    [
+        PG8: (pg8, Pin8, 8, Disabled),
+        PG9: (pg9, Pin9, 9, Disabled),
+        PG10: (pg10, Pin10, 10, Disabled),
+        PG11: (pg11, Pin11, 11, Disabled),
+        PG12: (pg12, Pin12, 12, Disabled),
+        PG13: (pg13, Pin13, 13, Disabled),
+    ]
+);
+
+gpio_pins!(
+    PIO,
+    PHx,
+    Port::H,
+    ph_cfg0,
+    Config0,
+    ph_data,
+    ph_driv0,
+    Driv0,
+    ph_pull0,
+    Pull0,
+    [
+        PH0: (ph0, Pin0, 0, Disabled),
+        PH1: (ph1, Pin1, 1, Disabled),
+        PH2: (ph2, Pin2, 2, Disabled),
+        PH3: (ph3, Pin3, 3, Disabled),
+        PH4: (ph4, Pin4, 4, Disabled),
+        PH5: (ph5, Pin5, 5, Disabled),
+        PH6: (ph6, Pin6, 6, Disabled),
+        PH7: (ph7, Pin7, 7, Disabled),
+    ]
+);
+
+gpio_pins!(
+    PIO,
+    PHx,
+    Port::H,
+    ph_cfg1,
+    Config1,
+    ph_data,
+    ph_driv0,
+    Driv0,
+    ph_pull0,
+    Pull0,
+    [
+        PH8: (ph8, Pin8, 8, Disabled),
+        PH9: (ph9, Pin9, 9, Disabled),
+        PH10: (ph10, Pin10, 10, Disabled),
+        PH11: (ph11, Pin11, 11, Disabled),
+    ]
+);
+
+gpio_pins!(
+    RPIO,
+    PLx,
+    Port::L,
+    pl_cfg0,
+    Config0,
+    pl_data,
+    pl_driv0,
+    Driv0,
+    pl_pull0,
+    Pull0,
+    [
+        PL0: (pl0, Pin0, 0, Disabled),
+        PL1: (pl1, Pin1, 1, Disabled),
+        PL2: (pl2, Pin2, 2, Disabled),
+        PL3: (pl3, Pin3, 3, Disabled),
+        PL4: (pl4, Pin4, 4, Disabled),
+        PL5: (pl5, Pin5, 5, Disabled),
+        PL6: (pl6, Pin6, 6, Disabled),
+        PL7: (pl7, Pin7, 7, Disabled),
+    ]
+);
+
+gpio_pins!(
+    RPIO,
+    PLx,
+    Port::L,
+    pl_cfg1,
+    Config1,
+    pl_data,
+    pl_driv0,
+    Driv0,
+    pl_pull0,
+    Pull0,
+    [
+        PL8: (pl8, Pin8, 8, Disabled),
+        PL9: (pl9, Pin9, 9, Disabled),
+        PL10: (pl10, Pin10, 10, Disabled),
+        PL11: (pl11, Pin11, 11, Disabled),
+        PL12: (pl12, Pin12, 12, Disabled),
+    ]
+);
+
+macro_rules! exti_pins {
+    (
+        // port controller, struct field name (r), register type (t)
+        $PIO:ident,
+        $CFGr:ident, $CFGt:ident,
+        $INTCFGr:ident, $INTCFGt:ident,
+        $INTCTRLr:ident,
+        $INTSTAr:ident,
+        $INTDEBr:ident,
+        [$($PXi:ident: ($px_field:ident, $i:expr),)+]
+        ) => {
+        $(
+        impl<MODE> $PXi<Input<MODE>> {
+            /// Switch the pin to its EINT function, keeping the pull
+            #[inline]
+            pub fn into_interrupt_source(self) -> $PXi<Interrupt<MODE>> {
+                unsafe { (*$PIO::mut_ptr()).$CFGr.modify($CFGt::$px_field::Af4) };
+                $PXi { _mode: PhantomData }
+            }
+        }
+
+        impl<MODE> ExtiPin for $PXi<Interrupt<MODE>> {
+            fn trigger_on_edge(&mut self, edge: Edge) {
+                let cfg = match edge {
+                    Edge::Rising => $INTCFGt::$px_field::PosEdge,
+                    Edge::Falling => $INTCFGt::$px_field::NegEdge,
+                    Edge::Both => $INTCFGt::$px_field::DoubleEdge,
+                    Edge::High => $INTCFGt::$px_field::HighLevel,
+                    Edge::Low => $INTCFGt::$px_field::LowLevel,
+                };
+                unsafe { (*$PIO::mut_ptr()).$INTCFGr.modify(cfg) };
+            }
+
+            fn enable_interrupt(&mut self) {
+                unsafe { (*$PIO::mut_ptr()).$INTCTRLr.modify(IntControl::$px_field::Set) };
+            }
+
+            fn disable_interrupt(&mut self) {
+                unsafe { (*$PIO::mut_ptr()).$INTCTRLr.modify(IntControl::$px_field::Clear) };
+            }
+
+            fn clear_interrupt_pending_bit(&mut self) {
+                // Write 1 to clear, a modify would clear the others too
+                unsafe { (*$PIO::mut_ptr()).$INTSTAr.write(1 << $i) };
+            }
+
+            fn check_interrupt(&self) -> bool {
+                unsafe { (*$PIO::ptr()).$INTSTAr.is_set(IntStatus::$px_field::Read) }
+            }
+
+            fn set_debounce(&mut self, clock: DebounceClock, prescale: u8) -> Result<(), ExtiError> {
+                let clock = match clock {
+                    DebounceClock::LOsc32K => IntDebounce::ClockSelect::LOsc32K,
+                    DebounceClock::HOsc24M => IntDebounce::ClockSelect::HOsc24M,
+                };
+                let prescale = IntDebounce::PreScale::Field::new(u32::from(prescale))
+                    .ok_or(ExtiError::InvalidPrescale)?;
+                unsafe { (*$PIO::mut_ptr()).$INTDEBr.modify(clock + prescale) };
+                Ok(())
+            }
+        }
+        )+
+    }
+}
+
+exti_pins!(
+    PIO,
+    pb_cfg0,
+    Config0,
+    pb_int_cfg0,
+    IntConfig0,
+    pb_int_ctrl,
+    pb_int_status,
+    pb_int_deb,
+    [
+        PB0: (Pin0, 0),
+        PB1: (Pin1, 1),
+        PB2: (Pin2, 2),
+        PB3: (Pin3, 3),
+        PB4: (Pin4, 4),
+        PB5: (Pin5, 5),
+        PB6: (Pin6, 6),
+        PB7: (Pin7, 7),
+    ]
+);
+
+exti_pins!(
+    PIO,
+    pb_cfg1,
+    Config1,
+    pb_int_cfg1,
+    IntConfig1,
+    pb_int_ctrl,
+    pb_int_status,
+    pb_int_deb,
+    [
+        PB8: (Pin8, 8),
+        PB9: (Pin9, 9),
+    ]
+);
+
+exti_pins!(
+    PIO,
+    pg_cfg0,
+    Config0,
+    pg_int_cfg0,
+    IntConfig0,
+    pg_int_ctrl,
+    pg_int_status,
+    pg_int_deb,
+    [
+        PG0: (Pin0, 0),
+        PG1: (Pin1, 1),
+        PG2: (Pin2, 2),
+        PG3: (Pin3, 3),
+        PG4: (Pin4, 4),
+        PG5: (Pin5, 5),
+        PG6: (Pin6, 6),
+        PG7: (Pin7, 7),
+    ]
+);
+
+exti_pins!(
+    PIO,
+    pg_cfg1,
+    Config1,
+    pg_int_cfg1,
+    IntConfig1,
+    pg_int_ctrl,
+    pg_int_status,
+    pg_int_deb,
+    [
+        PG8: (Pin8, 8),
+        PG9: (Pin9, 9),
+        PG10: (Pin10, 10),
+        PG11: (Pin11, 11),
+        PG12: (Pin12, 12),
+        PG13: (Pin13, 13),
+    ]
+);
+
+exti_pins!(
+    PIO,
+    ph_cfg0,
+    Config0,
+    ph_int_cfg0,
+    IntConfig0,
+    ph_int_ctrl,
+    ph_int_status,
+    ph_int_deb,
+    [
+        PH0: (Pin0, 0),
+        PH1: (Pin1, 1),
+        PH2: (Pin2, 2),
+        PH3: (Pin3, 3),
+        PH4: (Pin4, 4),
+        PH5: (Pin5, 5),
+        PH6: (Pin6, 6),
+        PH7: (Pin7, 7),
+    ]
+);
+
+exti_pins!(
+    PIO,
+    ph_cfg1,
+    Config1,
+    ph_int_cfg1,
+    IntConfig1,
+    ph_int_ctrl,
+    ph_int_status,
+    ph_int_deb,
+    [
+        PH8: (Pin8, 8),
+        PH9: (Pin9, 9),
+        PH10: (Pin10, 10),
+        PH11: (Pin11, 11),
+    ]
+);
+
+exti_pins!(
+    RPIO,
+    pl_cfg0,
+    Config0,
+    pl_int_cfg0,
+    IntConfig0,
+    pl_int_ctrl,
+    pl_int_status,
+    pl_int_deb,
+    [
+        PL0: (Pin0, 0),
+        PL1: (Pin1, 1),
+        PL2: (Pin2, 2),
+        PL3: (Pin3, 3),
+        PL4: (Pin4, 4),
+        PL5: (Pin5, 5),
+        PL6: (Pin6, 6),
+        PL7: (Pin7, 7),
+    ]
+);
+
+exti_pins!(
+    RPIO,
+    pl_cfg1,
+    Config1,
+    pl_int_cfg1,
+    IntConfig1,
+    pl_int_ctrl,
+    pl_int_status,
+    pl_int_deb,
+    [
+        PL8: (Pin8, 8),
+        PL9: (Pin9, 9),
+        PL10: (Pin10, 10),
+        PL11: (Pin11, 11),
+        PL12: (Pin12, 12),
+    ]
+);
@@ -0,0 +1,178 @@
+//! Parallel output bus over pins of one port
+//!
+//! ```ignore
+//! let mut bus = ParallelBus::new([
+//!     gpio.pd.pd2.into_push_pull_output().downgrade().downgrade(),
+//!     gpio.pd.pd3.into_push_pull_output().downgrade().downgrade(),
+//!     gpio.pd.pd7.into_push_pull_output().downgrade().downgrade(),
+//! ])
+//! .unwrap();
+//! bus.write(0b101);
+//! let word = bus.read();
+//! ```
+//!
+//! Bit `n` of the word drives `pins[n]`. Push pull pins all change with one
+//! store to the port's data register. The open drain emulation switches
+//! each pin's config instead, those pins change one after the other with
+//! interrupts masked.
+
+use super::erased::{interrupt_free, Port};
+use super::port::PortData;
+use super::{OpenDrain, Output, Pin, PushPull};
+use as_slice::AsSlice;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Error {
+    NoPins,
+    /// The pins don't all belong to the same port
+    MixedPorts,
+}
+
+/// Output modes a `ParallelBus` can drive
+pub trait BusMode {
+    /// Driven through the pin configs rather than the data register
+    const OPEN_DRAIN: bool;
+}
+
+impl BusMode for PushPull {
+    const OPEN_DRAIN: bool = false;
+}
+
+impl BusMode for OpenDrain {
+    const OPEN_DRAIN: bool = true;
+}
+
+pub struct ParallelBus<PINS> {
+    pins: PINS,
+    data: PortData,
+    /// Index of the first pin when the pins are consecutive and ascending
+    shift: Option<u8>,
+}
+
+impl<PINS, MODE> ParallelBus<PINS>
+where
+    PINS: AsSlice<Element = Pin<Output<MODE>>>,
+    MODE: BusMode,
+{
+    pub fn new(pins: PINS) -> Result<Self, Error> {
+        let port = match pins.as_slice().first() {
+            Some(pin) => pin.port(),
+            None => return Err(Error::NoPins),
+        };
+        if pins.as_slice().iter().any(|pin| pin.port() != port) {
+            return Err(Error::MixedPorts);
+        }
+        let (mask, shift) = layout(pins.as_slice().iter().map(Pin::pin_id));
+        Ok(ParallelBus {
+            pins,
+            data: PortData::new(port, mask),
+            shift,
+        })
+    }
+
+    pub fn port(&self) -> Port {
+        self.pins.as_slice()[0].port()
+    }
+
+    /// Number of pins
+    pub fn width(&self) -> usize {
+        self.pins.as_slice().len()
+    }
+
+    pub fn write(&mut self, word: u32) {
+        let pins = self.pins.as_slice();
+        if MODE::OPEN_DRAIN {
+            interrupt_free(|| {
+                for (n, pin) in pins.iter().enumerate() {
+                    if word & (1 << n) != 0 {
+                        pin.raw().release();
+                    } else {
+                        pin.raw().drive_low();
+                    }
+                }
+            });
+        } else {
+            let value = match self.shift {
+                Some(shift) => word << shift,
+                None => spread(word, pins.iter().map(Pin::pin_id)),
+            };
+            self.data.write(value);
+        }
+    }
+
+    /// Levels of the bus lines, bit `n` from `pins[n]`
+    pub fn read(&self) -> u32 {
+        let value = self.data.read();
+        match self.shift {
+            Some(shift) => value >> shift,
+            None => gather(value, self.pins.as_slice().iter().map(Pin::pin_id)),
+        }
+    }
+
+    pub fn free(self) -> PINS {
+        self.pins
+    }
+}
+
+/// Data register mask of the pins, and the first pin's index when the pins
+/// are consecutive and ascending
+fn layout<I: Iterator<Item = u8>>(ids: I) -> (u32, Option<u8>) {
+    let mut mask = 0;
+    let mut first = None;
+    let mut consecutive = true;
+    for (n, id) in ids.enumerate() {
+        let first = *first.get_or_insert(id);
+        consecutive &= usize::from(id) == usize::from(first) + n;
+        mask |= 1 << id;
+    }
+    (mask, if consecutive { first } else { None })
+}
+
+/// Move bit `n` of `word` to the bit of the `n`th pin
+fn spread<I: Iterator<Item = u8>>(word: u32, ids: I) -> u32 {
+    ids.enumerate()
+        .filter(|(n, _)| word & (1 << n) != 0)
+        .fold(0, |v, (_, id)| v | (1 << id))
+}
+
+/// Move the bit of the `n`th pin to bit `n`
+fn gather<I: Iterator<Item = u8>>(value: u32, ids: I) -> u32 {
+    ids.enumerate()
+        .filter(|(_, id)| value & (1 << id) != 0)
+        .fold(0, |w, (n, _)| w | (1 << n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_of_consecutive_pins() {
+        assert_eq!(layout([2, 3, 4].iter().copied()), (0b11100, Some(2)));
+        assert_eq!(layout([0].iter().copied()), (0b1, Some(0)));
+        assert_eq!(layout(0..24), (0xFF_FFFF, Some(0)));
+    }
+
+    #[test]
+    fn layout_of_scattered_pins() {
+        assert_eq!(layout([2, 3, 7].iter().copied()), (0b1000_1100, None));
+        // Descending pins aren't shifted either
+        assert_eq!(layout([4, 3].iter().copied()), (0b11000, None));
+        assert_eq!(layout(core::iter::empty()), (0, None));
+    }
+
+    #[test]
+    fn spread_and_gather() {
+        let ids = [2, 3, 7];
+        assert_eq!(spread(0b101, ids.iter().copied()), 0b1000_0100);
+        assert_eq!(spread(0b010, ids.iter().copied()), 0b0000_1000);
+        // Bits beyond the bus width are dropped
+        assert_eq!(spread(0b1111, ids.iter().copied()), 0b1000_1100);
+        assert_eq!(gather(0b1000_0100, ids.iter().copied()), 0b101);
+        assert_eq!(gather(!0, ids.iter().copied()), 0b111);
+
+        let ids = [4, 3];
+        assert_eq!(spread(0b01, ids.iter().copied()), 0b10000);
+        assert_eq!(gather(0b01000, ids.iter().copied()), 0b10);
+    }
+}
@@ -0,0 +1,63 @@
+//! Masked access to a port's data register
+//!
+//! The PIO has no set/clear registers, masked writes are a read-modify-write
+//! with a single store. The mask only covers pins the owner holds, the
+//! other bits are written back as they were read.
+
+use super::erased::{Port, Reg};
+
+/// The data register bits of the pins owned by one user of a port
+pub(crate) struct PortData {
+    port: Port,
+    mask: u32,
+}
+
+impl PortData {
+    /// `mask` is limited to the port's implemented pins
+    pub(crate) fn new(port: Port, mask: u32) -> Self {
+        PortData {
+            port,
+            mask: mask & pin_mask(port),
+        }
+    }
+
+    /// Levels of the owned pins
+    pub(crate) fn read(&self) -> u32 {
+        self.port.read(Reg::Data) & self.mask
+    }
+
+    /// Update the owned pins to the corresponding bits of `value`
+    pub(crate) fn write(&mut self, value: u32) {
+        let mask = self.mask;
+        self.port.modify(Reg::Data, |r| merge(r, mask, value));
+    }
+}
+
+/// Mask of the port's implemented pins
+fn pin_mask(port: Port) -> u32 {
+    (1 << port.width()) - 1
+}
+
+fn merge(reg: u32, mask: u32, value: u32) -> u32 {
+    (reg & !mask) | (value & mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mask_limited_to_port_width() {
+        assert_eq!(pin_mask(Port::F), 0x7F);
+        assert_eq!(pin_mask(Port::D), 0x1FF_FFFF);
+        assert_eq!(PortData::new(Port::B, !0).mask, 0x3FF);
+        assert_eq!(PortData::new(Port::L, 1 << 12 | 1 << 13).mask, 1 << 12);
+    }
+
+    #[test]
+    fn merge_keeps_unowned_bits() {
+        assert_eq!(merge(0b1010, 0b0110, 0b0101), 0b1100);
+        assert_eq!(merge(0xFFFF, 0x00F0, 0), 0xFF0F);
+        assert_eq!(merge(0, 0x00F0, !0), 0x00F0);
+    }
+}
@@ -0,0 +1,381 @@
+//! I2C
+//!
+//! Polled master driver for the TWI controllers, 7-bit addressing only
+//!
+//! TWI0-2 run from APB2. R_TWI runs from APB0 and is gated in the R_PRCM,
+//! so it is constructed with a `Prcm` instead of the `Ccu`.
+
+use crate::ccu::{Ccu, Clocks};
+use crate::gpio::{Alternate, AF0, AF1, PE14, PE15, PH0, PH1, PH2, PH3, PL0, PL1, PL8, PL9};
+use crate::hal::blocking::i2c::{Read, Transactional, Write, WriteRead};
+use crate::pac::twi_common::{
+    ClockControl, Control, Data, EnhancedFeature, RegisterBlock, SoftReset, Status,
+};
+use crate::pac::{r_twi::RTWI, twi0::TWI0, twi1::TWI1, twi2::TWI2};
+use crate::prcm::Prcm;
+use core::ops::DerefMut;
+use embedded_time::rate::Hertz;
+
+pub use crate::hal::blocking::i2c::Operation;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Error {
+    /// Illegal START or STOP condition on the bus
+    Bus,
+    /// Lost arbitration to another master
+    ArbitrationLost,
+    /// Address or data not acknowledged
+    Nack,
+    /// The controller didn't respond
+    Timeout,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Mode {
+    /// 100 kHz
+    Standard,
+    /// 400 kHz
+    Fast,
+}
+
+impl Mode {
+    pub fn frequency(self) -> Hertz {
+        match self {
+            Mode::Standard => Hertz(100_000),
+            Mode::Fast => Hertz(400_000),
+        }
+    }
+}
+
+// TODO - these should be "closed" traits
+pub trait Pins<TWI> {}
+pub trait PinScl<TWI> {}
+pub trait PinSda<TWI> {}
+
+impl<TWI, SCL, SDA> Pins<TWI> for (SCL, SDA)
+where
+    SCL: PinScl<TWI>,
+    SDA: PinSda<TWI>,
+{
+}
+
+impl PinScl<TWI0> for PH0<Alternate<AF0>> {}
+impl PinSda<TWI0> for PH1<Alternate<AF0>> {}
+
+impl PinScl<TWI1> for PH2<Alternate<AF0>> {}
+impl PinSda<TWI1> for PH3<Alternate<AF0>> {}
+
+impl PinScl<TWI2> for PE14<Alternate<AF1>> {}
+impl PinSda<TWI2> for PE15<Alternate<AF1>> {}
+
+impl PinScl<RTWI> for PL0<Alternate<AF1>> {}
+impl PinSda<RTWI> for PL1<Alternate<AF1>> {}
+
+impl PinScl<RTWI> for PL8<Alternate<AF0>> {}
+impl PinSda<RTWI> for PL9<Alternate<AF0>> {}
+
+/// Polling iterations before giving up on the controller
+const TIMEOUT: u32 = 0x10_0000;
+
+pub struct I2c<TWI, PINS> {
+    twi: TWI,
+    pins: PINS,
+}
+
+/// CCR factors (N, M) for the fastest SCL not above `scl`
+///
+/// Fscl = Fin / (2^N * 10 * (M + 1))
+fn clock_factors(fin: Hertz, scl: Hertz) -> (u32, u32) {
+    let mut best = (7, 15, 0);
+    for n in 0..8 {
+        for m in 0..16 {
+            let f = (fin.0 >> n) / (10 * (m + 1));
+            if f <= scl.0 && f > best.2 {
+                best = (n, m, f);
+            }
+        }
+    }
+    (best.0, best.1)
+}
+
+macro_rules! hal {
+    ($(
+        $TWIX:ident: $twiX:ident,
+    )+) => {
+        $(
+            impl<PINS> I2c<$TWIX, PINS> {
+                pub fn $twiX(
+                    twi: $TWIX,
+                    pins: PINS,
+                    mode: Mode,
+                    clocks: Clocks,
+                    ccu: &mut Ccu,
+                ) -> Self
+                where
+                    PINS: Pins<$TWIX>,
+                {
+                    ccu.enable_and_reset::<$TWIX>();
+
+                    let mut i2c = I2c { twi, pins };
+                    i2c.init(mode, clocks.apb2());
+                    i2c
+                }
+            }
+        )+
+    }
+}
+
+hal! {
+    TWI0: twi0,
+    TWI1: twi1,
+    TWI2: twi2,
+}
+
+impl<PINS> I2c<RTWI, PINS> {
+    pub fn r_twi(twi: RTWI, pins: PINS, mode: Mode, clocks: Clocks, prcm: &mut Prcm) -> Self
+    where
+        PINS: Pins<RTWI>,
+    {
+        prcm.enable_and_reset::<RTWI>();
+
+        let mut i2c = I2c { twi, pins };
+        i2c.init(mode, clocks.apb0());
+        i2c
+    }
+}
+
+impl<TWI, PINS> I2c<TWI, PINS>
+where
+    TWI: DerefMut<Target = RegisterBlock>,
+{
+    /// `fin` is the bus clock
+    fn init(&mut self, mode: Mode, fin: Hertz) {
+        self.twi.srst.modify(SoftReset::Reset::Set);
+        let mut timeout = TIMEOUT;
+        while self.twi.srst.is_set(SoftReset::Reset::Read) && timeout != 0 {
+            timeout -= 1;
+        }
+
+        let (n, m) = clock_factors(fin, mode.frequency());
+        self.twi.ccr.modify(
+            ClockControl::N::Field::new(n).unwrap() + ClockControl::M::Field::new(m).unwrap(),
+        );
+        self.twi.efr.modify(EnhancedFeature::DataByteNumber::NoData);
+        self.twi.cntr.modify(
+            Control::BusEnable::Set
+                + Control::IntEnable::Clear
+                + Control::AssertAck::Clear
+                + Control::IntFlag::Set,
+        );
+    }
+
+    pub fn free(self) -> (TWI, PINS) {
+        (self.twi, self.pins)
+    }
+
+    /// Wait for the controller to finish the current step
+    fn wait(&self) -> Result<Option<Status::Status::Field>, Error> {
+        for _ in 0..TIMEOUT {
+            if self.twi.cntr.is_set(Control::IntFlag::Read) {
+                return Ok(self.twi.stat.get_field(Status::Status::Read));
+            }
+        }
+        Err(Error::Timeout)
+    }
+
+    /// Wait for the step and check it ended in `expected`
+    fn expect(&self, expected: Status::Status::Field) -> Result<(), Error> {
+        let status = self.wait()?;
+        if status == Some(expected) {
+            Ok(())
+        } else if status == Some(Status::Status::ArbitrationLost) {
+            Err(Error::ArbitrationLost)
+        } else if status == Some(Status::Status::AddrWriteNack)
+            || status == Some(Status::Status::AddrReadNack)
+            || status == Some(Status::Status::DataTransmittedNack)
+        {
+            Err(Error::Nack)
+        } else {
+            Err(Error::Bus)
+        }
+    }
+
+    /// START, or a repeated START when a transfer is in progress
+    fn start(&mut self) -> Result<(), Error> {
+        self.twi
+            .cntr
+            .modify(Control::MasterStart::Set + Control::IntFlag::Set);
+        let status = self.wait()?;
+        if status == Some(Status::Status::StartTransmitted)
+            || status == Some(Status::Status::RepeatedStartTransmitted)
+        {
+            Ok(())
+        } else if status == Some(Status::Status::ArbitrationLost) {
+            Err(Error::ArbitrationLost)
+        } else {
+            Err(Error::Bus)
+        }
+    }
+
+    fn stop(&mut self) -> Result<(), Error> {
+        self.twi
+            .cntr
+            .modify(Control::MasterStop::Set + Control::IntFlag::Set);
+        // MasterStop clears once the STOP condition is on the bus
+        for _ in 0..TIMEOUT {
+            if !self.twi.cntr.is_set(Control::MasterStop::Read) {
+                return Ok(());
+            }
+        }
+        Err(Error::Timeout)
+    }
+
+    fn send_address(&mut self, address: u8, read: bool) -> Result<(), Error> {
+        let byte = (u32::from(address) << 1) | u32::from(read);
+        self.twi.data.modify(Data::Data::Field::new(byte).unwrap());
+        self.twi.cntr.modify(Control::IntFlag::Set);
+        if read {
+            self.expect(Status::Status::AddrReadAck)
+        } else {
+            self.expect(Status::Status::AddrWriteAck)
+        }
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        for byte in bytes {
+            self.twi
+                .data
+                .modify(Data::Data::Field::new(u32::from(*byte)).unwrap());
+            self.twi.cntr.modify(Control::IntFlag::Set);
+            self.expect(Status::Status::DataTransmittedAck)?;
+        }
+        Ok(())
+    }
+
+    /// Reads `buffer`, the last byte is not acknowledged when `last`
+    fn read_bytes(&mut self, buffer: &mut [u8], last: bool) -> Result<(), Error> {
+        let len = buffer.len();
+        for (i, byte) in buffer.iter_mut().enumerate() {
+            if last && i + 1 == len {
+                self.twi
+                    .cntr
+                    .modify(Control::AssertAck::Clear + Control::IntFlag::Set);
+                self.expect(Status::Status::DataReceivedNack)?;
+            } else {
+                self.twi
+                    .cntr
+                    .modify(Control::AssertAck::Set + Control::IntFlag::Set);
+                self.expect(Status::Status::DataReceivedAck)?;
+            }
+            *byte = self.twi.data.get_field(Data::Data::Read).unwrap().val() as u8;
+        }
+        Ok(())
+    }
+
+    /// Run `operations` between a single START and STOP
+    ///
+    /// Adjacent operations of the same kind are merged, a repeated START only
+    /// separates a write from a read.
+    pub fn transaction(&mut self, address: u8, operations: &mut [Operation]) -> Result<(), Error> {
+        if operations.is_empty() {
+            return Ok(());
+        }
+        let result = self.transfer(address, operations);
+        match result {
+            Ok(()) | Err(Error::Nack) => {
+                let stop = self.stop();
+                result.and(stop)
+            }
+            Err(Error::ArbitrationLost) => result,
+            Err(_) => {
+                // Recover the state machine
+                self.twi.srst.modify(SoftReset::Reset::Set);
+                result
+            }
+        }
+    }
+
+    fn transfer(&mut self, address: u8, operations: &mut [Operation]) -> Result<(), Error> {
+        let count = operations.len();
+        let mut reading = None;
+        for i in 0..count {
+            let next_is_read = match operations.get(i + 1) {
+                Some(Operation::Read(_)) => Some(true),
+                Some(Operation::Write(_)) => Some(false),
+                None => None,
+            };
+            match &mut operations[i] {
+                Operation::Write(bytes) => {
+                    if reading != Some(false) {
+                        self.start()?;
+                        self.send_address(address, false)?;
+                        reading = Some(false);
+                    }
+                    self.write_bytes(bytes)?;
+                }
+                Operation::Read(buffer) => {
+                    if reading != Some(true) {
+                        self.start()?;
+                        self.send_address(address, true)?;
+                        reading = Some(true);
+                    }
+                    // Keep acknowledging when the next read continues this one
+                    self.read_bytes(buffer, next_is_read != Some(true))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<TWI, PINS> Write for I2c<TWI, PINS>
+where
+    TWI: DerefMut<Target = RegisterBlock>,
+{
+    type Error = Error;
+
+    fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.transaction(addr, &mut [Operation::Write(bytes)])
+    }
+}
+
+impl<TWI, PINS> Read for I2c<TWI, PINS>
+where
+    TWI: DerefMut<Target = RegisterBlock>,
+{
+    type Error = Error;
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.transaction(address, &mut [Operation::Read(buffer)])
+    }
+}
+
+impl<TWI, PINS> WriteRead for I2c<TWI, PINS>
+where
+    TWI: DerefMut<Target = RegisterBlock>,
+{
+    type Error = Error;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.transaction(
+            address,
+            &mut [Operation::Write(bytes), Operation::Read(buffer)],
+        )
+    }
+}
+
+impl<TWI, PINS> Transactional for I2c<TWI, PINS>
+where
+    TWI: DerefMut<Target = RegisterBlock>,
+{
+    type Error = Error;
+
+    fn exec(&mut self, address: u8, operations: &mut [Operation]) -> Result<(), Self::Error> {
+        self.transaction(address, operations)
+    }
+}
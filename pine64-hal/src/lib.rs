@@ -8,11 +8,18 @@ pub use embedded_time as time;
 pub use nb;
 pub use pine64 as pac;
 
+pub mod axp803;
 pub mod ccu;
 pub mod delay;
 pub mod display;
 pub mod dma;
 pub mod gpio;
+pub mod i2c;
+pub mod prcm;
 pub mod prelude;
+pub mod pwm;
+pub mod rsb;
+pub mod rtc;
 pub mod serial;
+pub mod spi;
 pub mod timer;
@@ -0,0 +1,124 @@
+//! R_PRCM bus clock gating and reset
+//!
+//! The R_ peripherals sit on APB0 and are gated and reset in the R_PRCM
+//! rather than the CCU. The APB0 rate is in `Clocks::apb0`.
+//!
+//! ```ignore
+//! let mut prcm = r_prcm.constrain();
+//! prcm.enable_and_reset::<RTWI>();
+//! ```
+
+use crate::pac::r_pio::RPIO;
+use crate::pac::r_prcm::{Apb0ClockGating, Apb0SoftReset, RPRCM};
+use crate::pac::r_rsb::RRSB;
+use crate::pac::r_twi::RTWI;
+
+pub trait PrcmExt {
+    fn constrain(self) -> Prcm;
+}
+
+impl PrcmExt for RPRCM {
+    fn constrain(self) -> Prcm {
+        Prcm { prcm: self }
+    }
+}
+
+pub struct Prcm {
+    prcm: RPRCM,
+}
+
+/// APB0 bus clock gating
+pub trait Enable {
+    fn enable(prcm: &mut Prcm);
+
+    fn disable(prcm: &mut Prcm);
+
+    fn is_enabled(prcm: &mut Prcm) -> bool;
+}
+
+/// APB0 soft reset
+pub trait Reset {
+    /// Hold the peripheral in reset
+    fn assert_reset(prcm: &mut Prcm);
+
+    /// Release the peripheral from reset
+    fn deassert_reset(prcm: &mut Prcm);
+
+    fn reset(prcm: &mut Prcm) {
+        Self::assert_reset(prcm);
+        Self::deassert_reset(prcm);
+    }
+}
+
+impl Prcm {
+    pub fn enable<P: Enable>(&mut self) {
+        P::enable(self);
+    }
+
+    pub fn disable<P: Enable>(&mut self) {
+        P::disable(self);
+    }
+
+    /// Pulse the peripheral's reset and ungate its bus clock
+    pub fn enable_and_reset<P: Enable + Reset>(&mut self) {
+        P::reset(self);
+        P::enable(self);
+    }
+
+    /// Gate the peripheral's bus clock and hold it in reset
+    pub fn power_down<P: Enable + Reset>(&mut self) {
+        P::disable(self);
+        P::assert_reset(self);
+    }
+
+    pub fn free(self) -> RPRCM {
+        self.prcm
+    }
+}
+
+macro_rules! apb0_enable {
+    ($($PER:ident: $Field:ident,)+) => {
+        $(
+            impl Enable for $PER {
+                fn enable(prcm: &mut Prcm) {
+                    prcm.prcm.apb0_gate.modify(Apb0ClockGating::$Field::Set);
+                }
+
+                fn disable(prcm: &mut Prcm) {
+                    prcm.prcm.apb0_gate.modify(Apb0ClockGating::$Field::Clear);
+                }
+
+                fn is_enabled(prcm: &mut Prcm) -> bool {
+                    prcm.prcm.apb0_gate.is_set(Apb0ClockGating::$Field::Read)
+                }
+            }
+        )+
+    }
+}
+
+macro_rules! apb0_reset {
+    ($($PER:ident: $Field:ident,)+) => {
+        $(
+            impl Reset for $PER {
+                fn assert_reset(prcm: &mut Prcm) {
+                    prcm.prcm.apb0_rst.modify(Apb0SoftReset::$Field::Clear);
+                }
+
+                fn deassert_reset(prcm: &mut Prcm) {
+                    prcm.prcm.apb0_rst.modify(Apb0SoftReset::$Field::Set);
+                }
+            }
+        )+
+    }
+}
+
+apb0_enable! {
+    RPIO: RPio,
+    RRSB: RRsb,
+    RTWI: RTwi,
+}
+
+apb0_reset! {
+    RRSB: RRsb,
+    RTWI: RTwi,
+}
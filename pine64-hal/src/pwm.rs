@@ -0,0 +1,335 @@
+//! PWM
+//!
+//! One channel each on the PWM and R_PWM, clocked from the 24 MHz oscillator.
+//! Neither has a bus clock gate in the CCU.
+//!
+//! ```ignore
+//! let pin = rgpio.pl.pl10.into_alternate_af0();
+//! let mut backlight = Pwm::r_pwm(r_pwm, pin, 50_000_u32.Hz())?;
+//! backlight.try_set_duty(PwmPin::get_max_duty(&backlight) / 2)?;
+//! PwmPin::enable(&mut backlight);
+//! ```
+
+use crate::ccu::Clocks;
+use crate::gpio::{Alternate, AF0, PD22, PL10};
+use crate::hal;
+use crate::pac::pwm::{Control, Period, RegisterBlock, PWM};
+use crate::pac::r_pwm::RPWM;
+use core::ops::DerefMut;
+use embedded_time::rate::Hertz;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Error {
+    /// The previous period write never completed
+    Timeout,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Polarity {
+    ActiveHigh,
+    ActiveLow,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Mode {
+    /// Continuous output
+    Cycle,
+    /// A single active pulse per `Pwm::start_pulse`
+    Pulse,
+}
+
+// TODO - this should be a "closed" trait
+pub trait PinPwm<PWM> {}
+
+impl PinPwm<PWM> for PD22<Alternate<AF0>> {}
+impl PinPwm<RPWM> for PL10<Alternate<AF0>> {}
+
+/// Polling iterations before giving up on the period register
+const TIMEOUT: u32 = 0x10_0000;
+
+/// Largest period, in prescaled cycles
+///
+/// The active count is only 16 bits, keeping the period within it lets the
+/// duty reach 100%.
+const MAX_CYCLES: u32 = 0xFFFF;
+
+/// Prescaler register values and their divider, smallest divider first
+const PRESCALERS: [(u32, u32); 11] = [
+    (15, 1),
+    (0, 120),
+    (1, 180),
+    (2, 240),
+    (3, 360),
+    (4, 480),
+    (8, 12_000),
+    (9, 24_000),
+    (10, 36_000),
+    (11, 48_000),
+    (12, 72_000),
+];
+
+pub struct Pwm<PWM, PIN> {
+    pwm: PWM,
+    pin: PIN,
+    /// Prescaler divider
+    divider: u32,
+    /// Entire cycles of the period
+    cycles: u32,
+}
+
+macro_rules! hal {
+    ($(
+        $PWMX:ident: $pwmX:ident,
+    )+) => {
+        $(
+            impl<PIN> Pwm<$PWMX, PIN> {
+                /// Starts with a 0% duty
+                pub fn $pwmX(pwm: $PWMX, pin: PIN, period: Hertz) -> Result<Self, Error>
+                where
+                    PIN: PinPwm<$PWMX>,
+                {
+                    let mut pwm = Pwm {
+                        pwm,
+                        pin,
+                        divider: 1,
+                        cycles: MAX_CYCLES,
+                    };
+                    pwm.pwm.ctrl.modify(
+                        Control::Enable::Clear
+                            + Control::ClockGating::Clear
+                            + Control::Mode::Cycle
+                            + Control::ActiveState::High
+                            + Control::Bypass::Clear,
+                    );
+                    let cycles = pwm.set_prescaler(period);
+                    pwm.write_period(cycles, 0)?;
+                    Ok(pwm)
+                }
+            }
+        )+
+    }
+}
+
+hal! {
+    PWM: pwm,
+    RPWM: r_pwm,
+}
+
+/// Prescaler register value, divider and entire cycles for `period`
+fn period_factors(period: Hertz) -> (u32, u32, u32) {
+    let ticks = Clocks::OSC_24M_FREQ.0 / period.0.max(1);
+    PRESCALERS
+        .iter()
+        .map(|&(val, div)| (val, div, ticks / div))
+        .find(|&(_, _, cycles)| cycles <= MAX_CYCLES)
+        .map(|(val, div, cycles)| (val, div, cycles.max(1)))
+        .unwrap_or((12, 72_000, MAX_CYCLES))
+}
+
+impl<PWM, PIN> Pwm<PWM, PIN>
+where
+    PWM: DerefMut<Target = RegisterBlock>,
+{
+    pub fn free(self) -> (PWM, PIN) {
+        (self.pwm, self.pin)
+    }
+
+    pub fn set_polarity(&mut self, polarity: Polarity) {
+        match polarity {
+            Polarity::ActiveHigh => self.pwm.ctrl.modify(Control::ActiveState::High),
+            Polarity::ActiveLow => self.pwm.ctrl.modify(Control::ActiveState::Low),
+        }
+    }
+
+    pub fn set_mode(&mut self, mode: Mode) {
+        match mode {
+            Mode::Cycle => self.pwm.ctrl.modify(Control::Mode::Cycle),
+            Mode::Pulse => self.pwm.ctrl.modify(Control::Mode::Pulse),
+        }
+    }
+
+    /// Emit one pulse of the duty's width, in pulse mode
+    pub fn start_pulse(&mut self) {
+        self.pwm.ctrl.modify(Control::PulseStart::Set);
+    }
+
+    /// The previous pulse has completed
+    pub fn is_pulse_done(&self) -> bool {
+        !self.pwm.ctrl.is_set(Control::PulseStart::Read)
+    }
+
+    fn wait_period_ready(&self) -> Result<(), Error> {
+        let mut timeout = TIMEOUT;
+        while self.pwm.ctrl.is_set(Control::PeriodBusy::Read) {
+            if timeout == 0 {
+                return Err(Error::Timeout);
+            }
+            timeout -= 1;
+        }
+        Ok(())
+    }
+
+    fn active_cycles(&self) -> u32 {
+        self.pwm
+            .ch0_period
+            .get_field(Period::ActiveCycles::Read)
+            .map_or(0, |f| f.val())
+    }
+
+    fn write_period(&mut self, cycles: u32, active: u32) -> Result<(), Error> {
+        self.wait_period_ready()?;
+        self.pwm.ch0_period.modify(
+            Period::EntireCycles::Field::new(cycles - 1).unwrap()
+                + Period::ActiveCycles::Field::new(active.min(cycles)).unwrap(),
+        );
+        Ok(())
+    }
+
+    /// Program the prescaler for `period`, returns the entire cycles
+    fn set_prescaler(&mut self, period: Hertz) -> u32 {
+        let (val, divider, cycles) = period_factors(period);
+
+        // The prescaler only changes with the clock gated
+        let enabled = self.pwm.ctrl.is_set(Control::ClockGating::Read);
+        self.pwm.ctrl.modify(Control::ClockGating::Clear);
+        self.pwm
+            .ctrl
+            .modify(Control::Prescaler::Field::new(val).unwrap());
+        if enabled {
+            self.pwm.ctrl.modify(Control::ClockGating::Set);
+        }
+
+        self.divider = divider;
+        self.cycles = cycles;
+        cycles
+    }
+
+    /// Set the duty, in cycles of `get_max_duty`
+    pub fn try_set_duty(&mut self, duty: u32) -> Result<(), Error> {
+        let cycles = self.cycles;
+        self.write_period(cycles, duty)
+    }
+
+    /// Set the period, keeping the duty ratio
+    pub fn try_set_period(&mut self, period: Hertz) -> Result<(), Error> {
+        // Nothing changes if the last write is stuck
+        self.wait_period_ready()?;
+        let active = u64::from(self.active_cycles());
+        let old_cycles = u64::from(self.cycles);
+        let cycles = self.set_prescaler(period);
+        let active = (active * u64::from(cycles) / old_cycles) as u32;
+        self.write_period(cycles, active)
+    }
+}
+
+impl<PWM, PIN> hal::PwmPin for Pwm<PWM, PIN>
+where
+    PWM: DerefMut<Target = RegisterBlock>,
+{
+    type Duty = u32;
+
+    fn disable(&mut self) {
+        self.pwm
+            .ctrl
+            .modify(Control::Enable::Clear + Control::ClockGating::Clear);
+    }
+
+    fn enable(&mut self) {
+        self.pwm
+            .ctrl
+            .modify(Control::Enable::Set + Control::ClockGating::Set);
+    }
+
+    fn get_duty(&self) -> u32 {
+        self.active_cycles()
+    }
+
+    fn get_max_duty(&self) -> u32 {
+        self.cycles
+    }
+
+    /// A timeout leaves the duty unchanged, `try_set_duty` reports it
+    fn set_duty(&mut self, duty: u32) {
+        let _ = self.try_set_duty(duty);
+    }
+}
+
+impl<PWM, PIN> hal::Pwm for Pwm<PWM, PIN>
+where
+    PWM: DerefMut<Target = RegisterBlock>,
+{
+    type Channel = ();
+    type Time = Hertz;
+    type Duty = u32;
+
+    fn disable(&mut self, _channel: ()) {
+        hal::PwmPin::disable(self);
+    }
+
+    fn enable(&mut self, _channel: ()) {
+        hal::PwmPin::enable(self);
+    }
+
+    fn get_period(&self) -> Hertz {
+        Hertz(Clocks::OSC_24M_FREQ.0 / (self.divider * self.cycles))
+    }
+
+    fn get_duty(&self, _channel: ()) -> u32 {
+        hal::PwmPin::get_duty(self)
+    }
+
+    fn get_max_duty(&self) -> u32 {
+        hal::PwmPin::get_max_duty(self)
+    }
+
+    fn set_duty(&mut self, _channel: (), duty: u32) {
+        hal::PwmPin::set_duty(self, duty);
+    }
+
+    /// A timeout leaves the duty unchanged, `try_set_period` reports it
+    fn set_period<P>(&mut self, period: P)
+    where
+        P: Into<Hertz>,
+    {
+        let _ = self.try_set_period(period.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn period_factors_without_prescaler() {
+        // 24 MHz / 367 Hz = 65395 cycles, the last period the 16 bit count
+        // holds undivided
+        assert_eq!(period_factors(Hertz(367)), (15, 1, 65_395));
+        assert_eq!(period_factors(Hertz(24_000_000)), (15, 1, 1));
+        // Faster than the oscillator still gets a cycle
+        assert_eq!(period_factors(Hertz(48_000_000)), (15, 1, 1));
+    }
+
+    #[test]
+    fn period_factors_prescaler_edges() {
+        assert_eq!(period_factors(Hertz(366)), (0, 120, 546));
+        assert_eq!(period_factors(Hertz(4)), (0, 120, 50_000));
+        assert_eq!(period_factors(Hertz(3)), (1, 180, 44_444));
+        assert_eq!(period_factors(Hertz(1)), (4, 480, 50_000));
+        // A zero period is treated as 1 Hz
+        assert_eq!(period_factors(Hertz(0)), (4, 480, 50_000));
+    }
+
+    #[test]
+    fn period_factors_stay_within_range() {
+        for freq in 1..100_000 {
+            let (_, div, cycles) = period_factors(Hertz(freq));
+            assert!((1..=MAX_CYCLES).contains(&cycles));
+            // The smallest divider that fits is used
+            let ticks = Clocks::OSC_24M_FREQ.0 / freq;
+            assert!(PRESCALERS
+                .iter()
+                .take_while(|&&(_, d)| d < div)
+                .all(|&(_, d)| ticks / d > MAX_CYCLES));
+        }
+    }
+}
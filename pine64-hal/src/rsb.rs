@@ -0,0 +1,200 @@
+//! Reduced serial bus
+//!
+//! Push-pull two wire bus to the PMIC, devices are addressed by a runtime
+//! address assigned from their hardware address.
+//!
+//! R_RSB runs from APB0 and is gated in the R_PRCM, so it is constructed
+//! with a `Prcm` instead of the `Ccu`.
+
+use crate::ccu::Clocks;
+use crate::gpio::{Alternate, AF0, PL0, PL1};
+use crate::pac::r_rsb::{
+    ClockControl, Command, Control, DeviceAddress, DeviceModeControl, IntStatus, RRSB,
+};
+use crate::prcm::Prcm;
+use embedded_time::rate::Hertz;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Error {
+    /// The controller was busy when the transfer was loaded
+    Busy,
+    /// The device didn't acknowledge or a parity check failed
+    Transfer,
+    /// The controller didn't respond
+    Timeout,
+}
+
+// TODO - these should be "closed" traits
+pub trait Pins {}
+pub trait PinSck {}
+pub trait PinSda {}
+
+impl<SCK, SDA> Pins for (SCK, SDA)
+where
+    SCK: PinSck,
+    SDA: PinSda,
+{
+}
+
+impl PinSck for PL0<Alternate<AF0>> {}
+impl PinSda for PL1<Alternate<AF0>> {}
+
+/// Bus clock, the default used by the boot loaders
+pub const DEFAULT_FREQ: Hertz = Hertz(3_000_000);
+
+/// Polling iterations before giving up on the controller
+const TIMEOUT: u32 = 0x10_0000;
+
+// Switches a PMIC from I2C to RSB mode, sent to every device at once
+const DEVICE_MODE_REGISTER: u32 = 0x3E;
+const DEVICE_MODE_DATA: u32 = 0x7C;
+
+/// Clock divider and SDA output delay, SCK = apb0 / (2 * (div + 1))
+fn clock_divider(apb0: Hertz, frequency: Hertz) -> (u32, u32) {
+    let div = (apb0.0 / (2 * frequency.0.max(1))).clamp(1, 0x100) - 1;
+    let delay = (div >> 1).clamp(1, 7);
+    (div, delay)
+}
+
+pub struct Rsb<PINS> {
+    rsb: RRSB,
+    pins: PINS,
+}
+
+impl<PINS> Rsb<PINS>
+where
+    PINS: Pins,
+{
+    /// Reset the controller and switch the devices on the bus to RSB mode
+    pub fn new(
+        mut rsb: RRSB,
+        pins: PINS,
+        frequency: Hertz,
+        clocks: Clocks,
+        prcm: &mut Prcm,
+    ) -> Result<Self, Error> {
+        prcm.enable_and_reset::<RRSB>();
+
+        rsb.ctrl.modify(Control::SoftReset::Set);
+        wait_for(|| !rsb.ctrl.is_set(Control::SoftReset::Read))?;
+
+        let (div, delay) = clock_divider(clocks.apb0(), frequency);
+        rsb.ccr.modify(
+            ClockControl::ClockDiv::Field::new(div).unwrap()
+                + ClockControl::SdaOutputDelay::Field::new(delay).unwrap(),
+        );
+
+        rsb.dmcr.modify(
+            DeviceModeControl::DeviceAddress::Field::new(0).unwrap()
+                + DeviceModeControl::ModeRegister::Field::new(DEVICE_MODE_REGISTER).unwrap()
+                + DeviceModeControl::ModeData::Field::new(DEVICE_MODE_DATA).unwrap()
+                + DeviceModeControl::DeviceModeStart::Set,
+        );
+        wait_for(|| !rsb.dmcr.is_set(DeviceModeControl::DeviceModeStart::Read))?;
+
+        Ok(Rsb { rsb, pins })
+    }
+
+    pub fn free(self) -> (RRSB, PINS) {
+        (self.rsb, self.pins)
+    }
+
+    /// Assign `runtime_address` to the device at `hardware_address`
+    pub fn set_runtime_address(
+        &mut self,
+        hardware_address: u16,
+        runtime_address: u8,
+    ) -> Result<(), Error> {
+        self.rsb.cmd.modify(Command::Command::SetRuntimeAddress);
+        self.rsb.dar.modify(
+            DeviceAddress::HardwareAddress::Field::new(u32::from(hardware_address)).unwrap()
+                + DeviceAddress::RuntimeAddress::Field::new(u32::from(runtime_address)).unwrap(),
+        );
+        self.transfer()
+    }
+
+    pub fn read_u8(&mut self, runtime_address: u8, reg: u8) -> Result<u8, Error> {
+        self.rsb.cmd.modify(Command::Command::Read8);
+        self.set_target(runtime_address, reg);
+        self.transfer()?;
+        Ok(self.rsb.data.read() as u8)
+    }
+
+    pub fn write_u8(&mut self, runtime_address: u8, reg: u8, value: u8) -> Result<(), Error> {
+        self.rsb.cmd.modify(Command::Command::Write8);
+        self.set_target(runtime_address, reg);
+        self.rsb.data.write(u32::from(value));
+        self.transfer()
+    }
+
+    /// Read-modify-write of the bits in `mask`
+    pub fn modify_u8(
+        &mut self,
+        runtime_address: u8,
+        reg: u8,
+        mask: u8,
+        value: u8,
+    ) -> Result<(), Error> {
+        let old = self.read_u8(runtime_address, reg)?;
+        self.write_u8(runtime_address, reg, (old & !mask) | (value & mask))
+    }
+
+    fn set_target(&mut self, runtime_address: u8, reg: u8) {
+        self.rsb.dar.modify(
+            DeviceAddress::HardwareAddress::Field::new(0).unwrap()
+                + DeviceAddress::RuntimeAddress::Field::new(u32::from(runtime_address)).unwrap(),
+        );
+        self.rsb.addr.write(u32::from(reg));
+    }
+
+    fn transfer(&mut self) -> Result<(), Error> {
+        self.rsb.ctrl.modify(Control::StartTransfer::Set);
+        let rsb = &self.rsb;
+        wait_for(|| !rsb.ctrl.is_set(Control::StartTransfer::Read))?;
+
+        let status = self.rsb.stat.read();
+        // Write 1 to clear
+        self.rsb.stat.write(status);
+        let status = IntStatus::Register::new(status);
+        if status.is_set(IntStatus::LoadBusy::Read) {
+            Err(Error::Busy)
+        } else if status.is_set(IntStatus::TransferError::Read) {
+            Err(Error::Transfer)
+        } else if status.is_set(IntStatus::TransferOver::Read) {
+            Ok(())
+        } else {
+            Err(Error::Timeout)
+        }
+    }
+}
+
+fn wait_for<F: Fn() -> bool>(done: F) -> Result<(), Error> {
+    for _ in 0..TIMEOUT {
+        if done() {
+            return Ok(());
+        }
+    }
+    Err(Error::Timeout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn divider_from_apb0() {
+        // 24 MHz APB0, as the boot loader leaves it
+        assert_eq!(clock_divider(Hertz(24_000_000), DEFAULT_FREQ), (3, 1));
+        assert_eq!(clock_divider(Hertz(24_000_000), Hertz(400_000)), (29, 7));
+        // A slower APB0 needs a smaller divider for the same rate
+        assert_eq!(clock_divider(Hertz(12_000_000), DEFAULT_FREQ), (1, 1));
+    }
+
+    #[test]
+    fn divider_limits() {
+        // Too fast for the source, the smallest divider
+        assert_eq!(clock_divider(Hertz(24_000_000), Hertz(24_000_000)), (0, 1));
+        assert_eq!(clock_divider(Hertz(24_000_000), Hertz(0)), (0xFF, 7));
+        assert_eq!(clock_divider(Hertz(24_000_000), Hertz(1_000)), (0xFF, 7));
+    }
+}
@@ -0,0 +1,508 @@
+//! Real time clock
+//!
+//! Calendar time is kept in the always-on domain, clocked by the 32 kHz LOSC.
+//!
+//! ```ignore
+//! let mut rtc = Rtc::new(rtc, ClockSource::External);
+//! rtc.set_date_time(&DateTime::new(2021, 3, 14, 15, 9, 26)?)?;
+//! rtc.set_alarm0(Seconds(60_u32))?;
+//! rtc.listen(Event::Alarm0);
+//! let now: Instant<Rtc> = rtc.try_now().unwrap();
+//! ```
+
+use crate::ccu::Clocks;
+use crate::pac::rtc::{
+    Alarm0Counter, Alarm0Enable, Alarm1Enable, Alarm1Time, AlarmConfig, AlarmIrqEnable,
+    AlarmIrqStatus, Date, IntOscPrescaler, LoscControl, Time, RTC,
+};
+use embedded_time::{clock, duration::Seconds, fraction::Fraction, rate::Hertz, Clock, Instant};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Error {
+    /// A field is out of range, or the date doesn't exist
+    InvalidDateTime,
+    /// The previous write hasn't reached the always-on domain
+    Timeout,
+}
+
+/// Interrupt events
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Event {
+    /// Alarm 0 counted down to zero
+    Alarm0,
+    /// Alarm 1 matched the weekday and time
+    Alarm1,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ClockSource {
+    /// Internal RC oscillator, roughly 16 MHz / 32 / prescaler
+    Internal,
+    /// External 32.768 kHz crystal
+    External,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    /// Alarm 1 enable bit
+    pub fn mask(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// Calendar date and time, UTC
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct DateTime {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+impl DateTime {
+    /// The year register counts from 1970 in 6 bits
+    pub const MIN_YEAR: u16 = 1970;
+    pub const MAX_YEAR: u16 = 2033;
+
+    pub fn new(
+        year: u16,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    ) -> Result<Self, Error> {
+        if !(Self::MIN_YEAR..=Self::MAX_YEAR).contains(&year)
+            || !(1..=12).contains(&month)
+            || day < 1
+            || day > days_in_month(year, month)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return Err(Error::InvalidDateTime);
+        }
+        Ok(DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        // 1970-01-01 was a Thursday
+        Weekday::ALL[((self.days_since_epoch() + 3) % 7) as usize]
+    }
+
+    /// Seconds since the Unix epoch
+    pub fn unix_seconds(&self) -> Seconds<u32> {
+        Seconds(
+            self.days_since_epoch() * 86_400
+                + u32::from(self.hour) * 3600
+                + u32::from(self.minute) * 60
+                + u32::from(self.second),
+        )
+    }
+
+    pub fn from_unix_seconds(seconds: Seconds<u32>) -> Result<Self, Error> {
+        let days = seconds.0 / 86_400;
+        let secs = seconds.0 % 86_400;
+
+        // Civil from days, with years starting in March
+        let z = days + 719_468;
+        let era = z / 146_097;
+        let doe = z % 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + u32::from(month <= 2);
+        if year > u32::from(Self::MAX_YEAR) {
+            return Err(Error::InvalidDateTime);
+        }
+
+        Self::new(
+            year as u16,
+            month as u8,
+            day as u8,
+            (secs / 3600) as u8,
+            (secs / 60 % 60) as u8,
+            (secs % 60) as u8,
+        )
+    }
+
+    fn days_since_epoch(&self) -> u32 {
+        let y = u32::from(self.year) - u32::from(self.month <= 2);
+        let m = u32::from(self.month);
+        let era = y / 400;
+        let yoe = y % 400;
+        let mp = if m > 2 { m - 3 } else { m + 9 };
+        let doy = (153 * mp + 2) / 5 + u32::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+}
+
+impl From<DateTime> for Seconds<u32> {
+    fn from(dt: DateTime) -> Self {
+        dt.unix_seconds()
+    }
+}
+
+fn is_leap_year(year: u16) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Polling iterations before giving up on the always-on domain
+const TIMEOUT: u32 = 0x10_0000;
+
+/// Unlocks writes to the LOSC control register
+const LOSC_KEY: u32 = 0x16AA;
+
+/// Internal oscillator after its fixed divider
+const INTERNAL_OSC_FREQ: u32 = 16_000_000 / 32;
+
+/// Writes that cross into the always-on domain
+#[derive(Copy, Clone)]
+enum Access {
+    Date,
+    Time,
+    Alarm,
+}
+
+pub struct Rtc {
+    rtc: RTC,
+}
+
+impl Rtc {
+    /// Doesn't touch the date and time, they survive a reset
+    pub fn new(rtc: RTC, source: ClockSource) -> Self {
+        let mut rtc = Rtc { rtc };
+        rtc.set_clock_source(source);
+        rtc
+    }
+
+    pub fn free(self) -> RTC {
+        self.rtc
+    }
+
+    pub fn set_clock_source(&mut self, source: ClockSource) {
+        match source {
+            ClockSource::Internal => self
+                .rtc
+                .losc_ctrl
+                .modify(losc_key() + LoscControl::ClockSrcSel::InternalOsc),
+            ClockSource::External => {
+                // The crystal has to be running before it's selected
+                self.rtc
+                    .losc_ctrl
+                    .modify(losc_key() + LoscControl::ExtLoscEnable::Set);
+                self.rtc
+                    .losc_ctrl
+                    .modify(losc_key() + LoscControl::ClockSrcSel::ExternalOsc32K);
+            }
+        }
+    }
+
+    pub fn clock_source(&self) -> ClockSource {
+        if self.rtc.losc_ctrl.get_field(LoscControl::ClockSrcSel::Read)
+            == Some(LoscControl::ClockSrcSel::ExternalOsc32K)
+        {
+            ClockSource::External
+        } else {
+            ClockSource::Internal
+        }
+    }
+
+    /// Nominal LOSC frequency, the internal oscillator is only accurate to
+    /// within a few percent
+    pub fn frequency(&self) -> Hertz {
+        match self.clock_source() {
+            ClockSource::External => Clocks::OSC_32K_FREQ,
+            ClockSource::Internal => {
+                let prescaler = 1 + self
+                    .rtc
+                    .intosc_prescaler
+                    .get_field(IntOscPrescaler::Prescaler::Read)
+                    .unwrap()
+                    .val();
+                Hertz(INTERNAL_OSC_FREQ / prescaler)
+            }
+        }
+    }
+
+    /// `InvalidDateTime` until the calendar has been set, the registers
+    /// reset to day and month 0
+    pub fn date_time(&self) -> Result<DateTime, Error> {
+        // Re-read if the date rolled over between the two registers
+        loop {
+            let date = self.rtc.date.read();
+            let time = self.rtc.time.read();
+            if date == self.rtc.date.read() {
+                return decode(date, time);
+            }
+        }
+    }
+
+    pub fn set_date_time(&mut self, dt: &DateTime) -> Result<(), Error> {
+        self.wait_access(Access::Time)?;
+        self.rtc.time.modify(
+            Time::Hour::Field::new(u32::from(dt.hour)).unwrap()
+                + Time::Minute::Field::new(u32::from(dt.minute)).unwrap()
+                + Time::Second::Field::new(u32::from(dt.second)).unwrap(),
+        );
+        self.wait_access(Access::Time)?;
+
+        self.wait_access(Access::Date)?;
+        let leap = if is_leap_year(dt.year) {
+            Date::LeapYear::Set
+        } else {
+            Date::LeapYear::Clear
+        };
+        self.rtc.date.modify(
+            Date::Year::Field::new(u32::from(dt.year - DateTime::MIN_YEAR)).unwrap()
+                + Date::Month::Field::new(u32::from(dt.month)).unwrap()
+                + Date::Day::Field::new(u32::from(dt.day)).unwrap()
+                + leap,
+        );
+        self.wait_access(Access::Date)
+    }
+
+    /// Fire alarm 0 after `seconds`, replacing any pending countdown
+    pub fn set_alarm0(&mut self, seconds: Seconds<u32>) -> Result<(), Error> {
+        self.rtc.alarm0_enable.modify(Alarm0Enable::Enable::Clear);
+        self.wait_access(Access::Alarm)?;
+        self.rtc
+            .alarm0_counter
+            .modify(Alarm0Counter::Counter::Field::new(seconds.0).unwrap());
+        self.wait_access(Access::Alarm)?;
+        self.rtc.alarm0_enable.modify(Alarm0Enable::Enable::Set);
+        Ok(())
+    }
+
+    /// Fire alarm 0 at `dt`, which must be in the future
+    pub fn set_alarm0_at(&mut self, dt: &DateTime) -> Result<(), Error> {
+        let now = self.date_time()?.unix_seconds();
+        let then = dt.unix_seconds();
+        if then <= now {
+            return Err(Error::InvalidDateTime);
+        }
+        self.set_alarm0(Seconds(then.0 - now.0))
+    }
+
+    pub fn cancel_alarm0(&mut self) {
+        self.rtc.alarm0_enable.modify(Alarm0Enable::Enable::Clear);
+    }
+
+    /// Fire alarm 1 at the time of day on each of the `weekdays`, a mask of
+    /// `Weekday::mask` bits
+    pub fn set_alarm1(
+        &mut self,
+        weekdays: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    ) -> Result<(), Error> {
+        if weekdays > 0x7F || hour > 23 || minute > 59 || second > 59 {
+            return Err(Error::InvalidDateTime);
+        }
+        self.rtc
+            .alarm1_enable
+            .modify(Alarm1Enable::Weekdays::Field::new(0).unwrap());
+        self.wait_access(Access::Alarm)?;
+        self.rtc.alarm1_time.modify(
+            Alarm1Time::Hour::Field::new(u32::from(hour)).unwrap()
+                + Alarm1Time::Minute::Field::new(u32::from(minute)).unwrap()
+                + Alarm1Time::Second::Field::new(u32::from(second)).unwrap(),
+        );
+        self.wait_access(Access::Alarm)?;
+        self.rtc
+            .alarm1_enable
+            .modify(Alarm1Enable::Weekdays::Field::new(u32::from(weekdays)).unwrap());
+        Ok(())
+    }
+
+    pub fn cancel_alarm1(&mut self) {
+        self.rtc
+            .alarm1_enable
+            .modify(Alarm1Enable::Weekdays::Field::new(0).unwrap());
+    }
+
+    /// Let the alarms drive the NMI line, so they can power the board back on
+    pub fn set_wakeup(&mut self, enable: bool) {
+        if enable {
+            self.rtc.alarm_config.modify(AlarmConfig::Wakeup::Set);
+        } else {
+            self.rtc.alarm_config.modify(AlarmConfig::Wakeup::Clear);
+        }
+    }
+
+    pub fn listen(&mut self, event: Event) {
+        match event {
+            Event::Alarm0 => self
+                .rtc
+                .alarm0_irq_enable
+                .modify(AlarmIrqEnable::Enable::Set),
+            Event::Alarm1 => self
+                .rtc
+                .alarm1_irq_enable
+                .modify(AlarmIrqEnable::Enable::Set),
+        }
+    }
+
+    pub fn unlisten(&mut self, event: Event) {
+        match event {
+            Event::Alarm0 => self
+                .rtc
+                .alarm0_irq_enable
+                .modify(AlarmIrqEnable::Enable::Clear),
+            Event::Alarm1 => self
+                .rtc
+                .alarm1_irq_enable
+                .modify(AlarmIrqEnable::Enable::Clear),
+        }
+    }
+
+    pub fn is_pending(&self, event: Event) -> bool {
+        match event {
+            Event::Alarm0 => self
+                .rtc
+                .alarm0_irq_status
+                .is_set(AlarmIrqStatus::Pending::Read),
+            Event::Alarm1 => self
+                .rtc
+                .alarm1_irq_status
+                .is_set(AlarmIrqStatus::Pending::Read),
+        }
+    }
+
+    pub fn clear(&mut self, event: Event) {
+        // Write 1 to clear
+        match event {
+            Event::Alarm0 => self.rtc.alarm0_irq_status.write(1),
+            Event::Alarm1 => self.rtc.alarm1_irq_status.write(1),
+        }
+    }
+
+    fn wait_access(&self, access: Access) -> Result<(), Error> {
+        for _ in 0..TIMEOUT {
+            let busy = match access {
+                Access::Date => self.rtc.losc_ctrl.is_set(LoscControl::DateAccess::Read),
+                Access::Time => self.rtc.losc_ctrl.is_set(LoscControl::TimeAccess::Read),
+                Access::Alarm => self.rtc.losc_ctrl.is_set(LoscControl::AlarmAccess::Read),
+            };
+            if !busy {
+                return Ok(());
+            }
+        }
+        Err(Error::Timeout)
+    }
+}
+
+fn losc_key() -> LoscControl::Key::Field {
+    LoscControl::Key::Field::new(LOSC_KEY).unwrap()
+}
+
+fn decode(date: u32, time: u32) -> Result<DateTime, Error> {
+    let date = Date::Register::new(date);
+    let time = Time::Register::new(time);
+    let year = date.get_field(Date::Year::Read).unwrap().val() as u16;
+    DateTime::new(
+        DateTime::MIN_YEAR + year,
+        date.get_field(Date::Month::Read).unwrap().val() as u8,
+        date.get_field(Date::Day::Read).unwrap().val() as u8,
+        time.get_field(Time::Hour::Read).unwrap().val() as u8,
+        time.get_field(Time::Minute::Read).unwrap().val() as u8,
+        time.get_field(Time::Second::Read).unwrap().val() as u8,
+    )
+}
+
+/// One tick per second since the Unix epoch
+impl Clock for Rtc {
+    type T = u32;
+
+    const SCALING_FACTOR: Fraction = Fraction::new(1, 1);
+
+    /// `NotRunning` until the calendar has been set
+    fn try_now(&self) -> Result<Instant<Self>, clock::Error> {
+        let dt = self.date_time().map_err(|_| clock::Error::NotRunning)?;
+        Ok(Instant::new(dt.unix_seconds().0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_rejects_reset_value() {
+        assert_eq!(decode(0, 0), Err(Error::InvalidDateTime));
+    }
+
+    #[test]
+    fn unix_seconds_round_trip() {
+        let dt = DateTime::new(2021, 3, 14, 15, 9, 26).unwrap();
+        assert_eq!(dt.unix_seconds().0, 1_615_734_566);
+        assert_eq!(DateTime::from_unix_seconds(dt.unix_seconds()), Ok(dt));
+        assert_eq!(dt.weekday(), Weekday::Sunday);
+    }
+}
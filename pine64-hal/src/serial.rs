@@ -1,11 +1,10 @@
 //! Serial
 
-use crate::ccu::{Ccu, Clocks};
+use crate::ccu::{Ccu, ClockSnapshot, Clocks};
 use crate::gpio::{
     Alternate, AF0, AF1, AF2, PB0, PB1, PB2, PB3, PB8, PB9, PD0, PD1, PD2, PD3, PD4, PD5,
 };
 use crate::hal::serial;
-use crate::pac::ccu::{BusClockGating3, BusSoftReset4};
 use crate::pac::uart_common::{
     DivisorLatchHigh, DivisorLatchLow, FifoControl, IntEnable, LineControl, LineStatus,
     NotConfigured, Receive, ReceiveHolding, Status, Transmit, TransmitHolding,
@@ -14,7 +13,7 @@ use crate::pac::{uart0::UART0, uart1::UART1, uart2::UART2, uart3::UART3, uart4::
 use core::convert::Infallible;
 use core::fmt;
 use core::marker::PhantomData;
-use embedded_time::rate::BitsPerSecond;
+use embedded_time::rate::{BitsPerSecond, Hertz};
 use nb::block;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
@@ -96,6 +95,26 @@ pub struct Serial<UART, PINS> {
     pins: PINS,
 }
 
+/// The APB2 rate the UARTs are clocked from
+///
+/// Implemented for `ClockSnapshot` so a panic handler can print without
+/// freezing the clocks.
+pub trait SerialClock {
+    fn apb2(&self) -> Hertz;
+}
+
+impl SerialClock for Clocks {
+    fn apb2(&self) -> Hertz {
+        Clocks::apb2(self)
+    }
+}
+
+impl SerialClock for ClockSnapshot {
+    fn apb2(&self) -> Hertz {
+        self.apb2
+    }
+}
+
 /// Serial receiver
 pub struct Rx<UART> {
     _uart: PhantomData<UART>,
@@ -108,24 +127,22 @@ pub struct Tx<UART> {
 
 macro_rules! hal {
     ($(
-        $UARTX:ident: ($uartX:ident, $UartX:ident, $BCGr:ident, $BCGt:ident, $BSRr:ident, $BSRt:ident),
+        $UARTX:ident: $uartX:ident,
     )+) => {
         $(
             impl<PINS, RxTx> Serial<$UARTX<RxTx>, PINS> {
-                pub fn $uartX(
+                pub fn $uartX<CLK>(
                     uart: $UARTX<RxTx>,
                     pins: PINS,
                     baud_rate: BitsPerSecond,
-                    clocks: Clocks,
+                    clocks: CLK,
                     ccu: &mut Ccu,
                 ) -> Self
                 where
                     PINS: Pins<$UARTX<RxTx>>,
+                    CLK: SerialClock,
                 {
-                    ccu.$BCGr.enr().modify($BCGt::$UartX::Set);
-                    ccu.$BSRr.rstr().modify($BSRt::$UartX::Clear);
-                    ccu.$BSRr.rstr().modify($BSRt::$UartX::Set);
-                    let _ = ccu.$BSRr.rstr().is_set($BSRt::$UartX::Set);
+                    ccu.enable_and_reset::<$UARTX>();
 
                     // Disable UART
                     unsafe {
@@ -184,7 +201,7 @@ macro_rules! hal {
                     // => divisor = (serial-clock / 16) / baudrate
                     //
                     // Add half of the denominator to deal with rounding errors
-                    let divisor = (clocks.apb2().0 + (8 * baud_rate.0)) / (16 * baud_rate.0);
+                    let divisor = (SerialClock::apb2(&clocks).0 + (8 * baud_rate.0)) / (16 * baud_rate.0);
                     let lsb = divisor & 0xFF;
                     let msb = (divisor >> 8) & 0xFF;
 
@@ -282,9 +299,9 @@ macro_rules! hal {
 }
 
 hal! {
-    UART0: (uart0, Uart0, bcg3, BusClockGating3, bsr4, BusSoftReset4),
-    UART1: (uart1, Uart1, bcg3, BusClockGating3, bsr4, BusSoftReset4),
-    UART2: (uart2, Uart2, bcg3, BusClockGating3, bsr4, BusSoftReset4),
-    UART3: (uart3, Uart3, bcg3, BusClockGating3, bsr4, BusSoftReset4),
-    UART4: (uart4, Uart4, bcg3, BusClockGating3, bsr4, BusSoftReset4),
+    UART0: uart0,
+    UART1: uart1,
+    UART2: uart2,
+    UART3: uart3,
+    UART4: uart4,
 }
@@ -0,0 +1,528 @@
+//! SPI
+//!
+//! Master mode with 8-bit words. The module clock is set to the AHB1 rate
+//! and SCK is divided down from it.
+//!
+//! Blocking transfers are split into bursts of up to one FIFO (64 bytes).
+//! Chip-select is either the controller's CS0 pin, held for the duration of
+//! each blocking call, or any GPIO through `Spi::with_cs`.
+
+use crate::ccu::{Ccu, ClockSource, Clocks, ModuleClock};
+use crate::dma::descriptor::{AddressMode, BurstLength, Config, DataWidth, DrqPort, Param};
+use crate::dma::{Buffer, Channel, Descriptor};
+use crate::gpio::{Alternate, AF2, PC0, PC1, PC2, PC3, PD0, PD1, PD2, PD3};
+use crate::hal::blocking::spi::{Transfer, Write};
+use crate::hal::digital::v2::OutputPin;
+use crate::hal::spi::{FullDuplex, Mode, Phase, Polarity};
+use crate::pac::spi_common::{
+    BurstControl, BurstCounter, ClockControl, FifoControl, FifoStatus, GlobalControl, IntStatus,
+    RegisterBlock, TransferControl, TransmitCounter, TxData,
+};
+use crate::pac::{spi0, spi0::SPI0, spi1, spi1::SPI1};
+use core::ops::{Deref, DerefMut};
+use core::pin::Pin;
+use core::sync::atomic::{self, Ordering};
+use embedded_time::rate::Hertz;
+
+pub use crate::hal::spi::{MODE_0, MODE_1, MODE_2, MODE_3};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Error {
+    /// RX FIFO overflowed
+    Overrun,
+    /// The module clock couldn't be set up
+    InvalidClock,
+    /// A burst or DMA write didn't complete
+    Timeout,
+    /// The DMA buffer is empty or larger than a burst
+    BufferSize,
+}
+
+// TODO - these should be "closed" traits
+pub trait Pins<SPI> {
+    /// The controller drives the chip-select pin
+    const HW_CS: bool;
+}
+pub trait PinSck<SPI> {}
+pub trait PinMiso<SPI> {}
+pub trait PinMosi<SPI> {}
+pub trait PinCs<SPI> {}
+
+impl<SPI, SCK, MISO, MOSI> Pins<SPI> for (SCK, MISO, MOSI)
+where
+    SCK: PinSck<SPI>,
+    MISO: PinMiso<SPI>,
+    MOSI: PinMosi<SPI>,
+{
+    const HW_CS: bool = false;
+}
+
+impl<SPI, SCK, MISO, MOSI, CS> Pins<SPI> for (SCK, MISO, MOSI, CS)
+where
+    SCK: PinSck<SPI>,
+    MISO: PinMiso<SPI>,
+    MOSI: PinMosi<SPI>,
+    CS: PinCs<SPI>,
+{
+    const HW_CS: bool = true;
+}
+
+impl PinMosi<SPI0> for PC0<Alternate<AF2>> {}
+impl PinMiso<SPI0> for PC1<Alternate<AF2>> {}
+impl PinSck<SPI0> for PC2<Alternate<AF2>> {}
+impl PinCs<SPI0> for PC3<Alternate<AF2>> {}
+
+impl PinCs<SPI1> for PD0<Alternate<AF2>> {}
+impl PinSck<SPI1> for PD1<Alternate<AF2>> {}
+impl PinMosi<SPI1> for PD2<Alternate<AF2>> {}
+impl PinMiso<SPI1> for PD3<Alternate<AF2>> {}
+
+const FIFO_DEPTH: usize = 64;
+
+/// Largest burst the counters can describe
+pub const MAX_BURST: usize = 0xFF_FFFF;
+
+/// Polling iterations before giving up on a burst
+const TIMEOUT: u32 = 0x10_0000;
+
+/// TXD register offset, the DMA destination
+const TXD_OFFSET: usize = 0x200;
+
+pub struct Spi<SPI, PINS> {
+    spi: SPI,
+    pins: PINS,
+}
+
+/// DMA write in progress
+pub struct DmaWrite<SPI, PINS, BUF> {
+    spi: Spi<SPI, PINS>,
+    desc: Pin<&'static mut Descriptor>,
+    buffer: Pin<BUF>,
+    timeout: u32,
+}
+
+/// A DMA write that failed, handing back what it was given
+pub struct DmaError<SPI, PINS, BUF> {
+    pub error: Error,
+    pub spi: Spi<SPI, PINS>,
+    pub desc: Pin<&'static mut Descriptor>,
+    pub buffer: Pin<BUF>,
+}
+
+macro_rules! hal {
+    ($(
+        $SPIX:ident: ($spiX:ident, $spix:ident, $ModuleClock:ident, $DrqPort:ident),
+    )+) => {
+        $(
+            impl<PINS> Spi<$SPIX, PINS> {
+                pub fn $spiX(
+                    spi: $SPIX,
+                    pins: PINS,
+                    mode: Mode,
+                    frequency: Hertz,
+                    clocks: Clocks,
+                    ccu: &mut Ccu,
+                ) -> Result<Self, Error>
+                where
+                    PINS: Pins<$SPIX>,
+                {
+                    ccu.enable_and_reset::<$SPIX>();
+                    let clk = ccu
+                        .set_module_clock(
+                            ModuleClock::$ModuleClock,
+                            ClockSource::PllPeriph0,
+                            clocks.ahb1(),
+                        )
+                        .map_err(|_| Error::InvalidClock)?;
+
+                    let mut spi = Spi { spi, pins };
+                    spi.init(mode, frequency, clk);
+                    Ok(spi)
+                }
+
+                /// Write `buffer` through the TX FIFO's DMA request
+                ///
+                /// The received data is discarded. `buffer` holds at most
+                /// `MAX_BURST` bytes, `Error::BufferSize` otherwise.
+                pub fn write_dma<BUF>(
+                    mut self,
+                    channel: &mut Channel,
+                    mut desc: Pin<&'static mut Descriptor>,
+                    buffer: Pin<BUF>,
+                ) -> Result<DmaWrite<$SPIX, PINS, BUF>, DmaError<$SPIX, PINS, BUF>>
+                where
+                    PINS: Pins<$SPIX>,
+                    BUF: Deref + 'static,
+                    BUF::Target: Buffer<u8>,
+                {
+                    if buffer.size() == 0 || buffer.size() > MAX_BURST {
+                        return Err(DmaError {
+                            error: Error::BufferSize,
+                            spi: self,
+                            desc,
+                            buffer,
+                        });
+                    }
+
+                    let mut config = Config(0);
+                    config.set_src_drq_port(DrqPort::SdRam);
+                    config.set_dst_drq_port(DrqPort::$DrqPort);
+                    config.set_src_address_mode(AddressMode::Linear);
+                    config.set_dst_address_mode(AddressMode::Io);
+                    config.set_src_burst_length(BurstLength::Bytes4);
+                    config.set_dst_burst_length(BurstLength::Bytes4);
+                    config.set_src_data_width(DataWidth::Bits8);
+                    config.set_dst_data_width(DataWidth::Bits8);
+
+                    let mut param = Param(0);
+                    param.set_wait(Param::NORMAL_WAIT);
+
+                    desc.config = config;
+                    desc.src_addr = buffer.as_ptr() as u32;
+                    desc.dst_addr = ($spix::PADDR + TXD_OFFSET) as u32;
+                    desc.length = buffer.size() as u32;
+                    desc.param = param;
+                    desc.next_addr = Descriptor::LAST_ADDR;
+
+                    if PINS::HW_CS {
+                        self.assert_cs();
+                    }
+                    self.spi.fcr.modify(
+                        FifoControl::TxTriggerLevel::Field::new(FIFO_DEPTH as u32 / 2).unwrap()
+                            + FifoControl::TxDrqEnable::Set,
+                    );
+                    self.discard_rx(true);
+                    self.set_burst_len(buffer.size(), buffer.size());
+
+                    channel.set_nonsecure();
+                    channel.set_desc_addr(&desc);
+                    atomic::fence(Ordering::SeqCst);
+                    channel.enable();
+
+                    self.start_burst();
+
+                    // The time a blocking call gets per FIFO
+                    let fifos = (buffer.size() / FIFO_DEPTH) as u32 + 1;
+                    Ok(DmaWrite {
+                        spi: self,
+                        desc,
+                        buffer,
+                        timeout: TIMEOUT.saturating_mul(fifos),
+                    })
+                }
+            }
+
+            impl<PINS, BUF> DmaWrite<$SPIX, PINS, BUF>
+            where
+                PINS: Pins<$SPIX>,
+            {
+                pub fn is_active(&self, channel: &mut Channel) -> bool {
+                    channel.is_active()
+                }
+
+                /// Wait for the write to finish
+                ///
+                /// The channel is stopped if it doesn't finish in time.
+                pub fn wait(
+                    self,
+                    channel: &mut Channel,
+                ) -> Result<
+                    (Spi<$SPIX, PINS>, Pin<&'static mut Descriptor>, Pin<BUF>),
+                    DmaError<$SPIX, PINS, BUF>,
+                > {
+                    let DmaWrite {
+                        mut spi,
+                        desc,
+                        buffer,
+                        timeout,
+                    } = self;
+
+                    let mut result = Err(Error::Timeout);
+                    for _ in 0..timeout {
+                        if !channel.is_active() {
+                            result = Ok(());
+                            break;
+                        }
+                    }
+                    if result.is_err() {
+                        channel.disable();
+                    }
+                    atomic::fence(Ordering::SeqCst);
+
+                    let result = result.and_then(|_| spi.wait_burst());
+                    spi.spi.fcr.modify(
+                        FifoControl::TxDrqEnable::Clear
+                            + FifoControl::TxReset::Set
+                            + FifoControl::RxReset::Set,
+                    );
+                    if PINS::HW_CS {
+                        spi.deassert_cs();
+                    }
+                    match result {
+                        Ok(()) => Ok((spi, desc, buffer)),
+                        Err(error) => Err(DmaError {
+                            error,
+                            spi,
+                            desc,
+                            buffer,
+                        }),
+                    }
+                }
+            }
+        )+
+    }
+}
+
+hal! {
+    SPI0: (spi0, spi0, Spi0, Spi0),
+    SPI1: (spi1, spi1, Spi1, Spi1),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+enum Divider {
+    /// SCK = clk / 2^(CDR1 + 1)
+    Cdr1(u32),
+    /// SCK = clk / (2 * (CDR2 + 1))
+    Cdr2(u32),
+}
+
+/// SCK divider for the fastest rate not above `sck`
+fn clock_divider(clk: Hertz, sck: Hertz) -> Divider {
+    let sck = sck.0.max(1);
+    let cdr2 = ((clk.0 + 2 * sck - 1) / (2 * sck)).max(1) - 1;
+    if cdr2 <= 0xFF {
+        Divider::Cdr2(cdr2)
+    } else {
+        Divider::Cdr1((0..0xF).find(|n| clk.0 >> (n + 1) <= sck).unwrap_or(0xF))
+    }
+}
+
+impl<SPI, PINS> Spi<SPI, PINS>
+where
+    SPI: DerefMut<Target = RegisterBlock>,
+    PINS: Pins<SPI>,
+{
+    fn init(&mut self, mode: Mode, frequency: Hertz, clk: Hertz) {
+        self.spi.gcr.modify(GlobalControl::SoftReset::Set);
+        let mut timeout = TIMEOUT;
+        while self.spi.gcr.is_set(GlobalControl::SoftReset::Read) && timeout != 0 {
+            timeout -= 1;
+        }
+
+        self.spi.gcr.modify(
+            GlobalControl::Enable::Set
+                + GlobalControl::Mode::Master
+                + GlobalControl::TransmitPause::Clear,
+        );
+
+        let cpol = match mode.polarity {
+            Polarity::IdleLow => TransferControl::Cpol::Clear,
+            Polarity::IdleHigh => TransferControl::Cpol::Set,
+        };
+        let cpha = match mode.phase {
+            Phase::CaptureOnFirstTransition => TransferControl::Cpha::Clear,
+            Phase::CaptureOnSecondTransition => TransferControl::Cpha::Set,
+        };
+        self.spi.tcr.modify(
+            cpol + cpha
+                + TransferControl::ChipSelectPolarity::ActiveLow
+                + TransferControl::ChipSelect::Cs0
+                + TransferControl::ChipSelectOwner::Software
+                + TransferControl::ChipSelectLevel::Set
+                + TransferControl::DiscardHashBurst::Clear
+                + TransferControl::FirstBit::Msb,
+        );
+
+        match clock_divider(clk, frequency) {
+            Divider::Cdr1(n) => self.spi.ccr.modify(
+                ClockControl::DivideRateSelect::Cdr1 + ClockControl::Cdr1::Field::new(n).unwrap(),
+            ),
+            Divider::Cdr2(n) => self.spi.ccr.modify(
+                ClockControl::DivideRateSelect::Cdr2 + ClockControl::Cdr2::Field::new(n).unwrap(),
+            ),
+        }
+
+        self.spi
+            .fcr
+            .modify(FifoControl::RxReset::Set + FifoControl::TxReset::Set);
+        // Write 1 to clear
+        self.spi.isr.write(!0);
+    }
+
+    pub fn free(self) -> (SPI, PINS) {
+        (self.spi, self.pins)
+    }
+
+    /// Drive the controller's chip-select low
+    pub fn assert_cs(&mut self) {
+        self.spi.tcr.modify(TransferControl::ChipSelectLevel::Clear);
+    }
+
+    pub fn deassert_cs(&mut self) {
+        self.spi.tcr.modify(TransferControl::ChipSelectLevel::Set);
+    }
+
+    /// Run `f` with a GPIO chip-select held low
+    pub fn with_cs<CS, R, F>(&mut self, cs: &mut CS, f: F) -> R
+    where
+        CS: OutputPin,
+        F: FnOnce(&mut Self) -> R,
+    {
+        cs.set_low().ok();
+        let r = f(self);
+        cs.set_high().ok();
+        r
+    }
+
+    /// Drop the received bytes instead of filling the RX FIFO, for
+    /// transmit-only bursts
+    fn discard_rx(&mut self, discard: bool) {
+        if discard {
+            self.spi.tcr.modify(TransferControl::DiscardHashBurst::Set);
+        } else {
+            self.spi
+                .tcr
+                .modify(TransferControl::DiscardHashBurst::Clear);
+        }
+    }
+
+    fn set_burst_len(&mut self, total: usize, transmit: usize) {
+        let total = total as u32;
+        let transmit = transmit as u32;
+        self.spi
+            .mbc
+            .modify(BurstCounter::Count::Field::new(total).unwrap());
+        self.spi
+            .mtc
+            .modify(TransmitCounter::Count::Field::new(transmit).unwrap());
+        self.spi
+            .bcc
+            .modify(BurstControl::SingleModeTransmitCount::Field::new(transmit).unwrap());
+    }
+
+    fn start_burst(&mut self) {
+        // Write 1 to clear
+        self.spi.isr.write(1 << 12);
+        self.spi.tcr.modify(TransferControl::Exchange::Set);
+    }
+
+    fn wait_burst(&mut self) -> Result<(), Error> {
+        for _ in 0..TIMEOUT {
+            if self.spi.isr.is_set(IntStatus::TransferComplete::Read) {
+                self.spi.isr.write(1 << 12);
+                return Ok(());
+            }
+        }
+        Err(Error::Timeout)
+    }
+
+    fn push(&mut self, word: u8) {
+        self.spi.txd.modify(TxData::Data::Field::new(word).unwrap());
+    }
+
+    fn rx_count(&self) -> u32 {
+        self.spi
+            .fsr
+            .get_field(FifoStatus::RxCount::Read)
+            .map_or(0, |f| f.val())
+    }
+
+    fn transfer_bursts(&mut self, words: &mut [u8]) -> Result<(), Error> {
+        self.discard_rx(false);
+        for chunk in words.chunks_mut(FIFO_DEPTH) {
+            for word in chunk.iter() {
+                self.push(*word);
+            }
+            self.set_burst_len(chunk.len(), chunk.len());
+            self.start_burst();
+            self.wait_burst()?;
+            for word in chunk.iter_mut() {
+                *word = self.spi.rxd.read();
+            }
+        }
+        Ok(())
+    }
+
+    fn write_bursts(&mut self, words: &[u8]) -> Result<(), Error> {
+        self.discard_rx(true);
+        for chunk in words.chunks(FIFO_DEPTH) {
+            for word in chunk {
+                self.push(*word);
+            }
+            self.set_burst_len(chunk.len(), chunk.len());
+            self.start_burst();
+            self.wait_burst()?;
+        }
+        Ok(())
+    }
+}
+
+impl<SPI, PINS> FullDuplex<u8> for Spi<SPI, PINS>
+where
+    SPI: DerefMut<Target = RegisterBlock>,
+    PINS: Pins<SPI>,
+{
+    type Error = Error;
+
+    fn read(&mut self) -> nb::Result<u8, Error> {
+        if self.spi.isr.is_set(IntStatus::RxOverflow::Read) {
+            // Write 1 to clear
+            self.spi.isr.write(1 << 8);
+            Err(nb::Error::Other(Error::Overrun))
+        } else if self.rx_count() != 0 {
+            Ok(self.spi.rxd.read())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    fn send(&mut self, word: u8) -> nb::Result<(), Error> {
+        if self.spi.tcr.is_set(TransferControl::Exchange::Read) {
+            Err(nb::Error::WouldBlock)
+        } else {
+            self.discard_rx(false);
+            self.push(word);
+            self.set_burst_len(1, 1);
+            self.start_burst();
+            Ok(())
+        }
+    }
+}
+
+impl<SPI, PINS> Transfer<u8> for Spi<SPI, PINS>
+where
+    SPI: DerefMut<Target = RegisterBlock>,
+    PINS: Pins<SPI>,
+{
+    type Error = Error;
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Error> {
+        if PINS::HW_CS {
+            self.assert_cs();
+        }
+        let result = self.transfer_bursts(words);
+        if PINS::HW_CS {
+            self.deassert_cs();
+        }
+        result.map(move |_| &*words)
+    }
+}
+
+impl<SPI, PINS> Write<u8> for Spi<SPI, PINS>
+where
+    SPI: DerefMut<Target = RegisterBlock>,
+    PINS: Pins<SPI>,
+{
+    type Error = Error;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Error> {
+        if PINS::HW_CS {
+            self.assert_cs();
+        }
+        let result = self.write_bursts(words);
+        if PINS::HW_CS {
+            self.deassert_cs();
+        }
+        result
+    }
+}
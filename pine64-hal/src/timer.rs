@@ -2,7 +2,6 @@
 
 use crate::ccu::{Ccu, Clocks};
 use crate::hal::timer::{Cancel, CountDown, Periodic};
-use crate::pac::ccu::{BusClockGating0, BusSoftReset0};
 use crate::pac::hstimer::{self, HSTIMER};
 use crate::pac::timer::{
     Control, IrqEnable, IrqStatus, RegisterBlock as TimerRegisterBlock, TIMER,
@@ -169,9 +168,7 @@ impl Cancel for Timer<TIM0> {
 
 impl Timer<HSTIMER> {
     pub fn hstimer(tim: HSTIMER, clocks: Clocks, ccu: &mut Ccu) -> Self {
-        ccu.bsr0.rstr().modify(BusSoftReset0::HsTimer::Clear);
-        ccu.bsr0.rstr().modify(BusSoftReset0::HsTimer::Set);
-        ccu.bcg0.enr().modify(BusClockGating0::HsTimer::Set);
+        ccu.enable_and_reset::<HSTIMER>();
 
         let mut timer = Timer {
             tim,
@@ -11,7 +11,7 @@ pub const PADDR: usize = 0x01C2_0000;
 register! {
     PllCpuXControl,
     u32,
-    RO,
+    RW,
     Fields [
         FactorM WIDTH(U2) OFFSET(U0),
         FactorK WIDTH(U2) OFFSET(U4),
@@ -22,7 +22,23 @@ register! {
             Divide4 = U2,
             Divide8 = U3
         ]
-        Lock WIDTH(U1) OFFSET(U28)
+        SdmEnable WIDTH(U1) OFFSET(U24),
+        Lock WIDTH(U1) OFFSET(U28),
+        Enable WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    PllAudioControl,
+    u32,
+    RW,
+    Fields [
+        PreDivM WIDTH(U5) OFFSET(U0),
+        FactorN WIDTH(U7) OFFSET(U8),
+        PostDivP WIDTH(U4) OFFSET(U16),
+        SdmEnable WIDTH(U1) OFFSET(U24),
+        Lock WIDTH(U1) OFFSET(U28),
+        Enable WIDTH(U1) OFFSET(U31)
     ]
 }
 
@@ -33,11 +49,46 @@ register! {
     Fields [
         PreDivM WIDTH(U4) OFFSET(U0),
         FactorN WIDTH(U7) OFFSET(U8),
+        SdmEnable WIDTH(U1) OFFSET(U20),
+        Mode WIDTH(U1) OFFSET(U24) [
+            Fractional = U0,
+            Integer = U1
+        ]
+        FracClockOut WIDTH(U1) OFFSET(U25),
+        Lock WIDTH(U1) OFFSET(U28),
+        Enable WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    PllVeControl,
+    u32,
+    RW,
+    Fields [
+        PreDivM WIDTH(U4) OFFSET(U0),
+        FactorN WIDTH(U7) OFFSET(U8),
+        SdmEnable WIDTH(U1) OFFSET(U20),
         Mode WIDTH(U1) OFFSET(U24) [
             Fractional = U0,
             Integer = U1
         ]
-        Lock WIDTH(U1) OFFSET(U28)
+        FracClockOut WIDTH(U1) OFFSET(U25),
+        Lock WIDTH(U1) OFFSET(U28),
+        Enable WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    PllDdr0Control,
+    u32,
+    RW,
+    Fields [
+        FactorM WIDTH(U2) OFFSET(U0),
+        FactorK WIDTH(U2) OFFSET(U4),
+        FactorN WIDTH(U5) OFFSET(U8),
+        ConfigUpdate WIDTH(U1) OFFSET(U20),
+        SdmEnable WIDTH(U1) OFFSET(U24),
+        Lock WIDTH(U1) OFFSET(U28),
         Enable WIDTH(U1) OFFSET(U31)
     ]
 }
@@ -45,22 +96,103 @@ register! {
 register! {
     PllPeriph0Control,
     u32,
-    RO,
+    RW,
     Fields [
         FactorK WIDTH(U2) OFFSET(U4),
         FactorN WIDTH(U5) OFFSET(U8),
+        Out24MPostDiv WIDTH(U2) OFFSET(U16),
+        Out24MEnable WIDTH(U1) OFFSET(U18),
+        ClockOutEnable WIDTH(U1) OFFSET(U24),
+        BypassEnable WIDTH(U1) OFFSET(U25),
         Lock WIDTH(U1) OFFSET(U28),
+        Enable WIDTH(U1) OFFSET(U31)
     ]
 }
 
 register! {
     PllPeriph1Control,
     u32,
-    RO,
+    RW,
     Fields [
         FactorK WIDTH(U2) OFFSET(U4),
         FactorN WIDTH(U5) OFFSET(U8),
+        Out24MPostDiv WIDTH(U2) OFFSET(U16),
+        Out24MEnable WIDTH(U1) OFFSET(U18),
+        ClockOutEnable WIDTH(U1) OFFSET(U24),
+        BypassEnable WIDTH(U1) OFFSET(U25),
         Lock WIDTH(U1) OFFSET(U28),
+        Enable WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    PllVideo1Control,
+    u32,
+    RW,
+    Fields [
+        PreDivM WIDTH(U4) OFFSET(U0),
+        FactorN WIDTH(U7) OFFSET(U8),
+        SdmEnable WIDTH(U1) OFFSET(U20),
+        Mode WIDTH(U1) OFFSET(U24) [
+            Fractional = U0,
+            Integer = U1
+        ]
+        FracClockOut WIDTH(U1) OFFSET(U25),
+        Lock WIDTH(U1) OFFSET(U28),
+        Enable WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    PllGpuControl,
+    u32,
+    RW,
+    Fields [
+        PreDivM WIDTH(U4) OFFSET(U0),
+        FactorN WIDTH(U7) OFFSET(U8),
+        SdmEnable WIDTH(U1) OFFSET(U20),
+        Mode WIDTH(U1) OFFSET(U24) [
+            Fractional = U0,
+            Integer = U1
+        ]
+        FracClockOut WIDTH(U1) OFFSET(U25),
+        Lock WIDTH(U1) OFFSET(U28),
+        Enable WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    /// Sourced from PLL_VIDEO0(1X)
+    PllMipiControl,
+    u32,
+    RW,
+    Fields [
+        PreDivM WIDTH(U4) OFFSET(U0),
+        FactorK WIDTH(U2) OFFSET(U4),
+        FactorN WIDTH(U4) OFFSET(U8),
+        SDiv2 WIDTH(U1) OFFSET(U17),
+        Ldo2Enable WIDTH(U1) OFFSET(U22),
+        Ldo1Enable WIDTH(U1) OFFSET(U23),
+        Lock WIDTH(U1) OFFSET(U28),
+        Enable WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    PllHsicControl,
+    u32,
+    RW,
+    Fields [
+        PreDivM WIDTH(U4) OFFSET(U0),
+        FactorN WIDTH(U7) OFFSET(U8),
+        SdmEnable WIDTH(U1) OFFSET(U20),
+        Mode WIDTH(U1) OFFSET(U24) [
+            Fractional = U0,
+            Integer = U1
+        ]
+        FracClockOut WIDTH(U1) OFFSET(U25),
+        Lock WIDTH(U1) OFFSET(U28),
+        Enable WIDTH(U1) OFFSET(U31)
     ]
 }
 
@@ -82,10 +214,43 @@ register! {
     ]
 }
 
+register! {
+    PllDdr1Control,
+    u32,
+    RW,
+    Fields [
+        PreDivM WIDTH(U2) OFFSET(U0),
+        FactorN WIDTH(U7) OFFSET(U8),
+        SdmEnable WIDTH(U1) OFFSET(U24),
+        Lock WIDTH(U1) OFFSET(U28),
+        ConfigUpdate WIDTH(U1) OFFSET(U30),
+        Enable WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    CpuXAxiConfig,
+    u32,
+    RW,
+    Fields [
+        AxiClockDivRatio WIDTH(U2) OFFSET(U0),
+        ApbClockDivRatio WIDTH(U2) OFFSET(U8) [
+            Divide1 = U0,
+            Divide2 = U1,
+            Divide4 = U2
+        ]
+        ClockSrcSel WIDTH(U2) OFFSET(U16) [
+            LOsc = U0,
+            Osc24M = U1,
+            PllCpuX = U2
+        ]
+    ]
+}
+
 register! {
     Ahb1Apb1Config,
     u32,
-    RO,
+    RW,
     Fields [
         Ahb1ClockDivRatio WIDTH(U2) OFFSET(U4) [
             Divide1 = U0,
@@ -111,7 +276,7 @@ register! {
 register! {
     Apb2Config,
     u32,
-    RO,
+    RW,
     Fields [
         RatioM WIDTH(U5) OFFSET(U0),
         RatioN WIDTH(U2) OFFSET(U16) [
@@ -131,7 +296,7 @@ register! {
 register! {
     Ahb2Config,
     u32,
-    RO,
+    RW,
     Fields [
         ClockConfig WIDTH(U2) OFFSET(U0) [
             Ahb1Clock = U0,
@@ -146,8 +311,24 @@ register! {
     u32,
     RW,
     Fields [
+        MipiDsi WIDTH(U1) OFFSET(U1),
+        Ce WIDTH(U1) OFFSET(U5),
         Dma WIDTH(U1) OFFSET(U6),
+        Mmc0 WIDTH(U1) OFFSET(U8),
+        Mmc1 WIDTH(U1) OFFSET(U9),
+        Mmc2 WIDTH(U1) OFFSET(U10),
+        Nand WIDTH(U1) OFFSET(U13),
+        Sdram WIDTH(U1) OFFSET(U14),
+        Emac WIDTH(U1) OFFSET(U17),
+        Ts WIDTH(U1) OFFSET(U18),
         HsTimer WIDTH(U1) OFFSET(U19),
+        Spi0 WIDTH(U1) OFFSET(U20),
+        Spi1 WIDTH(U1) OFFSET(U21),
+        UsbOtg WIDTH(U1) OFFSET(U23),
+        UsbEhci0 WIDTH(U1) OFFSET(U24),
+        UsbEhci1 WIDTH(U1) OFFSET(U25),
+        UsbOhci0 WIDTH(U1) OFFSET(U28),
+        UsbOhci1 WIDTH(U1) OFFSET(U29),
     ]
 }
 
@@ -157,10 +338,16 @@ register! {
     u32,
     RW,
     Fields [
+        Ve WIDTH(U1) OFFSET(U0),
         Tcon0 WIDTH(U1) OFFSET(U3),
         Tcon1 WIDTH(U1) OFFSET(U4),
+        Deinterlace WIDTH(U1) OFFSET(U5),
+        Csi WIDTH(U1) OFFSET(U8),
         Hdmi WIDTH(U1) OFFSET(U11),
         De WIDTH(U1) OFFSET(U12),
+        Gpu WIDTH(U1) OFFSET(U20),
+        MsgBox WIDTH(U1) OFFSET(U21),
+        SpinLock WIDTH(U1) OFFSET(U22),
     ]
 }
 
@@ -170,7 +357,13 @@ register! {
     u32,
     RW,
     Fields [
+        Codec WIDTH(U1) OFFSET(U0),
+        Spdif WIDTH(U1) OFFSET(U1),
         Pio WIDTH(U1) OFFSET(U5),
+        Ths WIDTH(U1) OFFSET(U8),
+        I2sPcm0 WIDTH(U1) OFFSET(U12),
+        I2sPcm1 WIDTH(U1) OFFSET(U13),
+        I2sPcm2 WIDTH(U1) OFFSET(U14),
     ]
 }
 
@@ -180,6 +373,10 @@ register! {
     u32,
     RW,
     Fields [
+        Twi0 WIDTH(U1) OFFSET(U0),
+        Twi1 WIDTH(U1) OFFSET(U1),
+        Twi2 WIDTH(U1) OFFSET(U2),
+        Scr WIDTH(U1) OFFSET(U5),
         Uart0 WIDTH(U1) OFFSET(U16),
         Uart1 WIDTH(U1) OFFSET(U17),
         Uart2 WIDTH(U1) OFFSET(U18),
@@ -188,6 +385,112 @@ register! {
     ]
 }
 
+register! {
+    ThsClockConfig,
+    u32,
+    RW,
+    Fields [
+        DivRatio WIDTH(U2) OFFSET(U0) [
+            Divide1 = U0,
+            Divide2 = U1,
+            Divide4 = U2,
+            Divide6 = U3
+        ]
+        ClockSrcSel WIDTH(U2) OFFSET(U24) [
+            Osc24M = U0
+        ]
+        SClockGating WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    /// Shared layout of the NAND, SDMMC0-2, CE and SPI0-1 clocks
+    ///
+    /// SCLK = source / M / N
+    ///
+    /// NAND, SDMMC and CE use the 2X outputs of the peripheral PLLs,
+    /// SPI uses the 1X outputs.
+    ModuleClockConfig,
+    u32,
+    RW,
+    Fields [
+        DivRatioM WIDTH(U4) OFFSET(U0),
+        DivRatioN WIDTH(U2) OFFSET(U16) [
+            Divide1 = U0,
+            Divide2 = U1,
+            Divide4 = U2,
+            Divide8 = U3
+        ]
+        ClockSrcSel WIDTH(U2) OFFSET(U24) [
+            Osc24M = U0,
+            PllPeriph0 = U1,
+            PllPeriph1 = U2
+        ]
+        SClockGating WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    TsClockConfig,
+    u32,
+    RW,
+    Fields [
+        DivRatioM WIDTH(U4) OFFSET(U0),
+        DivRatioN WIDTH(U2) OFFSET(U16) [
+            Divide1 = U0,
+            Divide2 = U1,
+            Divide4 = U2,
+            Divide8 = U3
+        ]
+        ClockSrcSel WIDTH(U4) OFFSET(U24) [
+            Osc24M = U0,
+            PllPeriph01x = U1
+        ]
+        SClockGating WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    I2sPcmClockConfig,
+    u32,
+    RW,
+    Fields [
+        ClockSrcSel WIDTH(U2) OFFSET(U16) [
+            PllAudio8x = U0,
+            PllAudio4x = U1,
+            PllAudio2x = U2,
+            PllAudio1x = U3
+        ]
+        SClockGating WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    /// Sourced from PLL_AUDIO
+    SpdifClockConfig,
+    u32,
+    RW,
+    Fields [
+        DivRatioM WIDTH(U4) OFFSET(U0),
+        SClockGating WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    DramConfig,
+    u32,
+    RW,
+    Fields [
+        DivRatioM WIDTH(U4) OFFSET(U0),
+        SdrClockUpdate WIDTH(U1) OFFSET(U16),
+        ClockSrcSel WIDTH(U2) OFFSET(U20) [
+            PllDdr0 = U0,
+            PllDdr1 = U1
+        ]
+        ModuleReset WIDTH(U1) OFFSET(U31)
+    ]
+}
+
 register! {
     DeClockConfig,
     u32,
@@ -202,12 +505,105 @@ register! {
     ]
 }
 
+register! {
+    Tcon0ClockConfig,
+    u32,
+    RW,
+    Fields [
+        ClockSel WIDTH(U3) OFFSET(U24) [
+            PllMipi = U0,
+            PllVideo0x2 = U2
+        ]
+        SClockGating WIDTH(U1) OFFSET(U31)
+    ]
+}
+
 register! {
     Tcon1ClockConfig,
     u32,
     RW,
     Fields [
         DivRatioM WIDTH(U4) OFFSET(U0),
+        ClockSel WIDTH(U2) OFFSET(U24) [
+            PllVideo0x1 = U0,
+            PllVideo1x1 = U2
+        ]
+        SClockGating WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    DeinterlaceClockConfig,
+    u32,
+    RW,
+    Fields [
+        DivRatioM WIDTH(U4) OFFSET(U0),
+        ClockSel WIDTH(U3) OFFSET(U24) [
+            PllPeriph0 = U0,
+            PllPeriph1 = U1
+        ]
+        SClockGating WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    CsiMiscClockConfig,
+    u32,
+    RW,
+    Fields [
+        SClockGating WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    CsiClockConfig,
+    u32,
+    RW,
+    Fields [
+        MClockDivRatioM WIDTH(U4) OFFSET(U0),
+        MClockSel WIDTH(U3) OFFSET(U8) [
+            Osc24M = U0,
+            PllVideo1 = U1,
+            PllPeriph1 = U5
+        ]
+        MClockGating WIDTH(U1) OFFSET(U15),
+        SClockDivRatioM WIDTH(U4) OFFSET(U16),
+        SClockSel WIDTH(U3) OFFSET(U24) [
+            PllPeriph0 = U0,
+            PllPeriph1 = U1
+        ]
+        SClockGating WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    /// Sourced from PLL_VE
+    VeClockConfig,
+    u32,
+    RW,
+    Fields [
+        DivRatioN WIDTH(U3) OFFSET(U16),
+        SClockGating WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    /// Sourced from PLL_AUDIO
+    AcDigitalClockConfig,
+    u32,
+    RW,
+    Fields [
+        SClock1xGating WIDTH(U1) OFFSET(U30),
+        SClockGating WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    /// Sourced from OSC24M
+    AvsClockConfig,
+    u32,
+    RW,
+    Fields [
         SClockGating WIDTH(U1) OFFSET(U31)
     ]
 }
@@ -237,14 +633,71 @@ register! {
     ]
 }
 
+register! {
+    MBusClockConfig,
+    u32,
+    RW,
+    Fields [
+        DivRatioM WIDTH(U3) OFFSET(U0),
+        ClockSrcSel WIDTH(U2) OFFSET(U24) [
+            Osc24M = U0,
+            PllPeriph02x = U1,
+            PllDdr0 = U2,
+            PllDdr1 = U3
+        ]
+        SClockGating WIDTH(U1) OFFSET(U31)
+    ]
+}
+
+register! {
+    MipiDsiClockConfig,
+    u32,
+    RW,
+    Fields [
+        DivRatioM WIDTH(U4) OFFSET(U0),
+        ClockSel WIDTH(U2) OFFSET(U8) [
+            PllVideo0 = U0,
+            PllPeriph0 = U2
+        ]
+        SClockGating WIDTH(U1) OFFSET(U15)
+    ]
+}
+
+register! {
+    /// Sourced from PLL_GPU
+    GpuClockConfig,
+    u32,
+    RW,
+    Fields [
+        DivRatioM WIDTH(U3) OFFSET(U0),
+        SClockGating WIDTH(U1) OFFSET(U31)
+    ]
+}
+
 register! {
     /// AHB1 reset 0
     BusSoftReset0,
     u32,
     RW,
     Fields [
+        MipiDsi WIDTH(U1) OFFSET(U1),
+        Ce WIDTH(U1) OFFSET(U5),
         Dma WIDTH(U1) OFFSET(U6),
+        Mmc0 WIDTH(U1) OFFSET(U8),
+        Mmc1 WIDTH(U1) OFFSET(U9),
+        Mmc2 WIDTH(U1) OFFSET(U10),
+        Nand WIDTH(U1) OFFSET(U13),
+        Sdram WIDTH(U1) OFFSET(U14),
+        Emac WIDTH(U1) OFFSET(U17),
+        Ts WIDTH(U1) OFFSET(U18),
         HsTimer WIDTH(U1) OFFSET(U19),
+        Spi0 WIDTH(U1) OFFSET(U20),
+        Spi1 WIDTH(U1) OFFSET(U21),
+        UsbOtg WIDTH(U1) OFFSET(U23),
+        UsbEhci0 WIDTH(U1) OFFSET(U24),
+        UsbEhci1 WIDTH(U1) OFFSET(U25),
+        UsbOhci0 WIDTH(U1) OFFSET(U28),
+        UsbOhci1 WIDTH(U1) OFFSET(U29),
     ]
 }
 
@@ -254,11 +707,18 @@ register! {
     u32,
     RW,
     Fields [
+        Ve WIDTH(U1) OFFSET(U0),
         Tcon0 WIDTH(U1) OFFSET(U3),
         Tcon1 WIDTH(U1) OFFSET(U4),
+        Deinterlace WIDTH(U1) OFFSET(U5),
+        Csi WIDTH(U1) OFFSET(U8),
         Hdmi0 WIDTH(U1) OFFSET(U10),
         Hdmi1 WIDTH(U1) OFFSET(U11),
         De WIDTH(U1) OFFSET(U12),
+        Gpu WIDTH(U1) OFFSET(U20),
+        MsgBox WIDTH(U1) OFFSET(U21),
+        SpinLock WIDTH(U1) OFFSET(U22),
+        DbgSys WIDTH(U1) OFFSET(U31),
     ]
 }
 
@@ -268,7 +728,7 @@ register! {
     u32,
     RW,
     Fields [
-        Bits WIDTH(U32) OFFSET(U0),
+        Lvds WIDTH(U1) OFFSET(U0),
     ]
 }
 
@@ -278,7 +738,12 @@ register! {
     u32,
     RW,
     Fields [
-        Bits WIDTH(U32) OFFSET(U0),
+        Codec WIDTH(U1) OFFSET(U0),
+        Spdif WIDTH(U1) OFFSET(U1),
+        Ths WIDTH(U1) OFFSET(U8),
+        I2sPcm0 WIDTH(U1) OFFSET(U12),
+        I2sPcm1 WIDTH(U1) OFFSET(U13),
+        I2sPcm2 WIDTH(U1) OFFSET(U14),
     ]
 }
 
@@ -288,6 +753,9 @@ register! {
     u32,
     RW,
     Fields [
+        Twi0 WIDTH(U1) OFFSET(U0),
+        Twi1 WIDTH(U1) OFFSET(U1),
+        Twi2 WIDTH(U1) OFFSET(U2),
         Scr WIDTH(U1) OFFSET(U5),
         Uart0 WIDTH(U1) OFFSET(U16),
         Uart1 WIDTH(U1) OFFSET(U17),
@@ -301,37 +769,86 @@ const_assert_eq!(core::mem::size_of::<RegisterBlock>(), 0x02DC);
 
 #[repr(C)]
 pub struct RegisterBlock {
-    pub pll_cpu_ctrl: PllCpuXControl::Register,     // 0x0000
-    __reserved_0: [u32; 3],                         // 0x0004
-    pub pll_video0: PllVideo0Control::Register,     // 0x0010
-    __reserved_1: [u32; 5],                         // 0x0014
-    pub pll_periph0: PllPeriph0Control::Register,   // 0x0028
-    pub pll_periph1: PllPeriph1Control::Register,   // 0x002C
-    __reserved_2: [u32; 6],                         // 0x0030
-    pub pll_de: PllDeControl::Register,             // 0x0048
-    __reserved_3: [u32; 2],                         // 0x004C
-    pub ahb1_apb1_cfg: Ahb1Apb1Config::Register,    // 0x0054
-    pub apb2_cfg: Apb2Config::Register,             // 0x0058
-    pub ahb2_cfg: Ahb2Config::Register,             // 0x005C
-    pub bcg0: BusClockGating0::Register,            // 0x0060
-    pub bcg1: BusClockGating1::Register,            // 0x0064
-    pub bcg2: BusClockGating2::Register,            // 0x0068
-    pub bcg3: BusClockGating3::Register,            // 0x006C
-    __reserved_5: [u32; 37],                        // 0x0070
-    pub de_clk_cfg: DeClockConfig::Register,        // 0x0104
-    __reserved_6: [u32; 5],                         // 0x0108
-    pub tcon1_clk_cfg: Tcon1ClockConfig::Register,  // 0x011C
-    __reserved_7: [u32; 12],                        // 0x0120
-    pub hdmi_clk_cfg: HdmiClockConfig::Register,    // 0x0150
+    pub pll_cpu_ctrl: PllCpuXControl::Register,    // 0x0000
+    __reserved_0: u32,                             // 0x0004
+    pub pll_audio: PllAudioControl::Register,      // 0x0008
+    __reserved_1: u32,                             // 0x000C
+    pub pll_video0: PllVideo0Control::Register,    // 0x0010
+    __reserved_2: u32,                             // 0x0014
+    pub pll_ve: PllVeControl::Register,            // 0x0018
+    __reserved_3: u32,                             // 0x001C
+    pub pll_ddr0: PllDdr0Control::Register,        // 0x0020
+    __reserved_4: u32,                             // 0x0024
+    pub pll_periph0: PllPeriph0Control::Register,  // 0x0028
+    pub pll_periph1: PllPeriph1Control::Register,  // 0x002C
+    pub pll_video1: PllVideo1Control::Register,    // 0x0030
+    __reserved_5: u32,                             // 0x0034
+    pub pll_gpu: PllGpuControl::Register,          // 0x0038
+    __reserved_6: u32,                             // 0x003C
+    pub pll_mipi: PllMipiControl::Register,        // 0x0040
+    pub pll_hsic: PllHsicControl::Register,        // 0x0044
+    pub pll_de: PllDeControl::Register,            // 0x0048
+    pub pll_ddr1: PllDdr1Control::Register,        // 0x004C
+    pub cpu_axi_cfg: CpuXAxiConfig::Register,      // 0x0050
+    pub ahb1_apb1_cfg: Ahb1Apb1Config::Register,   // 0x0054
+    pub apb2_cfg: Apb2Config::Register,            // 0x0058
+    pub ahb2_cfg: Ahb2Config::Register,            // 0x005C
+    pub bcg0: BusClockGating0::Register,           // 0x0060
+    pub bcg1: BusClockGating1::Register,           // 0x0064
+    pub bcg2: BusClockGating2::Register,           // 0x0068
+    pub bcg3: BusClockGating3::Register,           // 0x006C
+    __reserved_7: u32,                             // 0x0070
+    pub ths_clk_cfg: ThsClockConfig::Register,     // 0x0074
+    __reserved_8: [u32; 2],                        // 0x0078
+    pub nand_clk_cfg: ModuleClockConfig::Register, // 0x0080
+    __reserved_9: u32,                             // 0x0084
+    pub mmc0_clk_cfg: ModuleClockConfig::Register, // 0x0088
+    pub mmc1_clk_cfg: ModuleClockConfig::Register, // 0x008C
+    pub mmc2_clk_cfg: ModuleClockConfig::Register, // 0x0090
+    __reserved_10: u32,                            // 0x0094
+    pub ts_clk_cfg: TsClockConfig::Register,       // 0x0098
+    pub ce_clk_cfg: ModuleClockConfig::Register,   // 0x009C
+    pub spi0_clk_cfg: ModuleClockConfig::Register, // 0x00A0
+    pub spi1_clk_cfg: ModuleClockConfig::Register, // 0x00A4
+    __reserved_11: [u32; 2],                       // 0x00A8
+    pub i2s_pcm0_clk_cfg: I2sPcmClockConfig::Register, // 0x00B0
+    pub i2s_pcm1_clk_cfg: I2sPcmClockConfig::Register, // 0x00B4
+    pub i2s_pcm2_clk_cfg: I2sPcmClockConfig::Register, // 0x00B8
+    __reserved_12: u32,                            // 0x00BC
+    pub spdif_clk_cfg: SpdifClockConfig::Register, // 0x00C0
+    __reserved_13: [u32; 12],                      // 0x00C4
+    pub dram_cfg: DramConfig::Register,            // 0x00F4
+    __reserved_14: [u32; 3],                       // 0x00F8
+    pub de_clk_cfg: DeClockConfig::Register,       // 0x0104
+    __reserved_15: [u32; 4],                       // 0x0108
+    pub tcon0_clk_cfg: Tcon0ClockConfig::Register, // 0x0118
+    pub tcon1_clk_cfg: Tcon1ClockConfig::Register, // 0x011C
+    __reserved_16: u32,                            // 0x0120
+    pub deinterlace_clk_cfg: DeinterlaceClockConfig::Register, // 0x0124
+    __reserved_17: [u32; 2],                       // 0x0128
+    pub csi_misc_clk_cfg: CsiMiscClockConfig::Register, // 0x0130
+    pub csi_clk_cfg: CsiClockConfig::Register,     // 0x0134
+    __reserved_18: u32,                            // 0x0138
+    pub ve_clk_cfg: VeClockConfig::Register,       // 0x013C
+    pub ac_dig_clk_cfg: AcDigitalClockConfig::Register, // 0x0140
+    pub avs_clk_cfg: AvsClockConfig::Register,     // 0x0144
+    __reserved_19: [u32; 2],                       // 0x0148
+    pub hdmi_clk_cfg: HdmiClockConfig::Register,   // 0x0150
     pub hdmi_slow_clk_cfg: HdmiSlowClock::Register, // 0x0154
-    __reserved_8: [u32; 90],                        // 0x0158
-    pub bsr0: BusSoftReset0::Register,              // 0x02C0
-    pub bsr1: BusSoftReset1::Register,              // 0x02C4
-    pub bsr2: BusSoftReset2::Register,              // 0x02C8
-    __reserved_9: u32,                              // 0x02CC
-    pub bsr3: BusSoftReset3::Register,              // 0x02D0
-    __reserved_10: u32,                             // 0x02D4
-    pub bsr4: BusSoftReset4::Register,              // 0x02D8
+    __reserved_20: u32,                            // 0x0158
+    pub mbus_clk_cfg: MBusClockConfig::Register,   // 0x015C
+    __reserved_21: [u32; 2],                       // 0x0160
+    pub mipi_dsi_clk_cfg: MipiDsiClockConfig::Register, // 0x0168
+    __reserved_22: [u32; 13],                      // 0x016C
+    pub gpu_clk_cfg: GpuClockConfig::Register,     // 0x01A0
+    __reserved_23: [u32; 71],                      // 0x01A4
+    pub bsr0: BusSoftReset0::Register,             // 0x02C0
+    pub bsr1: BusSoftReset1::Register,             // 0x02C4
+    pub bsr2: BusSoftReset2::Register,             // 0x02C8
+    __reserved_24: u32,                            // 0x02CC
+    pub bsr3: BusSoftReset3::Register,             // 0x02D0
+    __reserved_25: u32,                            // 0x02D4
+    pub bsr4: BusSoftReset4::Register,             // 0x02D8
 }
 
 pub struct CCU {
@@ -0,0 +1,52 @@
+//! I2S0
+//!
+//! Size: 1K
+
+use crate::i2s_common::RegisterBlock;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+pub const PADDR: usize = 0x01C2_2000;
+
+pub struct I2S0 {
+    _marker: PhantomData<*const ()>,
+}
+
+unsafe impl Send for I2S0 {}
+
+impl I2S0 {
+    pub unsafe fn from_paddr() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn as_ptr(&self) -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub const unsafe fn ptr() -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+
+    pub const unsafe fn mut_ptr() -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+}
+
+impl Deref for I2S0 {
+    type Target = RegisterBlock;
+    fn deref(&self) -> &RegisterBlock {
+        unsafe { &*self.as_ptr() }
+    }
+}
+
+impl DerefMut for I2S0 {
+    fn deref_mut(&mut self) -> &mut RegisterBlock {
+        unsafe { &mut *self.as_mut_ptr() }
+    }
+}
@@ -0,0 +1,53 @@
+//! I2S2
+//!
+//! Size: 1K
+//! Internally connected to the HDMI audio sampler
+
+use crate::i2s_common::RegisterBlock;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+pub const PADDR: usize = 0x01C2_2800;
+
+pub struct I2S2 {
+    _marker: PhantomData<*const ()>,
+}
+
+unsafe impl Send for I2S2 {}
+
+impl I2S2 {
+    pub unsafe fn from_paddr() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn as_ptr(&self) -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub const unsafe fn ptr() -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+
+    pub const unsafe fn mut_ptr() -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+}
+
+impl Deref for I2S2 {
+    type Target = RegisterBlock;
+    fn deref(&self) -> &RegisterBlock {
+        unsafe { &*self.as_ptr() }
+    }
+}
+
+impl DerefMut for I2S2 {
+    fn deref_mut(&mut self) -> &mut RegisterBlock {
+        unsafe { &mut *self.as_mut_ptr() }
+    }
+}
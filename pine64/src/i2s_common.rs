@@ -0,0 +1,235 @@
+//! I2S/PCM
+//!
+//! 128 x 32-bit TX FIFO, 64 x 32-bit RX FIFO, up to 8 slots on each of the
+//! four SDO lines
+
+use static_assertions::const_assert_eq;
+
+register! {
+    Control,
+    u32,
+    RW,
+    Fields [
+        GlobalEnable WIDTH(U1) OFFSET(U0),
+        RxEnable WIDTH(U1) OFFSET(U1),
+        TxEnable WIDTH(U1) OFFSET(U2),
+        Loopback WIDTH(U1) OFFSET(U3),
+        Mode WIDTH(U2) OFFSET(U4) [
+            Pcm = U0,
+            LeftJustified = U1,
+            RightJustified = U2
+        ]
+        OutMute WIDTH(U1) OFFSET(U6),
+        Sdo0Enable WIDTH(U1) OFFSET(U8),
+        Sdo1Enable WIDTH(U1) OFFSET(U9),
+        Sdo2Enable WIDTH(U1) OFFSET(U10),
+        Sdo3Enable WIDTH(U1) OFFSET(U11),
+        // Master mode, LRCK and BCLK are outputs
+        LrckOut WIDTH(U1) OFFSET(U17),
+        BclkOut WIDTH(U1) OFFSET(U18),
+    ]
+}
+
+register! {
+    Format0,
+    u32,
+    RW,
+    Fields [
+        // (bits / 4) - 1
+        SlotWidth WIDTH(U3) OFFSET(U0),
+        // (bits / 4) - 1
+        SampleResolution WIDTH(U3) OFFSET(U4),
+        BclkPolarity WIDTH(U1) OFFSET(U7) [
+            Normal = U0,
+            Inverted = U1
+        ]
+        // BCLKs per LRCK phase, minus 1
+        LrckPeriod WIDTH(U10) OFFSET(U8),
+        LrckPolarity WIDTH(U1) OFFSET(U19) [
+            Normal = U0,
+            Inverted = U1
+        ]
+        LrckWidth WIDTH(U1) OFFSET(U30),
+    ]
+}
+
+register! {
+    Format1,
+    u32,
+    RW,
+    Fields [
+        TxPdm WIDTH(U2) OFFSET(U0) [
+            Linear = U0,
+            ULaw = U2,
+            ALaw = U3
+        ]
+        RxPdm WIDTH(U2) OFFSET(U2) [
+            Linear = U0,
+            ULaw = U2,
+            ALaw = U3
+        ]
+        RxSignExtend WIDTH(U2) OFFSET(U4),
+        TxLsbFirst WIDTH(U1) OFFSET(U6),
+        RxLsbFirst WIDTH(U1) OFFSET(U7),
+    ]
+}
+
+register! {
+    IntStatus,
+    u32,
+    RW,
+    Fields [
+        RxAvailable WIDTH(U1) OFFSET(U0),
+        RxOverrun WIDTH(U1) OFFSET(U1),
+        RxUnderrun WIDTH(U1) OFFSET(U2),
+        TxEmpty WIDTH(U1) OFFSET(U4),
+        TxOverrun WIDTH(U1) OFFSET(U5),
+        TxUnderrun WIDTH(U1) OFFSET(U6),
+    ]
+}
+
+register! {
+    RxFifo,
+    u32,
+    RO,
+    Fields [
+        Data WIDTH(U32) OFFSET(U0),
+    ]
+}
+
+register! {
+    FifoControl,
+    u32,
+    RW,
+    Fields [
+        RxMode WIDTH(U2) OFFSET(U0),
+        TxMode WIDTH(U1) OFFSET(U2) [
+            // Valid data at the MSB of the TX FIFO register
+            Msb = U0,
+            // Valid data at the LSB of the TX FIFO register
+            Lsb = U1
+        ]
+        RxTriggerLevel WIDTH(U6) OFFSET(U4),
+        TxTriggerLevel WIDTH(U7) OFFSET(U12),
+        FlushRx WIDTH(U1) OFFSET(U24),
+        FlushTx WIDTH(U1) OFFSET(U25),
+        HubEnable WIDTH(U1) OFFSET(U31),
+    ]
+}
+
+register! {
+    FifoStatus,
+    u32,
+    RO,
+    Fields [
+        RxCount WIDTH(U7) OFFSET(U0),
+        RxAvailable WIDTH(U1) OFFSET(U8),
+        TxSpace WIDTH(U8) OFFSET(U16),
+        TxEmpty WIDTH(U1) OFFSET(U28),
+    ]
+}
+
+register! {
+    IntControl,
+    u32,
+    RW,
+    Fields [
+        RxAvailableIrq WIDTH(U1) OFFSET(U0),
+        RxOverrunIrq WIDTH(U1) OFFSET(U1),
+        RxUnderrunIrq WIDTH(U1) OFFSET(U2),
+        RxDrqEnable WIDTH(U1) OFFSET(U3),
+        TxEmptyIrq WIDTH(U1) OFFSET(U4),
+        TxOverrunIrq WIDTH(U1) OFFSET(U5),
+        TxUnderrunIrq WIDTH(U1) OFFSET(U6),
+        TxDrqEnable WIDTH(U1) OFFSET(U7),
+    ]
+}
+
+register! {
+    TxFifo,
+    u32,
+    WO,
+    Fields [
+        Data WIDTH(U32) OFFSET(U0),
+    ]
+}
+
+register! {
+    ClockDivide,
+    u32,
+    RW,
+    Fields [
+        // 1, 2, 4, 6, 8, 12, 16, 24, 32, 48, 64, 96, 128, 176, 192
+        MclkDiv WIDTH(U4) OFFSET(U0),
+        BclkDiv WIDTH(U4) OFFSET(U4),
+        MclkOutEnable WIDTH(U1) OFFSET(U8),
+    ]
+}
+
+register! {
+    Counter,
+    u32,
+    RW,
+    Fields [
+        Count WIDTH(U32) OFFSET(U0),
+    ]
+}
+
+register! {
+    ChannelConfig,
+    u32,
+    RW,
+    Fields [
+        // Slots per frame, minus 1
+        TxSlotNum WIDTH(U3) OFFSET(U0),
+        RxSlotNum WIDTH(U3) OFFSET(U4),
+        TxSlotHiz WIDTH(U1) OFFSET(U8),
+        TxState WIDTH(U1) OFFSET(U9),
+    ]
+}
+
+register! {
+    ChannelSelect,
+    u32,
+    RW,
+    Fields [
+        // Slots on the line, minus 1
+        Channels WIDTH(U3) OFFSET(U0),
+        SlotEnable WIDTH(U8) OFFSET(U4),
+        // BCLKs between LRCK and the MSB
+        Offset WIDTH(U2) OFFSET(U12),
+    ]
+}
+
+register! {
+    ChannelMap,
+    u32,
+    RW,
+    Fields [
+        // Sample channel sent in each slot, a nibble per slot
+        Map WIDTH(U32) OFFSET(U0),
+    ]
+}
+
+const_assert_eq!(core::mem::size_of::<RegisterBlock>(), 0x5C);
+
+#[repr(C)]
+pub struct RegisterBlock {
+    pub ctl: Control::Register,                 // 0x00
+    pub fmt0: Format0::Register,                // 0x04
+    pub fmt1: Format1::Register,                // 0x08
+    pub ista: IntStatus::Register,              // 0x0C
+    pub rxfifo: RxFifo::Register,               // 0x10
+    pub fctl: FifoControl::Register,            // 0x14
+    pub fsta: FifoStatus::Register,             // 0x18
+    pub int: IntControl::Register,              // 0x1C
+    pub txfifo: TxFifo::Register,               // 0x20
+    pub clkd: ClockDivide::Register,            // 0x24
+    pub txcnt: Counter::Register,               // 0x28
+    pub rxcnt: Counter::Register,               // 0x2C
+    pub chcfg: ChannelConfig::Register,         // 0x30
+    pub tx_chsel: [ChannelSelect::Register; 4], // 0x34
+    pub tx_chmap: [ChannelMap::Register; 4],    // 0x44
+    pub rx_chsel: ChannelSelect::Register,      // 0x54
+    pub rx_chmap: ChannelMap::Register,         // 0x58
+}
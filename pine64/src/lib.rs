@@ -12,11 +12,29 @@ pub mod de_mixer;
 pub mod dma;
 pub mod hdmi;
 pub mod hstimer;
+pub mod i2s0;
+pub mod i2s1;
+pub mod i2s2;
+pub mod i2s_common;
 pub mod pio;
+pub mod pwm;
+pub mod r_pio;
+pub mod r_prcm;
+pub mod r_pwm;
+pub mod r_rsb;
+pub mod r_twi;
+pub mod rtc;
+pub mod spi0;
+pub mod spi1;
+pub mod spi_common;
 pub mod sysc;
 pub mod tcon0;
 pub mod tcon1;
 pub mod timer;
+pub mod twi0;
+pub mod twi1;
+pub mod twi2;
+pub mod twi_common;
 pub mod uart0;
 pub mod uart1;
 pub mod uart2;
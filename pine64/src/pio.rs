@@ -2,6 +2,8 @@
 //!
 //! Size: 1K
 //!
+//! Ports B through H, port L lives in the R_PIO
+//!
 //! PxY_Select variants mapped to alt functions:
 //!   * 000 (U0): input
 //!   * 001 (U1): output
@@ -209,6 +211,76 @@ register! {
             Af4 = U6,
             Disabled = U7
         ]
+        Pin17 WIDTH(U3) OFFSET(U4) [
+            Input = U0,
+            Output = U1,
+            Af0 = U2,
+            Af1 = U3,
+            Af2 = U4,
+            Af3 = U5,
+            Af4 = U6,
+            Disabled = U7
+        ]
+        Pin18 WIDTH(U3) OFFSET(U8) [
+            Input = U0,
+            Output = U1,
+            Af0 = U2,
+            Af1 = U3,
+            Af2 = U4,
+            Af3 = U5,
+            Af4 = U6,
+            Disabled = U7
+        ]
+        Pin19 WIDTH(U3) OFFSET(U12) [
+            Input = U0,
+            Output = U1,
+            Af0 = U2,
+            Af1 = U3,
+            Af2 = U4,
+            Af3 = U5,
+            Af4 = U6,
+            Disabled = U7
+        ]
+        Pin20 WIDTH(U3) OFFSET(U16) [
+            Input = U0,
+            Output = U1,
+            Af0 = U2,
+            Af1 = U3,
+            Af2 = U4,
+            Af3 = U5,
+            Af4 = U6,
+            Disabled = U7
+        ]
+        Pin21 WIDTH(U3) OFFSET(U20) [
+            Input = U0,
+            Output = U1,
+            Af0 = U2,
+            Af1 = U3,
+            Af2 = U4,
+            Af3 = U5,
+            Af4 = U6,
+            Disabled = U7
+        ]
+        Pin22 WIDTH(U3) OFFSET(U24) [
+            Input = U0,
+            Output = U1,
+            Af0 = U2,
+            Af1 = U3,
+            Af2 = U4,
+            Af3 = U5,
+            Af4 = U6,
+            Disabled = U7
+        ]
+        Pin23 WIDTH(U3) OFFSET(U28) [
+            Input = U0,
+            Output = U1,
+            Af0 = U2,
+            Af1 = U3,
+            Af2 = U4,
+            Af3 = U5,
+            Af4 = U6,
+            Disabled = U7
+        ]
     ]
 }
 
@@ -378,6 +450,54 @@ register! {
             Level2 = U2,
             Level3 = U3
         ]
+        Pin17 WIDTH(U2) OFFSET(U2) [
+            Level0 = U0,
+            Level1 = U1,
+            Level2 = U2,
+            Level3 = U3
+        ]
+        Pin18 WIDTH(U2) OFFSET(U4) [
+            Level0 = U0,
+            Level1 = U1,
+            Level2 = U2,
+            Level3 = U3
+        ]
+        Pin19 WIDTH(U2) OFFSET(U6) [
+            Level0 = U0,
+            Level1 = U1,
+            Level2 = U2,
+            Level3 = U3
+        ]
+        Pin20 WIDTH(U2) OFFSET(U8) [
+            Level0 = U0,
+            Level1 = U1,
+            Level2 = U2,
+            Level3 = U3
+        ]
+        Pin21 WIDTH(U2) OFFSET(U10) [
+            Level0 = U0,
+            Level1 = U1,
+            Level2 = U2,
+            Level3 = U3
+        ]
+        Pin22 WIDTH(U2) OFFSET(U12) [
+            Level0 = U0,
+            Level1 = U1,
+            Level2 = U2,
+            Level3 = U3
+        ]
+        Pin23 WIDTH(U2) OFFSET(U14) [
+            Level0 = U0,
+            Level1 = U1,
+            Level2 = U2,
+            Level3 = U3
+        ]
+        Pin24 WIDTH(U2) OFFSET(U16) [
+            Level0 = U0,
+            Level1 = U1,
+            Level2 = U2,
+            Level3 = U3
+        ]
     ]
 }
 
@@ -479,41 +599,323 @@ register! {
             PullUp = U1,
             PullDown = U2
         ]
+        Pin17 WIDTH(U2) OFFSET(U2) [
+            Disabled = U0,
+            PullUp = U1,
+            PullDown = U2
+        ]
+        Pin18 WIDTH(U2) OFFSET(U4) [
+            Disabled = U0,
+            PullUp = U1,
+            PullDown = U2
+        ]
+        Pin19 WIDTH(U2) OFFSET(U6) [
+            Disabled = U0,
+            PullUp = U1,
+            PullDown = U2
+        ]
+        Pin20 WIDTH(U2) OFFSET(U8) [
+            Disabled = U0,
+            PullUp = U1,
+            PullDown = U2
+        ]
+        Pin21 WIDTH(U2) OFFSET(U10) [
+            Disabled = U0,
+            PullUp = U1,
+            PullDown = U2
+        ]
+        Pin22 WIDTH(U2) OFFSET(U12) [
+            Disabled = U0,
+            PullUp = U1,
+            PullDown = U2
+        ]
+        Pin23 WIDTH(U2) OFFSET(U14) [
+            Disabled = U0,
+            PullUp = U1,
+            PullDown = U2
+        ]
+        Pin24 WIDTH(U2) OFFSET(U16) [
+            Disabled = U0,
+            PullUp = U1,
+            PullDown = U2
+        ]
+    ]
+}
+
+register! {
+    IntConfig0,
+    u32,
+    RW,
+    Fields [
+        Pin0 WIDTH(U4) OFFSET(U0) [
+            PosEdge = U0,
+            NegEdge = U1,
+            HighLevel = U2,
+            LowLevel = U3,
+            DoubleEdge = U4
+        ]
+        Pin1 WIDTH(U4) OFFSET(U4) [
+            PosEdge = U0,
+            NegEdge = U1,
+            HighLevel = U2,
+            LowLevel = U3,
+            DoubleEdge = U4
+        ]
+        Pin2 WIDTH(U4) OFFSET(U8) [
+            PosEdge = U0,
+            NegEdge = U1,
+            HighLevel = U2,
+            LowLevel = U3,
+            DoubleEdge = U4
+        ]
+        Pin3 WIDTH(U4) OFFSET(U12) [
+            PosEdge = U0,
+            NegEdge = U1,
+            HighLevel = U2,
+            LowLevel = U3,
+            DoubleEdge = U4
+        ]
+        Pin4 WIDTH(U4) OFFSET(U16) [
+            PosEdge = U0,
+            NegEdge = U1,
+            HighLevel = U2,
+            LowLevel = U3,
+            DoubleEdge = U4
+        ]
+        Pin5 WIDTH(U4) OFFSET(U20) [
+            PosEdge = U0,
+            NegEdge = U1,
+            HighLevel = U2,
+            LowLevel = U3,
+            DoubleEdge = U4
+        ]
+        Pin6 WIDTH(U4) OFFSET(U24) [
+            PosEdge = U0,
+            NegEdge = U1,
+            HighLevel = U2,
+            LowLevel = U3,
+            DoubleEdge = U4
+        ]
+        Pin7 WIDTH(U4) OFFSET(U28) [
+            PosEdge = U0,
+            NegEdge = U1,
+            HighLevel = U2,
+            LowLevel = U3,
+            DoubleEdge = U4
+        ]
+    ]
+}
+
+register! {
+    IntConfig1,
+    u32,
+    RW,
+    Fields [
+        Pin8 WIDTH(U4) OFFSET(U0) [
+            PosEdge = U0,
+            NegEdge = U1,
+            HighLevel = U2,
+            LowLevel = U3,
+            DoubleEdge = U4
+        ]
+        Pin9 WIDTH(U4) OFFSET(U4) [
+            PosEdge = U0,
+            NegEdge = U1,
+            HighLevel = U2,
+            LowLevel = U3,
+            DoubleEdge = U4
+        ]
+        Pin10 WIDTH(U4) OFFSET(U8) [
+            PosEdge = U0,
+            NegEdge = U1,
+            HighLevel = U2,
+            LowLevel = U3,
+            DoubleEdge = U4
+        ]
+        Pin11 WIDTH(U4) OFFSET(U12) [
+            PosEdge = U0,
+            NegEdge = U1,
+            HighLevel = U2,
+            LowLevel = U3,
+            DoubleEdge = U4
+        ]
+        Pin12 WIDTH(U4) OFFSET(U16) [
+            PosEdge = U0,
+            NegEdge = U1,
+            HighLevel = U2,
+            LowLevel = U3,
+            DoubleEdge = U4
+        ]
+        Pin13 WIDTH(U4) OFFSET(U20) [
+            PosEdge = U0,
+            NegEdge = U1,
+            HighLevel = U2,
+            LowLevel = U3,
+            DoubleEdge = U4
+        ]
+        Pin14 WIDTH(U4) OFFSET(U24) [
+            PosEdge = U0,
+            NegEdge = U1,
+            HighLevel = U2,
+            LowLevel = U3,
+            DoubleEdge = U4
+        ]
+        Pin15 WIDTH(U4) OFFSET(U28) [
+            PosEdge = U0,
+            NegEdge = U1,
+            HighLevel = U2,
+            LowLevel = U3,
+            DoubleEdge = U4
+        ]
+    ]
+}
+
+register! {
+    IntControl,
+    u32,
+    RW,
+    Fields [
+        Pin0 WIDTH(U1) OFFSET(U0),
+        Pin1 WIDTH(U1) OFFSET(U1),
+        Pin2 WIDTH(U1) OFFSET(U2),
+        Pin3 WIDTH(U1) OFFSET(U3),
+        Pin4 WIDTH(U1) OFFSET(U4),
+        Pin5 WIDTH(U1) OFFSET(U5),
+        Pin6 WIDTH(U1) OFFSET(U6),
+        Pin7 WIDTH(U1) OFFSET(U7),
+        Pin8 WIDTH(U1) OFFSET(U8),
+        Pin9 WIDTH(U1) OFFSET(U9),
+        Pin10 WIDTH(U1) OFFSET(U10),
+        Pin11 WIDTH(U1) OFFSET(U11),
+        Pin12 WIDTH(U1) OFFSET(U12),
+        Pin13 WIDTH(U1) OFFSET(U13),
+        Pin14 WIDTH(U1) OFFSET(U14),
+        Pin15 WIDTH(U1) OFFSET(U15),
+    ]
+}
+
+register! {
+    /// Write 1 to clear
+    IntStatus,
+    u32,
+    RW,
+    Fields [
+        Pin0 WIDTH(U1) OFFSET(U0),
+        Pin1 WIDTH(U1) OFFSET(U1),
+        Pin2 WIDTH(U1) OFFSET(U2),
+        Pin3 WIDTH(U1) OFFSET(U3),
+        Pin4 WIDTH(U1) OFFSET(U4),
+        Pin5 WIDTH(U1) OFFSET(U5),
+        Pin6 WIDTH(U1) OFFSET(U6),
+        Pin7 WIDTH(U1) OFFSET(U7),
+        Pin8 WIDTH(U1) OFFSET(U8),
+        Pin9 WIDTH(U1) OFFSET(U9),
+        Pin10 WIDTH(U1) OFFSET(U10),
+        Pin11 WIDTH(U1) OFFSET(U11),
+        Pin12 WIDTH(U1) OFFSET(U12),
+        Pin13 WIDTH(U1) OFFSET(U13),
+        Pin14 WIDTH(U1) OFFSET(U14),
+        Pin15 WIDTH(U1) OFFSET(U15),
+    ]
+}
+
+register! {
+    IntDebounce,
+    u32,
+    RW,
+    Fields [
+        ClockSelect WIDTH(U1) OFFSET(U0) [
+            LOsc32K = U0,
+            HOsc24M = U1
+        ]
+        PreScale WIDTH(U3) OFFSET(U4),
     ]
 }
 
-const_assert_eq!(core::mem::size_of::<RegisterBlock>(), 0x90);
+const_assert_eq!(core::mem::size_of::<RegisterBlock>(), 0x300);
 
 #[repr(C)]
 pub struct RegisterBlock {
-    __reserved_0: [u32; 9],         // 0x00
-    pub pb_cfg0: Config0::Register, // 0x24
-    pub pb_cfg1: Config1::Register, // 0x28
-    __reserved_1: u32,              // 0x2C
-    __reserved_2: u32,              // 0x30
-    pub pb_data: Data::Register,    // 0x34
-    pub pb_driv0: Driv0::Register,  // 0x38
-    __reserved_3: u32,              // 0x3C
-    pub pb_pull0: Pull0::Register,  // 0x40
-    __reserved_4: u32,              // 0x44
-    pub pc_cfg0: Config0::Register, // 0x48
-    pub pc_cfg1: Config1::Register, // 0x4C
-    pub pc_cfg2: Config2::Register, // 0x50
-    __reserved_5: u32,              // 0x54
-    pub pc_data: Data::Register,    // 0x58
-    pub pc_driv0: Driv0::Register,  // 0x5C
-    pub pc_driv1: Driv1::Register,  // 0x60
-    pub pc_pull0: Pull0::Register,  // 0x64
-    pub pc_pull1: Pull1::Register,  // 0x68
-    pub pd_cfg0: Config0::Register, // 0x6C
-    pub pd_cfg1: Config1::Register, // 0x70
-    pub pd_cfg2: Config2::Register, // 0x74
-    pub pd_cfg3: Config3::Register, // 0x78
-    pub pd_data: Data::Register,    // 0x7C
-    pub pd_driv0: Driv0::Register,  // 0x80
-    pub pd_driv1: Driv1::Register,  // 0x84
-    pub pd_pull0: Pull0::Register,  // 0x88
-    pub pd_pull1: Pull1::Register,  // 0x8C
+    __reserved_0: [u32; 9],                 // 0x00
+    pub pb_cfg0: Config0::Register,         // 0x24
+    pub pb_cfg1: Config1::Register,         // 0x28
+    __reserved_1: [u32; 2],                 // 0x2C
+    pub pb_data: Data::Register,            // 0x34
+    pub pb_driv0: Driv0::Register,          // 0x38
+    __reserved_2: u32,                      // 0x3C
+    pub pb_pull0: Pull0::Register,          // 0x40
+    __reserved_3: u32,                      // 0x44
+    pub pc_cfg0: Config0::Register,         // 0x48
+    pub pc_cfg1: Config1::Register,         // 0x4C
+    pub pc_cfg2: Config2::Register,         // 0x50
+    __reserved_4: u32,                      // 0x54
+    pub pc_data: Data::Register,            // 0x58
+    pub pc_driv0: Driv0::Register,          // 0x5C
+    pub pc_driv1: Driv1::Register,          // 0x60
+    pub pc_pull0: Pull0::Register,          // 0x64
+    pub pc_pull1: Pull1::Register,          // 0x68
+    pub pd_cfg0: Config0::Register,         // 0x6C
+    pub pd_cfg1: Config1::Register,         // 0x70
+    pub pd_cfg2: Config2::Register,         // 0x74
+    pub pd_cfg3: Config3::Register,         // 0x78
+    pub pd_data: Data::Register,            // 0x7C
+    pub pd_driv0: Driv0::Register,          // 0x80
+    pub pd_driv1: Driv1::Register,          // 0x84
+    pub pd_pull0: Pull0::Register,          // 0x88
+    pub pd_pull1: Pull1::Register,          // 0x8C
+    pub pe_cfg0: Config0::Register,         // 0x90
+    pub pe_cfg1: Config1::Register,         // 0x94
+    pub pe_cfg2: Config2::Register,         // 0x98
+    __reserved_5: u32,                      // 0x9C
+    pub pe_data: Data::Register,            // 0xA0
+    pub pe_driv0: Driv0::Register,          // 0xA4
+    pub pe_driv1: Driv1::Register,          // 0xA8
+    pub pe_pull0: Pull0::Register,          // 0xAC
+    pub pe_pull1: Pull1::Register,          // 0xB0
+    pub pf_cfg0: Config0::Register,         // 0xB4
+    __reserved_6: [u32; 3],                 // 0xB8
+    pub pf_data: Data::Register,            // 0xC4
+    pub pf_driv0: Driv0::Register,          // 0xC8
+    __reserved_7: u32,                      // 0xCC
+    pub pf_pull0: Pull0::Register,          // 0xD0
+    __reserved_8: u32,                      // 0xD4
+    pub pg_cfg0: Config0::Register,         // 0xD8
+    pub pg_cfg1: Config1::Register,         // 0xDC
+    __reserved_9: [u32; 2],                 // 0xE0
+    pub pg_data: Data::Register,            // 0xE8
+    pub pg_driv0: Driv0::Register,          // 0xEC
+    __reserved_10: u32,                     // 0xF0
+    pub pg_pull0: Pull0::Register,          // 0xF4
+    __reserved_11: u32,                     // 0xF8
+    pub ph_cfg0: Config0::Register,         // 0xFC
+    pub ph_cfg1: Config1::Register,         // 0x100
+    __reserved_12: [u32; 2],                // 0x104
+    pub ph_data: Data::Register,            // 0x10C
+    pub ph_driv0: Driv0::Register,          // 0x110
+    __reserved_13: u32,                     // 0x114
+    pub ph_pull0: Pull0::Register,          // 0x118
+    __reserved_14: [u32; 65],               // 0x11C
+    pub pb_int_cfg0: IntConfig0::Register,  // 0x220
+    pub pb_int_cfg1: IntConfig1::Register,  // 0x224
+    __reserved_15: [u32; 2],                // 0x228
+    pub pb_int_ctrl: IntControl::Register,  // 0x230
+    pub pb_int_status: IntStatus::Register, // 0x234
+    pub pb_int_deb: IntDebounce::Register,  // 0x238
+    __reserved_16: [u32; 33],               // 0x23C
+    pub pg_int_cfg0: IntConfig0::Register,  // 0x2C0
+    pub pg_int_cfg1: IntConfig1::Register,  // 0x2C4
+    __reserved_17: [u32; 2],                // 0x2C8
+    pub pg_int_ctrl: IntControl::Register,  // 0x2D0
+    pub pg_int_status: IntStatus::Register, // 0x2D4
+    pub pg_int_deb: IntDebounce::Register,  // 0x2D8
+    __reserved_18: u32,                     // 0x2DC
+    pub ph_int_cfg0: IntConfig0::Register,  // 0x2E0
+    pub ph_int_cfg1: IntConfig1::Register,  // 0x2E4
+    __reserved_19: [u32; 2],                // 0x2E8
+    pub ph_int_ctrl: IntControl::Register,  // 0x2F0
+    pub ph_int_status: IntStatus::Register, // 0x2F4
+    pub ph_int_deb: IntDebounce::Register,  // 0x2F8
+    __reserved_20: u32,                     // 0x2FC
 }
 
 pub struct PIO {
@@ -0,0 +1,107 @@
+//! PWM
+//!
+//! Size: 1K
+//! PWM0: PD22
+
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use static_assertions::const_assert_eq;
+
+pub const PADDR: usize = 0x01C2_1400;
+
+register! {
+    Control,
+    u32,
+    RW,
+    Fields [
+        Prescaler WIDTH(U4) OFFSET(U0) [
+            Div120 = U0,
+            Div180 = U1,
+            Div240 = U2,
+            Div360 = U3,
+            Div480 = U4,
+            Div12k = U8,
+            Div24k = U9,
+            Div36k = U10,
+            Div48k = U11,
+            Div72k = U12,
+            Div1 = U15
+        ]
+        Enable WIDTH(U1) OFFSET(U4),
+        ActiveState WIDTH(U1) OFFSET(U5) [
+            Low = U0,
+            High = U1
+        ]
+        ClockGating WIDTH(U1) OFFSET(U6),
+        Mode WIDTH(U1) OFFSET(U7) [
+            Cycle = U0,
+            Pulse = U1
+        ]
+        PulseStart WIDTH(U1) OFFSET(U8),
+        Bypass WIDTH(U1) OFFSET(U9),
+        // The period register is busy
+        PeriodBusy WIDTH(U1) OFFSET(U28),
+    ]
+}
+
+register! {
+    Period,
+    u32,
+    RW,
+    Fields [
+        ActiveCycles WIDTH(U16) OFFSET(U0),
+        // Minus one
+        EntireCycles WIDTH(U16) OFFSET(U16),
+    ]
+}
+
+const_assert_eq!(core::mem::size_of::<RegisterBlock>(), 0x08);
+
+#[repr(C)]
+pub struct RegisterBlock {
+    pub ctrl: Control::Register,      // 0x00
+    pub ch0_period: Period::Register, // 0x04
+}
+
+pub struct PWM {
+    _marker: PhantomData<*const ()>,
+}
+
+unsafe impl Send for PWM {}
+
+impl PWM {
+    pub unsafe fn from_paddr() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn as_ptr(&self) -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub const unsafe fn ptr() -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+
+    pub const unsafe fn mut_ptr() -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+}
+
+impl Deref for PWM {
+    type Target = RegisterBlock;
+    fn deref(&self) -> &RegisterBlock {
+        unsafe { &*self.as_ptr() }
+    }
+}
+
+impl DerefMut for PWM {
+    fn deref_mut(&mut self) -> &mut RegisterBlock {
+        unsafe { &mut *self.as_mut_ptr() }
+    }
+}
@@ -0,0 +1,79 @@
+//! R_PIO port controller (CPUs-PORT)
+//!
+//! Size: 1K
+//!
+//! Port L, shares the register layout of the PIO
+
+use crate::pio::{
+    Config0, Config1, Data, Driv0, Driv1, IntConfig0, IntConfig1, IntControl, IntDebounce,
+    IntStatus, Pull0, Pull1,
+};
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use static_assertions::const_assert_eq;
+
+pub const PADDR: usize = 0x01F0_2C00;
+
+const_assert_eq!(core::mem::size_of::<RegisterBlock>(), 0x21C);
+
+#[repr(C)]
+pub struct RegisterBlock {
+    pub pl_cfg0: Config0::Register,         // 0x00
+    pub pl_cfg1: Config1::Register,         // 0x04
+    __reserved_0: [u32; 2],                 // 0x08
+    pub pl_data: Data::Register,            // 0x10
+    pub pl_driv0: Driv0::Register,          // 0x14
+    pub pl_driv1: Driv1::Register,          // 0x18
+    pub pl_pull0: Pull0::Register,          // 0x1C
+    pub pl_pull1: Pull1::Register,          // 0x20
+    __reserved_1: [u32; 119],               // 0x24
+    pub pl_int_cfg0: IntConfig0::Register,  // 0x200
+    pub pl_int_cfg1: IntConfig1::Register,  // 0x204
+    __reserved_2: [u32; 2],                 // 0x208
+    pub pl_int_ctrl: IntControl::Register,  // 0x210
+    pub pl_int_status: IntStatus::Register, // 0x214
+    pub pl_int_deb: IntDebounce::Register,  // 0x218
+}
+
+pub struct RPIO {
+    _marker: PhantomData<*const ()>,
+}
+
+unsafe impl Send for RPIO {}
+
+impl RPIO {
+    pub unsafe fn from_paddr() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn as_ptr(&self) -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub const unsafe fn ptr() -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+
+    pub const unsafe fn mut_ptr() -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+}
+
+impl Deref for RPIO {
+    type Target = RegisterBlock;
+    fn deref(&self) -> &RegisterBlock {
+        unsafe { &*self.as_ptr() }
+    }
+}
+
+impl DerefMut for RPIO {
+    fn deref_mut(&mut self) -> &mut RegisterBlock {
+        unsafe { &mut *self.as_mut_ptr() }
+    }
+}
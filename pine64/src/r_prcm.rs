@@ -0,0 +1,121 @@
+//! R_PRCM, clock and reset control of the CPUS domain
+//!
+//! Size: 1K
+
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use static_assertions::const_assert_eq;
+
+pub const PADDR: usize = 0x01F0_1400;
+
+register! {
+    CpusConfig,
+    u32,
+    RW,
+    Fields [
+        // Divide by 2^n
+        ClockDivRatio WIDTH(U2) OFFSET(U4),
+        // PLL_PERIPH0 only, divide by n + 1
+        PllPeriph0PreDiv WIDTH(U5) OFFSET(U8),
+        ClockSrcSel WIDTH(U2) OFFSET(U16) [
+            LOsc = U0,
+            Osc24M = U1,
+            PllPeriph0 = U2,
+            IOsc = U3
+        ]
+    ]
+}
+
+register! {
+    Apb0Config,
+    u32,
+    RW,
+    Fields [
+        // Divide by 2^n
+        ClockDivRatio WIDTH(U2) OFFSET(U0),
+    ]
+}
+
+register! {
+    Apb0ClockGating,
+    u32,
+    RW,
+    Fields [
+        RPio WIDTH(U1) OFFSET(U0),
+        RIr WIDTH(U1) OFFSET(U1),
+        RTimer WIDTH(U1) OFFSET(U2),
+        RRsb WIDTH(U1) OFFSET(U3),
+        RUart WIDTH(U1) OFFSET(U4),
+        RTwi WIDTH(U1) OFFSET(U6),
+        RTwd WIDTH(U1) OFFSET(U7),
+    ]
+}
+
+register! {
+    Apb0SoftReset,
+    u32,
+    RW,
+    Fields [
+        RIr WIDTH(U1) OFFSET(U1),
+        RTimer WIDTH(U1) OFFSET(U2),
+        RRsb WIDTH(U1) OFFSET(U3),
+        RUart WIDTH(U1) OFFSET(U4),
+        RTwi WIDTH(U1) OFFSET(U6),
+    ]
+}
+
+const_assert_eq!(core::mem::size_of::<RegisterBlock>(), 0xB4);
+
+#[repr(C)]
+pub struct RegisterBlock {
+    pub cpus_cfg: CpusConfig::Register,       // 0x00
+    __reserved_0: [u32; 2],                   // 0x04
+    pub apb0_cfg: Apb0Config::Register,       // 0x0C
+    __reserved_1: [u32; 6],                   // 0x10
+    pub apb0_gate: Apb0ClockGating::Register, // 0x28
+    __reserved_2: [u32; 33],                  // 0x2C
+    pub apb0_rst: Apb0SoftReset::Register,    // 0xB0
+}
+
+pub struct RPRCM {
+    _marker: PhantomData<*const ()>,
+}
+
+unsafe impl Send for RPRCM {}
+
+impl RPRCM {
+    pub unsafe fn from_paddr() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn as_ptr(&self) -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub const unsafe fn ptr() -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+
+    pub const unsafe fn mut_ptr() -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+}
+
+impl Deref for RPRCM {
+    type Target = RegisterBlock;
+    fn deref(&self) -> &RegisterBlock {
+        unsafe { &*self.as_ptr() }
+    }
+}
+
+impl DerefMut for RPRCM {
+    fn deref_mut(&mut self) -> &mut RegisterBlock {
+        unsafe { &mut *self.as_mut_ptr() }
+    }
+}
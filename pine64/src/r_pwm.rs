@@ -0,0 +1,55 @@
+//! R_PWM (S_PWM)
+//!
+//! Size: 1K
+//! S_PWM: PL10
+//!
+//! Shares the register layout of the PWM
+
+use crate::pwm::RegisterBlock;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+pub const PADDR: usize = 0x01F0_3800;
+
+pub struct RPWM {
+    _marker: PhantomData<*const ()>,
+}
+
+unsafe impl Send for RPWM {}
+
+impl RPWM {
+    pub unsafe fn from_paddr() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn as_ptr(&self) -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub const unsafe fn ptr() -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+
+    pub const unsafe fn mut_ptr() -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+}
+
+impl Deref for RPWM {
+    type Target = RegisterBlock;
+    fn deref(&self) -> &RegisterBlock {
+        unsafe { &*self.as_ptr() }
+    }
+}
+
+impl DerefMut for RPWM {
+    fn deref_mut(&mut self) -> &mut RegisterBlock {
+        unsafe { &mut *self.as_mut_ptr() }
+    }
+}
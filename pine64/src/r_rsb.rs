@@ -0,0 +1,191 @@
+//! R_RSB, reduced serial bus
+//!
+//! Size: 1K
+//! S_RSB_SCK: PL0
+//! S_RSB_SDA: PL1
+
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use static_assertions::const_assert_eq;
+
+pub const PADDR: usize = 0x01F0_3400;
+
+register! {
+    Control,
+    u32,
+    RW,
+    Fields [
+        SoftReset WIDTH(U1) OFFSET(U0),
+        GlobalIntEnable WIDTH(U1) OFFSET(U1),
+        AbortTransfer WIDTH(U1) OFFSET(U6),
+        StartTransfer WIDTH(U1) OFFSET(U7),
+    ]
+}
+
+register! {
+    ClockControl,
+    u32,
+    RW,
+    Fields [
+        // SCK = clk / (2 * (div + 1))
+        ClockDiv WIDTH(U8) OFFSET(U0),
+        SdaOutputDelay WIDTH(U3) OFFSET(U8),
+    ]
+}
+
+register! {
+    IntEnable,
+    u32,
+    RW,
+    Fields [
+        TransferOver WIDTH(U1) OFFSET(U0),
+        TransferError WIDTH(U1) OFFSET(U1),
+        LoadBusy WIDTH(U1) OFFSET(U2),
+    ]
+}
+
+register! {
+    IntStatus,
+    u32,
+    RW,
+    Fields [
+        TransferOver WIDTH(U1) OFFSET(U0),
+        TransferError WIDTH(U1) OFFSET(U1),
+        LoadBusy WIDTH(U1) OFFSET(U2),
+        // Byte the error occurred on
+        ErrorData WIDTH(U4) OFFSET(U8),
+        ErrorAck WIDTH(U1) OFFSET(U16),
+    ]
+}
+
+register! {
+    Address,
+    u32,
+    RW,
+    Fields [
+        Address WIDTH(U8) OFFSET(U0),
+    ]
+}
+
+register! {
+    Data,
+    u32,
+    RW,
+    Fields [
+        Data WIDTH(U32) OFFSET(U0),
+    ]
+}
+
+register! {
+    LineControl,
+    u32,
+    RW,
+    Fields [
+        SdaControlEnable WIDTH(U1) OFFSET(U0),
+        SdaControl WIDTH(U1) OFFSET(U1),
+        SckControlEnable WIDTH(U1) OFFSET(U2),
+        SckControl WIDTH(U1) OFFSET(U3),
+        SdaState WIDTH(U1) OFFSET(U4),
+        SckState WIDTH(U1) OFFSET(U5),
+    ]
+}
+
+register! {
+    DeviceModeControl,
+    u32,
+    RW,
+    Fields [
+        DeviceAddress WIDTH(U8) OFFSET(U0),
+        ModeRegister WIDTH(U8) OFFSET(U8),
+        ModeData WIDTH(U8) OFFSET(U16),
+        DeviceModeStart WIDTH(U1) OFFSET(U31),
+    ]
+}
+
+register! {
+    Command,
+    u32,
+    RW,
+    Fields [
+        Command WIDTH(U8) OFFSET(U0) [
+            Write8 = U78,
+            Write16 = U89,
+            Write32 = U99,
+            Read8 = U139,
+            Read16 = U156,
+            Read32 = U166,
+            SetRuntimeAddress = U232
+        ]
+    ]
+}
+
+register! {
+    DeviceAddress,
+    u32,
+    RW,
+    Fields [
+        HardwareAddress WIDTH(U16) OFFSET(U0),
+        RuntimeAddress WIDTH(U8) OFFSET(U16),
+    ]
+}
+
+const_assert_eq!(core::mem::size_of::<RegisterBlock>(), 0x34);
+
+#[repr(C)]
+pub struct RegisterBlock {
+    pub ctrl: Control::Register,           // 0x00
+    pub ccr: ClockControl::Register,       // 0x04
+    pub inte: IntEnable::Register,         // 0x08
+    pub stat: IntStatus::Register,         // 0x0C
+    pub addr: Address::Register,           // 0x10
+    __reserved_0: [u32; 2],                // 0x14
+    pub data: Data::Register,              // 0x1C
+    __reserved_1: u32,                     // 0x20
+    pub lcr: LineControl::Register,        // 0x24
+    pub dmcr: DeviceModeControl::Register, // 0x28
+    pub cmd: Command::Register,            // 0x2C
+    pub dar: DeviceAddress::Register,      // 0x30
+}
+
+pub struct RRSB {
+    _marker: PhantomData<*const ()>,
+}
+
+unsafe impl Send for RRSB {}
+
+impl RRSB {
+    pub unsafe fn from_paddr() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn as_ptr(&self) -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub const unsafe fn ptr() -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+
+    pub const unsafe fn mut_ptr() -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+}
+
+impl Deref for RRSB {
+    type Target = RegisterBlock;
+    fn deref(&self) -> &RegisterBlock {
+        unsafe { &*self.as_ptr() }
+    }
+}
+
+impl DerefMut for RRSB {
+    fn deref_mut(&mut self) -> &mut RegisterBlock {
+        unsafe { &mut *self.as_mut_ptr() }
+    }
+}
@@ -0,0 +1,54 @@
+//! R_TWI (S_TWI)
+//!
+//! Size: 1K
+//! SCK: PL0, PL8
+//! SDA: PL1, PL9
+
+use crate::twi_common::RegisterBlock;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+pub const PADDR: usize = 0x01F0_2400;
+
+pub struct RTWI {
+    _marker: PhantomData<*const ()>,
+}
+
+unsafe impl Send for RTWI {}
+
+impl RTWI {
+    pub unsafe fn from_paddr() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn as_ptr(&self) -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub const unsafe fn ptr() -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+
+    pub const unsafe fn mut_ptr() -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+}
+
+impl Deref for RTWI {
+    type Target = RegisterBlock;
+    fn deref(&self) -> &RegisterBlock {
+        unsafe { &*self.as_ptr() }
+    }
+}
+
+impl DerefMut for RTWI {
+    fn deref_mut(&mut self) -> &mut RegisterBlock {
+        unsafe { &mut *self.as_mut_ptr() }
+    }
+}
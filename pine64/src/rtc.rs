@@ -0,0 +1,280 @@
+//! RTC
+//!
+//! Size: 1K
+
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use static_assertions::const_assert_eq;
+
+pub const PADDR: usize = 0x01F0_0000;
+
+register! {
+    LoscControl,
+    u32,
+    RW,
+    Fields [
+        ClockSrcSel WIDTH(U1) OFFSET(U0) [
+            InternalOsc = U0,
+            ExternalOsc32K = U1
+        ],
+        ExtLoscGsm WIDTH(U2) OFFSET(U2),
+        ExtLoscEnable WIDTH(U1) OFFSET(U4),
+        // Busy while a date, time or alarm write is in progress
+        DateAccess WIDTH(U1) OFFSET(U7),
+        TimeAccess WIDTH(U1) OFFSET(U8),
+        AlarmAccess WIDTH(U1) OFFSET(U9),
+        AutoSwitchEnable WIDTH(U1) OFFSET(U14),
+        AutoSwitchBypass WIDTH(U1) OFFSET(U15),
+        // Must be 0x16AA for a write to take effect
+        Key WIDTH(U16) OFFSET(U16),
+    ]
+}
+
+register! {
+    LoscAutoSwitchStatus,
+    u32,
+    RW,
+    Fields [
+        ClockSrcStatus WIDTH(U1) OFFSET(U0) [
+            InternalOsc = U0,
+            ExternalOsc32K = U1
+        ],
+        // The external oscillator stopped, write 1 to clear
+        AutoSwitchPending WIDTH(U1) OFFSET(U1),
+    ]
+}
+
+register! {
+    IntOscPrescaler,
+    u32,
+    RW,
+    Fields [
+        // Divides by N + 1
+        Prescaler WIDTH(U5) OFFSET(U0),
+    ]
+}
+
+register! {
+    Date,
+    u32,
+    RW,
+    Fields [
+        Day WIDTH(U5) OFFSET(U0),
+        Month WIDTH(U4) OFFSET(U8),
+        // Years since 1970
+        Year WIDTH(U6) OFFSET(U16),
+        LeapYear WIDTH(U1) OFFSET(U22),
+    ]
+}
+
+register! {
+    Time,
+    u32,
+    RW,
+    Fields [
+        Second WIDTH(U6) OFFSET(U0),
+        Minute WIDTH(U6) OFFSET(U8),
+        Hour WIDTH(U5) OFFSET(U16),
+    ]
+}
+
+register! {
+    /// Seconds until alarm 0 fires
+    Alarm0Counter,
+    u32,
+    RW,
+    Fields [
+        Counter WIDTH(U32) OFFSET(U0),
+    ]
+}
+
+register! {
+    Alarm0CurrentValue,
+    u32,
+    RO,
+    Fields [
+        Counter WIDTH(U32) OFFSET(U0),
+    ]
+}
+
+register! {
+    Alarm0Enable,
+    u32,
+    RW,
+    Fields [
+        Enable WIDTH(U1) OFFSET(U0),
+    ]
+}
+
+register! {
+    AlarmIrqEnable,
+    u32,
+    RW,
+    Fields [
+        Enable WIDTH(U1) OFFSET(U0),
+    ]
+}
+
+register! {
+    AlarmIrqStatus,
+    u32,
+    RW,
+    Fields [
+        Pending WIDTH(U1) OFFSET(U0),
+    ]
+}
+
+register! {
+    Alarm1Time,
+    u32,
+    RW,
+    Fields [
+        Second WIDTH(U6) OFFSET(U0),
+        Minute WIDTH(U6) OFFSET(U8),
+        Hour WIDTH(U5) OFFSET(U16),
+    ]
+}
+
+register! {
+    Alarm1Enable,
+    u32,
+    RW,
+    Fields [
+        // One bit per weekday, Monday first
+        Weekdays WIDTH(U7) OFFSET(U0),
+    ]
+}
+
+register! {
+    AlarmConfig,
+    u32,
+    RW,
+    Fields [
+        // Drive the NMI line, used to wake the PMIC
+        Wakeup WIDTH(U1) OFFSET(U0),
+    ]
+}
+
+register! {
+    LoscOutputGating,
+    u32,
+    RW,
+    Fields [
+        Enable WIDTH(U1) OFFSET(U0),
+    ]
+}
+
+register! {
+    /// Battery backed, preserved while VDD_RTC is up
+    GeneralPurpose,
+    u32,
+    RW,
+    Fields [
+        Data WIDTH(U32) OFFSET(U0),
+    ]
+}
+
+register! {
+    GplHoldOutput,
+    u32,
+    RW,
+    Fields [
+        Hold WIDTH(U13) OFFSET(U0),
+    ]
+}
+
+register! {
+    VddRtc,
+    u32,
+    RW,
+    Fields [
+        Voltage WIDTH(U3) OFFSET(U0),
+    ]
+}
+
+register! {
+    IcCharacteristic,
+    u32,
+    RW,
+    Fields [
+        Id WIDTH(U16) OFFSET(U0),
+        Key WIDTH(U16) OFFSET(U16),
+    ]
+}
+
+const_assert_eq!(core::mem::size_of::<RegisterBlock>(), 0x1F4);
+
+#[repr(C)]
+pub struct RegisterBlock {
+    pub losc_ctrl: LoscControl::Register,                  // 0x000
+    pub losc_auto_swt_sta: LoscAutoSwitchStatus::Register, // 0x004
+    pub intosc_prescaler: IntOscPrescaler::Register,       // 0x008
+    __reserved_0: u32,                                     // 0x00C
+    pub date: Date::Register,                              // 0x010
+    pub time: Time::Register,                              // 0x014
+    __reserved_1: [u32; 2],                                // 0x018
+    pub alarm0_counter: Alarm0Counter::Register,           // 0x020
+    pub alarm0_cur_val: Alarm0CurrentValue::Register,      // 0x024
+    pub alarm0_enable: Alarm0Enable::Register,             // 0x028
+    pub alarm0_irq_enable: AlarmIrqEnable::Register,       // 0x02C
+    pub alarm0_irq_status: AlarmIrqStatus::Register,       // 0x030
+    __reserved_2: [u32; 3],                                // 0x034
+    pub alarm1_time: Alarm1Time::Register,                 // 0x040
+    pub alarm1_enable: Alarm1Enable::Register,             // 0x044
+    pub alarm1_irq_enable: AlarmIrqEnable::Register,       // 0x048
+    pub alarm1_irq_status: AlarmIrqStatus::Register,       // 0x04C
+    pub alarm_config: AlarmConfig::Register,               // 0x050
+    __reserved_3: [u32; 3],                                // 0x054
+    pub losc_out_gating: LoscOutputGating::Register,       // 0x060
+    __reserved_4: [u32; 39],                               // 0x064
+    pub gp_data: [GeneralPurpose::Register; 8],            // 0x100
+    __reserved_5: [u32; 24],                               // 0x120
+    pub gpl_hold_output: GplHoldOutput::Register,          // 0x180
+    __reserved_6: [u32; 3],                                // 0x184
+    pub vdd_rtc: VddRtc::Register,                         // 0x190
+    __reserved_7: [u32; 23],                               // 0x194
+    pub ic_chara: IcCharacteristic::Register,              // 0x1F0
+}
+
+pub struct RTC {
+    _marker: PhantomData<*const ()>,
+}
+
+unsafe impl Send for RTC {}
+
+impl RTC {
+    pub unsafe fn from_paddr() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn as_ptr(&self) -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub const unsafe fn ptr() -> *const RegisterBlock {
+        PADDR as *const _
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+
+    pub const unsafe fn mut_ptr() -> *mut RegisterBlock {
+        PADDR as *mut _
+    }
+}
+
+impl Deref for RTC {
+    type Target = RegisterBlock;
+    fn deref(&self) -> &RegisterBlock {
+        unsafe { &*self.as_ptr() }
+    }
+}
+
+impl DerefMut for RTC {
+    fn deref_mut(&mut self) -> &mut RegisterBlock {
+        unsafe { &mut *self.as_mut_ptr() }
+    }
+}
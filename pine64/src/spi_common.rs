@@ -0,0 +1,223 @@
+//! Serial peripheral interface (SPI)
+//!
+//! 64 byte TX and RX FIFOs
+
+use static_assertions::const_assert_eq;
+
+register! {
+    GlobalControl,
+    u32,
+    RW,
+    Fields [
+        Enable WIDTH(U1) OFFSET(U0),
+        Mode WIDTH(U1) OFFSET(U1) [
+            Slave = U0,
+            Master = U1
+        ]
+        TransmitPause WIDTH(U1) OFFSET(U7),
+        SoftReset WIDTH(U1) OFFSET(U31),
+    ]
+}
+
+register! {
+    TransferControl,
+    u32,
+    RW,
+    Fields [
+        Cpha WIDTH(U1) OFFSET(U0),
+        Cpol WIDTH(U1) OFFSET(U1),
+        ChipSelectPolarity WIDTH(U1) OFFSET(U2) [
+            ActiveHigh = U0,
+            ActiveLow = U1
+        ]
+        ChipSelectControl WIDTH(U1) OFFSET(U3),
+        ChipSelect WIDTH(U2) OFFSET(U4) [
+            Cs0 = U0,
+            Cs1 = U1,
+            Cs2 = U2,
+            Cs3 = U3
+        ]
+        ChipSelectOwner WIDTH(U1) OFFSET(U6) [
+            Controller = U0,
+            Software = U1
+        ]
+        ChipSelectLevel WIDTH(U1) OFFSET(U7),
+        DiscardHashBurst WIDTH(U1) OFFSET(U8),
+        DummyBurstType WIDTH(U1) OFFSET(U9),
+        RapidsMode WIDTH(U1) OFFSET(U10),
+        SampleDelay WIDTH(U1) OFFSET(U11),
+        FirstBit WIDTH(U1) OFFSET(U12) [
+            Msb = U0,
+            Lsb = U1
+        ]
+        SampleDelayMode WIDTH(U1) OFFSET(U13),
+        Exchange WIDTH(U1) OFFSET(U31),
+    ]
+}
+
+register! {
+    IntControl,
+    u32,
+    RW,
+    Fields [
+        RxReady WIDTH(U1) OFFSET(U0),
+        RxEmpty WIDTH(U1) OFFSET(U1),
+        RxFull WIDTH(U1) OFFSET(U2),
+        TxEmptyRequest WIDTH(U1) OFFSET(U4),
+        TxEmpty WIDTH(U1) OFFSET(U5),
+        TxFull WIDTH(U1) OFFSET(U6),
+        RxOverflow WIDTH(U1) OFFSET(U8),
+        RxUnderrun WIDTH(U1) OFFSET(U9),
+        TxOverflow WIDTH(U1) OFFSET(U10),
+        TxUnderrun WIDTH(U1) OFFSET(U11),
+        TransferComplete WIDTH(U1) OFFSET(U12),
+        ChipSelectInvalid WIDTH(U1) OFFSET(U13),
+    ]
+}
+
+register! {
+    IntStatus,
+    u32,
+    RW,
+    Fields [
+        RxReady WIDTH(U1) OFFSET(U0),
+        RxEmpty WIDTH(U1) OFFSET(U1),
+        RxFull WIDTH(U1) OFFSET(U2),
+        TxEmptyRequest WIDTH(U1) OFFSET(U4),
+        TxEmpty WIDTH(U1) OFFSET(U5),
+        TxFull WIDTH(U1) OFFSET(U6),
+        RxOverflow WIDTH(U1) OFFSET(U8),
+        RxUnderrun WIDTH(U1) OFFSET(U9),
+        TxOverflow WIDTH(U1) OFFSET(U10),
+        TxUnderrun WIDTH(U1) OFFSET(U11),
+        TransferComplete WIDTH(U1) OFFSET(U12),
+        ChipSelectInvalid WIDTH(U1) OFFSET(U13),
+    ]
+}
+
+register! {
+    FifoControl,
+    u32,
+    RW,
+    Fields [
+        RxTriggerLevel WIDTH(U8) OFFSET(U0),
+        RxDrqEnable WIDTH(U1) OFFSET(U8),
+        RxTestEnable WIDTH(U1) OFFSET(U14),
+        RxReset WIDTH(U1) OFFSET(U15),
+        TxTriggerLevel WIDTH(U8) OFFSET(U16),
+        TxDrqEnable WIDTH(U1) OFFSET(U24),
+        TxTestEnable WIDTH(U1) OFFSET(U30),
+        TxReset WIDTH(U1) OFFSET(U31),
+    ]
+}
+
+register! {
+    FifoStatus,
+    u32,
+    RO,
+    Fields [
+        RxCount WIDTH(U8) OFFSET(U0),
+        RxReadBufferCount WIDTH(U3) OFFSET(U12),
+        RxReadBufferWrite WIDTH(U1) OFFSET(U15),
+        TxCount WIDTH(U8) OFFSET(U16),
+        TxWriteBufferCount WIDTH(U3) OFFSET(U28),
+        TxWriteBufferWrite WIDTH(U1) OFFSET(U31),
+    ]
+}
+
+register! {
+    WaitClock,
+    u32,
+    RW,
+    Fields [
+        WaitCount WIDTH(U16) OFFSET(U0),
+        SwitchWait WIDTH(U4) OFFSET(U16),
+    ]
+}
+
+register! {
+    ClockControl,
+    u32,
+    RW,
+    Fields [
+        // SCK = clk / (2 * (CDR2 + 1))
+        Cdr2 WIDTH(U8) OFFSET(U0),
+        // SCK = clk / 2^(CDR1 + 1)
+        Cdr1 WIDTH(U4) OFFSET(U8),
+        DivideRateSelect WIDTH(U1) OFFSET(U12) [
+            Cdr1 = U0,
+            Cdr2 = U1
+        ]
+    ]
+}
+
+register! {
+    BurstCounter,
+    u32,
+    RW,
+    Fields [
+        Count WIDTH(U24) OFFSET(U0),
+    ]
+}
+
+register! {
+    TransmitCounter,
+    u32,
+    RW,
+    Fields [
+        Count WIDTH(U24) OFFSET(U0),
+    ]
+}
+
+register! {
+    BurstControl,
+    u32,
+    RW,
+    Fields [
+        SingleModeTransmitCount WIDTH(U24) OFFSET(U0),
+        DualModeRx WIDTH(U1) OFFSET(U28),
+    ]
+}
+
+register! {
+    TxData,
+    u8,
+    WO,
+    Fields [
+        Data WIDTH(U8) OFFSET(U0),
+    ]
+}
+
+register! {
+    RxData,
+    u8,
+    RO,
+    Fields [
+        Data WIDTH(U8) OFFSET(U0),
+    ]
+}
+
+const_assert_eq!(core::mem::size_of::<RegisterBlock>(), 0x304);
+
+#[repr(C)]
+pub struct RegisterBlock {
+    __reserved_0: u32,                  // 0x00
+    pub gcr: GlobalControl::Register,   // 0x04
+    pub tcr: TransferControl::Register, // 0x08
+    __reserved_1: u32,                  // 0x0C
+    pub ier: IntControl::Register,      // 0x10
+    pub isr: IntStatus::Register,       // 0x14
+    pub fcr: FifoControl::Register,     // 0x18
+    pub fsr: FifoStatus::Register,      // 0x1C
+    pub wcr: WaitClock::Register,       // 0x20
+    pub ccr: ClockControl::Register,    // 0x24
+    __reserved_2: [u32; 2],             // 0x28
+    pub mbc: BurstCounter::Register,    // 0x30
+    pub mtc: TransmitCounter::Register, // 0x34
+    pub bcc: BurstControl::Register,    // 0x38
+    __reserved_3: [u32; 113],           // 0x3C
+    pub txd: TxData::Register,          // 0x200
+    __reserved_4: [u8; 255],            // 0x201
+    pub rxd: RxData::Register,          // 0x300
+    __reserved_5: [u8; 3],              // 0x301
+}
@@ -24,7 +24,9 @@ register! {
     u32,
     RW,
     Fields [
-        Bits WIDTH(U32) OFFSET(U0),
+        // Write 0 to clear
+        Tcon0VblankFlag WIDTH(U1) OFFSET(U15),
+        Tcon0VblankIrqEnable WIDTH(U1) OFFSET(U31),
     ]
 }
 
@@ -27,7 +27,9 @@ register! {
     u32,
     RW,
     Fields [
-        Bits WIDTH(U32) OFFSET(U0),
+        // Write 0 to clear
+        Tcon1VblankFlag WIDTH(U1) OFFSET(U14),
+        Tcon1VblankIrqEnable WIDTH(U1) OFFSET(U30),
     ]
 }
 
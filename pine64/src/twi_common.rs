@@ -0,0 +1,135 @@
+//! Two wire interface (TWI)
+//!
+//! Marvell mv64xxx style I2C controller
+
+use static_assertions::const_assert_eq;
+
+register! {
+    SlaveAddress,
+    u32,
+    RW,
+    Fields [
+        GeneralCall WIDTH(U1) OFFSET(U0),
+        Address WIDTH(U7) OFFSET(U1),
+    ]
+}
+
+register! {
+    ExtendedSlaveAddress,
+    u32,
+    RW,
+    Fields [
+        Address WIDTH(U8) OFFSET(U0),
+    ]
+}
+
+register! {
+    Data,
+    u32,
+    RW,
+    Fields [
+        Data WIDTH(U8) OFFSET(U0),
+    ]
+}
+
+register! {
+    Control,
+    u32,
+    RW,
+    Fields [
+        AssertAck WIDTH(U1) OFFSET(U2),
+        // Write 1 to clear
+        IntFlag WIDTH(U1) OFFSET(U3),
+        MasterStop WIDTH(U1) OFFSET(U4),
+        MasterStart WIDTH(U1) OFFSET(U5),
+        BusEnable WIDTH(U1) OFFSET(U6),
+        IntEnable WIDTH(U1) OFFSET(U7),
+    ]
+}
+
+register! {
+    Status,
+    u32,
+    RO,
+    Fields [
+        Status WIDTH(U8) OFFSET(U0) [
+            BusError = U0,
+            StartTransmitted = U8,
+            RepeatedStartTransmitted = U16,
+            AddrWriteAck = U24,
+            AddrWriteNack = U32,
+            DataTransmittedAck = U40,
+            DataTransmittedNack = U48,
+            ArbitrationLost = U56,
+            AddrReadAck = U64,
+            AddrReadNack = U72,
+            DataReceivedAck = U80,
+            DataReceivedNack = U88,
+            Idle = U248
+        ]
+    ]
+}
+
+register! {
+    ClockControl,
+    u32,
+    RW,
+    Fields [
+        // F0 = Fin / 2^N
+        N WIDTH(U3) OFFSET(U0),
+        // Fscl = F0 / (10 * (M + 1))
+        M WIDTH(U4) OFFSET(U3),
+    ]
+}
+
+register! {
+    SoftReset,
+    u32,
+    RW,
+    Fields [
+        Reset WIDTH(U1) OFFSET(U0),
+    ]
+}
+
+register! {
+    EnhancedFeature,
+    u32,
+    RW,
+    Fields [
+        DataByteNumber WIDTH(U2) OFFSET(U0) [
+            NoData = U0,
+            OneByte = U1,
+            TwoBytes = U2,
+            ThreeBytes = U3
+        ]
+    ]
+}
+
+register! {
+    LineControl,
+    u32,
+    RW,
+    Fields [
+        SdaControlEnable WIDTH(U1) OFFSET(U0),
+        SdaControl WIDTH(U1) OFFSET(U1),
+        SclControlEnable WIDTH(U1) OFFSET(U2),
+        SclControl WIDTH(U1) OFFSET(U3),
+        SdaState WIDTH(U1) OFFSET(U4),
+        SclState WIDTH(U1) OFFSET(U5),
+    ]
+}
+
+const_assert_eq!(core::mem::size_of::<RegisterBlock>(), 0x24);
+
+#[repr(C)]
+pub struct RegisterBlock {
+    pub addr: SlaveAddress::Register,          // 0x00
+    pub xaddr: ExtendedSlaveAddress::Register, // 0x04
+    pub data: Data::Register,                  // 0x08
+    pub cntr: Control::Register,               // 0x0C
+    pub stat: Status::Register,                // 0x10
+    pub ccr: ClockControl::Register,           // 0x14
+    pub srst: SoftReset::Register,             // 0x18
+    pub efr: EnhancedFeature::Register,        // 0x1C
+    pub lcr: LineControl::Register,            // 0x20
+}
@@ -15,7 +15,7 @@ use core::ops::{Deref, DerefMut};
 
 pub const PADDR: usize = 0x01C2_8000;
 
-pub struct UART0<RxTx> {
+pub struct UART0<RxTx = NotConfigured> {
     _marker: PhantomData<RxTx>,
 }
 
@@ -17,7 +17,7 @@ use core::ops::{Deref, DerefMut};
 
 pub const PADDR: usize = 0x01C2_8400;
 
-pub struct UART1<RxTx> {
+pub struct UART1<RxTx = NotConfigured> {
     _marker: PhantomData<RxTx>,
 }
 
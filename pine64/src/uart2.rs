@@ -17,7 +17,7 @@ use core::ops::{Deref, DerefMut};
 
 pub const PADDR: usize = 0x01C2_8800;
 
-pub struct UART2<RxTx> {
+pub struct UART2<RxTx = NotConfigured> {
     _marker: PhantomData<RxTx>,
 }
 
@@ -17,7 +17,7 @@ use core::ops::{Deref, DerefMut};
 
 pub const PADDR: usize = 0x01C2_8C00;
 
-pub struct UART3<RxTx> {
+pub struct UART3<RxTx = NotConfigured> {
     _marker: PhantomData<RxTx>,
 }
 
@@ -17,7 +17,7 @@ use core::ops::{Deref, DerefMut};
 
 pub const PADDR: usize = 0x01C2_9000;
 
-pub struct UART4<RxTx> {
+pub struct UART4<RxTx = NotConfigured> {
     _marker: PhantomData<RxTx>,
 }
 